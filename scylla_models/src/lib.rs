@@ -2,17 +2,35 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::fmt::{Debug, Display, Formatter};
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct AddTaskModel {
     pub rn: String,
     pub spec: Value,
     pub priority: i8,
     pub queue: String,
+    pub not_before: Option<DateTime<Utc>>,
+    pub max_attempts: Option<i32>,
+    pub depends_on: Vec<String>,
+    pub on_dependency_failure: DependencyFailurePolicy,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub labels: BTreeMap<String, String>,
+    pub supersession_key: Option<String>,
+    pub concurrency_key: Option<String>,
 }
 
+/// Attributes of a resubmitted task which differ from the task it is resubmitted from
 #[derive(Debug, Default)]
+pub struct ResubmitOverrides {
+    pub spec: Option<Value>,
+    pub queue: Option<String>,
+    pub priority: Option<i8>,
+    pub max_attempts: Option<i32>,
+}
+
+#[derive(Debug, Default, Clone)]
 pub struct UpdateTaskModel {
     pub rn: String,
     pub operation: UpdateOperation,
@@ -22,6 +40,12 @@ pub struct UpdateTaskModel {
     pub progress: Option<f32>,
     pub task_timeout_in_secs: Option<i64>,
     pub metrics: Option<Value>,
+    pub lease_epoch: Option<i64>,
+    pub priority: Option<i8>,
+    pub spec: Option<Value>,
+    pub result: Option<Value>,
+    pub actor: Option<String>,
+    pub reason: Option<String>,
 }
 
 #[derive(Debug)]
@@ -30,6 +54,7 @@ pub struct GetTaskModel {
     pub queue: Option<String>,
     pub worker: Option<String>,
     pub status: Option<TaskStatus>,
+    pub labels: Vec<LabelSelector>,
 }
 impl Default for GetTaskModel {
     fn default() -> Self {
@@ -38,10 +63,20 @@ impl Default for GetTaskModel {
             queue: None,
             worker: None,
             status: None,
+            labels: Vec::new(),
         }
     }
 }
 
+/// Matches tasks by one of their labels. All selectors of a query need to match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LabelSelector {
+    /// label `key` has exactly the value
+    Equals(String, String),
+    /// label `key` has any of the values
+    In(String, Vec<String>),
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Default)]
 #[serde(rename_all = "camelCase")]
 pub enum UpdateOperation {
@@ -51,6 +86,8 @@ pub enum UpdateOperation {
     Status,
     Lease,
     Reset,
+    Reprioritise,
+    AmendSpec,
 }
 impl Display for UpdateOperation {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -70,6 +107,13 @@ pub enum TaskStatus {
     Aborted,
     #[serde(rename = "cancelled")]
     Cancelled,
+    #[serde(rename = "blocked")]
+    Blocked,
+    #[serde(rename = "expired")]
+    Expired,
+    /// Cancellation of a running task has been requested, it is cancelled once the worker acknowledges it
+    #[serde(rename = "cancelling")]
+    Cancelling,
 }
 
 pub trait TaskStatusExt {
@@ -77,12 +121,16 @@ pub trait TaskStatusExt {
 }
 
 // Even though Ready can be moved to Running stage. That is handled through Lease Operation and not statusOperation
+// Similarly Blocked is moved to Ready, Aborted or Cancelled by monitor once its dependencies are resolved
+// and Ready or Blocked is moved to Expired by monitor once expiry time has passed.
+// Cancelling is moved to Cancelled by its worker, or by monitor once the grace period has passed
 impl TaskStatusExt for TaskStatus {
     fn allowed_transitions(&self) -> &[TaskStatus] {
         match self {
-            TaskStatus::Ready => &[TaskStatus::Cancelled],
-            TaskStatus::Running => &[TaskStatus::Completed, TaskStatus::Cancelled, TaskStatus::Aborted],
-            TaskStatus::Completed | TaskStatus::Aborted | TaskStatus::Cancelled => &[],
+            TaskStatus::Ready | TaskStatus::Blocked => &[TaskStatus::Cancelled],
+            TaskStatus::Running => &[TaskStatus::Completed, TaskStatus::Cancelling, TaskStatus::Aborted],
+            TaskStatus::Cancelling => &[TaskStatus::Cancelled],
+            TaskStatus::Completed | TaskStatus::Aborted | TaskStatus::Cancelled | TaskStatus::Expired => &[],
         }
     }
 }
@@ -92,12 +140,26 @@ impl Display for TaskStatus {
         write!(f, "{self:?}")
     }
 }
+/// Outcome for a blocked task when one of the tasks it depends on is aborted or cancelled
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum DependencyFailurePolicy {
+    #[default]
+    Abort,
+    Cancel,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct TaskHistory {
     pub typ: TaskHistoryType,
+    /// Owner of the task at the time of the entry, empty if it was not leased
+    #[serde(default)]
     pub worker: String,
     pub progress: Option<f32>,
     pub time: DateTime<Utc>,
+    /// Who caused the entry, e.g. the worker completing the task, the client cancelling it or the monitor
+    pub actor: Option<String>,
+    pub reason: Option<String>,
 }
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum TaskHistoryType {
@@ -107,6 +169,74 @@ pub enum TaskHistoryType {
     Timeout,
     #[serde(rename = "TaskYield")]
     Yield,
+    #[serde(rename = "TaskReprioritisation")]
+    Reprioritisation,
+    #[serde(rename = "TaskSpecAmendment")]
+    SpecAmendment,
+    #[serde(rename = "TaskCompletion")]
+    Completion,
+    #[serde(rename = "TaskAbortion")]
+    Abortion,
+    #[serde(rename = "TaskCancellation")]
+    Cancellation,
+    #[serde(rename = "TaskExpiry")]
+    Expiry,
+    #[serde(rename = "TaskCancellationRequest")]
+    CancellationRequest,
+}
+
+/// Marker of a requested cancellation of a running task, surfaced to the worker on its heartbeats.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CancelRequest {
+    pub requester: Option<String>,
+    pub reason: Option<String>,
+    pub time: DateTime<Utc>,
+}
+
+/// Outcome of a heartbeat, telling the worker whether it is to carry on with the task.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "outcome", rename_all = "camelCase")]
+pub enum HeartbeatOutcome {
+    /// Lease of the worker has been extended
+    Extended {
+        task: Task,
+    },
+    /// Task has been reset, leased again, or terminated otherwise than by the worker since. `new_owner` holds the current
+    /// lease if any, `status` is the status of the task, e.g. `aborted` once it has run out of attempts.
+    LeaseLost {
+        #[serde(rename = "newOwner")]
+        new_owner: Option<String>,
+        status: TaskStatus,
+    },
+    /// Cancellation of the task has been requested, or it has been cancelled already. A cancelling task still needs
+    /// to be acknowledged by the worker.
+    Cancelled {
+        task: Task,
+    },
+    /// Worker is being drained, its lease has been extended for it to wind down and yield the task
+    YieldRequested {
+        task: Task,
+    },
+    /// Task has been completed already by the worker
+    Completed,
+    NotFound,
+}
+
+/// Heartbeat of one of the tasks held by a worker, sent along with the heartbeats of its other tasks.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskHeartbeat {
+    pub rn: String,
+    pub lease_epoch: i64,
+    pub progress: Option<f32>,
+}
+
+/// Outcome of the heartbeat of task `rn`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TaskHeartbeatOutcome {
+    pub rn: String,
+    #[serde(flatten)]
+    pub outcome: HeartbeatOutcome,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -132,6 +262,38 @@ pub struct Task {
     pub errors: Vec<TaskError>,
     pub history: Vec<TaskHistory>,
     pub metrics: Option<Value>,
+    /// Output of the task, set on completion.
+    pub result: Option<Value>,
+    pub not_before: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub attempts: i32,
+    pub max_attempts: Option<i32>,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub on_dependency_failure: DependencyFailurePolicy,
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// Identifies the tasks of a queue which are superseded by a newer submission.
+    pub supersession_key: Option<String>,
+    /// Tasks of a queue sharing the key are limited by `max_running_tasks_per_key` of the queue.
+    pub concurrency_key: Option<String>,
+    /// Task this task has been resubmitted from.
+    pub previous_rn: Option<String>,
+    /// Task this task has been resubmitted as.
+    pub resubmitted_as: Option<String>,
+    /// Set when cancellation of the task is requested while it is running.
+    pub cancel_requested: Option<CancelRequest>,
+    /// Incremented on every update. Update of a task is only applied if it was read at the current version.
+    #[serde(default)]
+    pub version: i64,
+    /// Fencing token of the current lease. Incremented every time the task is leased.
+    #[serde(default)]
+    pub lease_epoch: i64,
+    /// Priority after aging policy of the queue is applied. Only populated in query results.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effective_priority: Option<i32>,
 }
 impl Default for Task {
     fn default() -> Self {
@@ -149,6 +311,22 @@ impl Default for Task {
             errors: Vec::default(),
             history: Vec::default(),
             metrics: None,
+            result: None,
+            not_before: None,
+            attempts: 0,
+            max_attempts: None,
+            depends_on: Vec::default(),
+            on_dependency_failure: DependencyFailurePolicy::default(),
+            expires_at: None,
+            labels: BTreeMap::default(),
+            supersession_key: None,
+            concurrency_key: None,
+            previous_rn: None,
+            resubmitted_as: None,
+            cancel_requested: None,
+            version: 0,
+            lease_epoch: 0,
+            effective_priority: None,
         }
     }
 }
@@ -160,6 +338,142 @@ pub struct TaskBatch {
     pub failed_to_insert: Vec<Task>,
 }
 
+/// Per queue defaults. These are applied to tasks of the queue which do not specify their own values.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Queue {
+    pub name: String,
+    pub max_attempts: Option<i32>,
+    pub priority_aging: Option<PriorityAging>,
+    /// Lease timeout of leases and heartbeats which do not pass their own
+    pub lease_timeout_in_secs: Option<i64>,
+    /// Terminated tasks are deleted by monitor after this period instead of its global retention period
+    pub retention_period_in_secs: Option<i64>,
+    /// Tasks with a higher priority are rejected
+    pub max_priority: Option<i8>,
+    /// Tasks of the queue are not leased while this many of them are running
+    pub max_running_tasks: Option<i32>,
+    /// Tasks with a concurrency key are not leased while this many tasks of the queue with the same key are running
+    pub max_running_tasks_per_key: Option<i32>,
+    /// Tasks of the queue are started at the rate of the limit, shared by all workers
+    pub rate_limit: Option<RateLimit>,
+    pub description: Option<String>,
+}
+
+/// Operational mode of a queue, set during incidents without touching the tasks of the queue.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum QueueMode {
+    #[default]
+    Active,
+    /// Tasks are accepted but not leased
+    Paused,
+    /// Tasks are leased but no new tasks are accepted
+    Draining,
+}
+
+/// Mode of a queue. Queues without a stored state are active.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueState {
+    pub name: String,
+    pub mode: QueueMode,
+}
+
+/// Ready tasks gain one point of priority for every `seconds_per_point` they wait, up to `max_boost` points.
+/// Prevents starvation of low priority tasks by a steady stream of high priority ones.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityAging {
+    pub seconds_per_point: i32,
+    pub max_boost: i32,
+}
+
+/// Token bucket of `max_starts` tokens, refilled evenly over `interval_in_secs`. Every task leased takes a token,
+/// so that no more than `max_starts` tasks of the queue are started within any interval.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimit {
+    pub max_starts: i32,
+    pub interval_in_secs: i32,
+}
+
+/// Remaining budget of a rate limited queue. Workers finding no token left can back off until `available_at`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitBudget {
+    pub name: String,
+    /// Number of tasks which can be started right away
+    pub remaining: i32,
+    /// Time at which the next token is available
+    pub available_at: DateTime<Utc>,
+}
+
+/// Recurring task definition. Monitor adds a task to `queue` with rn `{name}:{fire time}` whenever `cron` is due in `timezone`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+    pub name: String,
+    pub cron: String,
+    pub timezone: String,
+    pub queue: String,
+    pub priority: i8,
+    pub spec: Value,
+    pub created: DateTime<Utc>,
+    pub last_fired: Option<DateTime<Utc>>,
+}
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            name: String::default(),
+            cron: String::default(),
+            timezone: "UTC".to_string(),
+            queue: String::default(),
+            priority: 0,
+            spec: serde_json::Value::default(),
+            created: Utc::now(),
+            last_fired: None,
+        }
+    }
+}
+
+/// Hostname and version a worker process registers with.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerMetadata {
+    pub hostname: String,
+    pub version: String,
+}
+
+/// Registered worker process. `id` is issued from a database sequence on registration and is the owner of the tasks
+/// the worker leases. It does not survive the process, a restarted process registers again.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Worker {
+    pub id: String,
+    pub hostname: String,
+    pub version: String,
+    pub registered: DateTime<Utc>,
+    /// Last time the worker signalled that it is alive
+    pub heartbeat: DateTime<Utc>,
+    /// Set by operators to drain the worker, which is then leased no more tasks and told to yield the ones it holds
+    #[serde(default)]
+    pub draining: bool,
+}
+impl Default for Worker {
+    fn default() -> Self {
+        let now = Utc::now();
+        Self {
+            id: String::default(),
+            hostname: String::default(),
+            version: String::default(),
+            registered: now,
+            heartbeat: now,
+            draining: false,
+        }
+    }
+}
+
 // $coverage:ignore-start
 #[cfg(test)]
 mod tests {
@@ -173,10 +487,18 @@ mod tests {
             queue: String::from("new model"),
             rn: String::from("1.2.3"),
             spec: serde_json::Value::default(),
+            not_before: None,
+            max_attempts: Some(3),
+            depends_on: vec![String::from("1.2.2")],
+            on_dependency_failure: DependencyFailurePolicy::Cancel,
+            expires_at: None,
+            labels: BTreeMap::new(),
+            supersession_key: None,
+            concurrency_key: None,
         };
         assert_eq!(
             format!("{:?}", atm),
-            "AddTaskModel { rn: \"1.2.3\", spec: Null, priority: 2, queue: \"new model\" }"
+            "AddTaskModel { rn: \"1.2.3\", spec: Null, priority: 2, queue: \"new model\", not_before: None, max_attempts: Some(3), depends_on: [\"1.2.2\"], on_dependency_failure: Cancel, expires_at: None, labels: {}, supersession_key: None, concurrency_key: None }"
         );
     }
     #[test]
@@ -190,10 +512,16 @@ mod tests {
             worker: None,
             task_timeout_in_secs: None,
             metrics: None,
+            lease_epoch: None,
+            priority: None,
+            spec: None,
+            result: None,
+            actor: None,
+            reason: None,
         };
         assert_eq!(
             format!("{:?}", utm),
-            "UpdateTaskModel { rn: \"1.2.3\", operation: HeartBeat, status: None, error: None, worker: None, progress: None, task_timeout_in_secs: None, metrics: None }"
+            "UpdateTaskModel { rn: \"1.2.3\", operation: HeartBeat, status: None, error: None, worker: None, progress: None, task_timeout_in_secs: None, metrics: None, lease_epoch: None, priority: None, spec: None, result: None, actor: None, reason: None }"
         );
     }
     #[test]
@@ -202,7 +530,7 @@ mod tests {
         //debug trait
         assert_eq!(
             format!("{:?}", gtm),
-            "GetTaskModel { limit: Some(100), queue: None, worker: None, status: None }"
+            "GetTaskModel { limit: Some(100), queue: None, worker: None, status: None, labels: [] }"
         );
         // default
         let gtm_default = GetTaskModel::default();
@@ -223,11 +551,15 @@ mod tests {
         assert_eq!(TaskStatus::Aborted.allowed_transitions(), &[]);
         assert_eq!(TaskStatus::Completed.allowed_transitions(), &[]);
         assert_eq!(TaskStatus::Cancelled.allowed_transitions(), &[]);
+        assert_eq!(TaskStatus::Expired.allowed_transitions(), &[]);
         assert_eq!(TaskStatus::Ready.allowed_transitions(), &[TaskStatus::Cancelled]);
+        assert_eq!(TaskStatus::Blocked.allowed_transitions(), &[TaskStatus::Cancelled]);
         assert_eq!(
             TaskStatus::Running.allowed_transitions(),
-            &[TaskStatus::Completed, TaskStatus::Cancelled, TaskStatus::Aborted]
+            &[TaskStatus::Completed, TaskStatus::Cancelling, TaskStatus::Aborted]
         );
+        assert_eq!(TaskStatus::Cancelling.allowed_transitions(), &[TaskStatus::Cancelled]);
+        assert_eq!(serde_json::to_string(&TaskStatus::Cancelling).unwrap(), "\"cancelling\"");
         // display trait
         assert_eq!(format!("Task Status is {}", TaskStatus::Running), "Task Status is Running");
 
@@ -241,6 +573,18 @@ mod tests {
         assert_eq!(serde_json::from_str::<TaskStatus>("\"aborted\"").unwrap(), TaskStatus::Aborted);
         assert_eq!(serde_json::from_str::<TaskStatus>("\"completed\"").unwrap(), TaskStatus::Completed);
         assert_eq!(serde_json::from_str::<TaskStatus>("\"cancelled\"").unwrap(), TaskStatus::Cancelled);
+        assert_eq!(serde_json::from_str::<TaskStatus>("\"blocked\"").unwrap(), TaskStatus::Blocked);
+        assert_eq!(serde_json::from_str::<TaskStatus>("\"expired\"").unwrap(), TaskStatus::Expired);
+    }
+
+    #[test]
+    fn dependency_failure_policy() {
+        assert_eq!(DependencyFailurePolicy::default(), DependencyFailurePolicy::Abort);
+        assert_eq!(serde_json::to_string(&DependencyFailurePolicy::Cancel).unwrap(), "\"cancel\"");
+        assert_eq!(
+            serde_json::from_str::<DependencyFailurePolicy>("\"abort\"").unwrap(),
+            DependencyFailurePolicy::Abort
+        );
     }
 
     #[test]
@@ -254,11 +598,24 @@ mod tests {
                     progress: None,
                     time: t_now,
                     typ: TaskHistoryType::Assignment,
-                    worker: String::from("worker1")
+                    worker: String::from("worker1"),
+                    actor: None,
+                    reason: None,
                 }
             ),
-            format!("TaskHistory {{ typ: Assignment, worker: \"worker1\", progress: None, time: {:?} }}", t_now)
+            format!(
+                "TaskHistory {{ typ: Assignment, worker: \"worker1\", progress: None, time: {:?}, actor: None, reason: None }}",
+                t_now
+            )
         );
+        let cancellation: TaskHistory = serde_json::from_str(
+            "{\"typ\": \"TaskCancellation\", \"progress\": null, \"time\": \"2021-10-09T12:13:21Z\", \"actor\": \"client\", \"reason\": \"obsolete\"}",
+        )
+        .unwrap();
+        assert_eq!(cancellation.typ, TaskHistoryType::Cancellation);
+        assert_eq!(cancellation.worker, String::new());
+        assert_eq!(cancellation.actor, Some("client".to_string()));
+        assert_eq!(cancellation.reason, Some("obsolete".to_string()));
         assert_eq!(format!("{:?}", TaskHistoryType::Assignment), "Assignment");
         assert_ne!(TaskHistoryType::Assignment, TaskHistoryType::Yield);
         assert_eq!(TaskHistoryType::Assignment.clone(), TaskHistoryType::Assignment);
@@ -281,7 +638,18 @@ mod tests {
             ..Task::default()
         };
         // debug trait
-        assert_eq!(format!("{:?}", t), format!("Task {{ rn: \"\", spec: Null, status: Ready, queue: \"\", progress: 0.0, priority: 0, created: {0:?}, updated: {0:?}, deadline: None, owner: None, errors: [], history: [], metrics: None }}", t_now));
+        assert_eq!(format!("{:?}", t), format!("Task {{ rn: \"\", spec: Null, status: Ready, queue: \"\", progress: 0.0, priority: 0, created: {0:?}, updated: {0:?}, deadline: None, owner: None, errors: [], history: [], metrics: None, result: None, not_before: None, attempts: 0, max_attempts: None, depends_on: [], on_dependency_failure: Abort, expires_at: None, labels: {{}}, supersession_key: None, concurrency_key: None, previous_rn: None, resubmitted_as: None, cancel_requested: None, version: 0, lease_epoch: 0, effective_priority: None }}", t_now));
+        let t_with_effective_priority = Task {
+            effective_priority: Some(5),
+            ..t.clone()
+        };
+        assert!(serde_json::to_string(&t_with_effective_priority)
+            .unwrap()
+            .ends_with(",\"effectivePriority\":5}"));
+        assert_eq!(
+            serde_json::from_str::<Task>(serde_json::to_string(&t_with_effective_priority).unwrap().as_str()).unwrap(),
+            t_with_effective_priority
+        );
         // default()
         let t = Task {
             created: t_now,
@@ -304,10 +672,70 @@ mod tests {
                 errors: Vec::default(),
                 history: Vec::default(),
                 metrics: None,
+                result: None,
+                not_before: None,
+                attempts: 0,
+                max_attempts: None,
+                depends_on: Vec::default(),
+                on_dependency_failure: DependencyFailurePolicy::Abort,
+                expires_at: None,
+                labels: BTreeMap::default(),
+                supersession_key: None,
+                concurrency_key: None,
+                previous_rn: None,
+                resubmitted_as: None,
+                cancel_requested: None,
+                version: 0,
+                lease_epoch: 0,
+                effective_priority: None,
             }
         )
     }
 
+    #[test]
+    fn heartbeat_outcome() {
+        assert_eq!(
+            serde_json::to_string(&HeartbeatOutcome::LeaseLost {
+                new_owner: Some("worker2".to_string()),
+                status: TaskStatus::Running,
+            })
+            .unwrap(),
+            "{\"outcome\":\"leaseLost\",\"newOwner\":\"worker2\",\"status\":\"running\"}"
+        );
+        assert_eq!(serde_json::to_string(&HeartbeatOutcome::NotFound).unwrap(), "{\"outcome\":\"notFound\"}");
+        assert_eq!(serde_json::to_string(&HeartbeatOutcome::Completed).unwrap(), "{\"outcome\":\"completed\"}");
+        let extended = HeartbeatOutcome::Extended { task: Task::default() };
+        let json = serde_json::to_value(&extended).unwrap();
+        assert_eq!(json["outcome"], "extended");
+        assert_eq!(json["task"]["status"], "ready");
+        assert_eq!(serde_json::from_value::<HeartbeatOutcome>(json).unwrap(), extended);
+        let yield_requested = serde_json::to_value(HeartbeatOutcome::YieldRequested { task: Task::default() }).unwrap();
+        assert_eq!(yield_requested["outcome"], "yieldRequested");
+    }
+
+    #[test]
+    fn task_heartbeat_outcome() {
+        let heartbeat: TaskHeartbeat = serde_json::from_str("{\"rn\":\"1\",\"leaseEpoch\":2,\"progress\":0.5}").unwrap();
+        assert_eq!(
+            heartbeat,
+            TaskHeartbeat {
+                rn: "1".to_string(),
+                lease_epoch: 2,
+                progress: Some(0.5),
+            }
+        );
+        let outcome = TaskHeartbeatOutcome {
+            rn: "1".to_string(),
+            outcome: HeartbeatOutcome::LeaseLost {
+                new_owner: None,
+                status: TaskStatus::Aborted,
+            },
+        };
+        let json = serde_json::to_string(&outcome).unwrap();
+        assert_eq!(json, "{\"rn\":\"1\",\"outcome\":\"leaseLost\",\"newOwner\":null,\"status\":\"aborted\"}");
+        assert_eq!(serde_json::from_str::<TaskHeartbeatOutcome>(&json).unwrap(), outcome);
+    }
+
     #[test]
     fn task_batch() {
         let t_now = Utc::now();
@@ -337,10 +765,177 @@ mod tests {
         };
 
         // debug trait
-        assert_eq!(format!("{:?}", tb), format!("TaskBatch {{ inserted: [Task {{ rn: \"123\", spec: Null, status: Ready, queue: \"\", progress: 0.0, priority: 0, created: {0:?}, updated: {0:?}, deadline: None, owner: None, errors: [], history: [], metrics: None }}, Task {{ rn: \"789\", spec: Null, status: Ready, queue: \"\", progress: 0.0, priority: 0, created: {0:?}, updated: {0:?}, deadline: None, owner: None, errors: [], history: [], metrics: None }}], failed_to_insert: [Task {{ rn: \"456\", spec: Null, status: Ready, queue: \"\", progress: 0.0, priority: 0, created: {0:?}, updated: {0:?}, deadline: None, owner: None, errors: [], history: [], metrics: None }}] }}", t_now));
+        assert_eq!(format!("{:?}", tb), format!("TaskBatch {{ inserted: [Task {{ rn: \"123\", spec: Null, status: Ready, queue: \"\", progress: 0.0, priority: 0, created: {0:?}, updated: {0:?}, deadline: None, owner: None, errors: [], history: [], metrics: None, result: None, not_before: None, attempts: 0, max_attempts: None, depends_on: [], on_dependency_failure: Abort, expires_at: None, labels: {{}}, supersession_key: None, concurrency_key: None, previous_rn: None, resubmitted_as: None, cancel_requested: None, version: 0, lease_epoch: 0, effective_priority: None }}, Task {{ rn: \"789\", spec: Null, status: Ready, queue: \"\", progress: 0.0, priority: 0, created: {0:?}, updated: {0:?}, deadline: None, owner: None, errors: [], history: [], metrics: None, result: None, not_before: None, attempts: 0, max_attempts: None, depends_on: [], on_dependency_failure: Abort, expires_at: None, labels: {{}}, supersession_key: None, concurrency_key: None, previous_rn: None, resubmitted_as: None, cancel_requested: None, version: 0, lease_epoch: 0, effective_priority: None }}], failed_to_insert: [Task {{ rn: \"456\", spec: Null, status: Ready, queue: \"\", progress: 0.0, priority: 0, created: {0:?}, updated: {0:?}, deadline: None, owner: None, errors: [], history: [], metrics: None, result: None, not_before: None, attempts: 0, max_attempts: None, depends_on: [], on_dependency_failure: Abort, expires_at: None, labels: {{}}, supersession_key: None, concurrency_key: None, previous_rn: None, resubmitted_as: None, cancel_requested: None, version: 0, lease_epoch: 0, effective_priority: None }}] }}", t_now));
         // serialize trait
-        assert_eq!(serde_json::to_string(&tb).unwrap(), format!("{{\"inserted\":[{{\"rn\":\"123\",\"spec\":null,\"status\":\"ready\",\"queue\":\"\",\"progress\":0.0,\"priority\":0,\"created\":\"{0:?}\",\"updated\":\"{0:?}\",\"deadline\":null,\"owner\":null,\"errors\":[],\"history\":[],\"metrics\":null}},{{\"rn\":\"789\",\"spec\":null,\"status\":\"ready\",\"queue\":\"\",\"progress\":0.0,\"priority\":0,\"created\":\"{0:?}\",\"updated\":\"{0:?}\",\"deadline\":null,\"owner\":null,\"errors\":[],\"history\":[],\"metrics\":null}}],\"failedToInsert\":[{{\"rn\":\"456\",\"spec\":null,\"status\":\"ready\",\"queue\":\"\",\"progress\":0.0,\"priority\":0,\"created\":\"{0:?}\",\"updated\":\"{0:?}\",\"deadline\":null,\"owner\":null,\"errors\":[],\"history\":[],\"metrics\":null}}]}}", t_now));
+        assert_eq!(serde_json::to_string(&tb).unwrap(), format!("{{\"inserted\":[{{\"rn\":\"123\",\"spec\":null,\"status\":\"ready\",\"queue\":\"\",\"progress\":0.0,\"priority\":0,\"created\":\"{0:?}\",\"updated\":\"{0:?}\",\"deadline\":null,\"owner\":null,\"errors\":[],\"history\":[],\"metrics\":null,\"result\":null,\"notBefore\":null,\"attempts\":0,\"maxAttempts\":null,\"dependsOn\":[],\"onDependencyFailure\":\"abort\",\"expiresAt\":null,\"labels\":{{}},\"supersessionKey\":null,\"concurrencyKey\":null,\"previousRn\":null,\"resubmittedAs\":null,\"cancelRequested\":null,\"version\":0,\"leaseEpoch\":0}},{{\"rn\":\"789\",\"spec\":null,\"status\":\"ready\",\"queue\":\"\",\"progress\":0.0,\"priority\":0,\"created\":\"{0:?}\",\"updated\":\"{0:?}\",\"deadline\":null,\"owner\":null,\"errors\":[],\"history\":[],\"metrics\":null,\"result\":null,\"notBefore\":null,\"attempts\":0,\"maxAttempts\":null,\"dependsOn\":[],\"onDependencyFailure\":\"abort\",\"expiresAt\":null,\"labels\":{{}},\"supersessionKey\":null,\"concurrencyKey\":null,\"previousRn\":null,\"resubmittedAs\":null,\"cancelRequested\":null,\"version\":0,\"leaseEpoch\":0}}],\"failedToInsert\":[{{\"rn\":\"456\",\"spec\":null,\"status\":\"ready\",\"queue\":\"\",\"progress\":0.0,\"priority\":0,\"created\":\"{0:?}\",\"updated\":\"{0:?}\",\"deadline\":null,\"owner\":null,\"errors\":[],\"history\":[],\"metrics\":null,\"result\":null,\"notBefore\":null,\"attempts\":0,\"maxAttempts\":null,\"dependsOn\":[],\"onDependencyFailure\":\"abort\",\"expiresAt\":null,\"labels\":{{}},\"supersessionKey\":null,\"concurrencyKey\":null,\"previousRn\":null,\"resubmittedAs\":null,\"cancelRequested\":null,\"version\":0,\"leaseEpoch\":0}}]}}", t_now));
         // deserialize trait
-        assert_eq!(serde_json::from_str::<TaskBatch>(format!("{{\"inserted\":[{{\"rn\":\"123\",\"spec\":null,\"status\":\"ready\",\"queue\":\"\",\"progress\":0.0,\"priority\":0,\"created\":\"{0:?}\",\"updated\":\"{0:?}\",\"deadline\":null,\"owner\":null,\"errors\":[],\"history\":[],\"metrics\":null}},{{\"rn\":\"789\",\"spec\":null,\"status\":\"ready\",\"queue\":\"\",\"progress\":0.0,\"priority\":0,\"created\":\"{0:?}\",\"updated\":\"{0:?}\",\"deadline\":null,\"owner\":null,\"errors\":[],\"history\":[],\"metrics\":null}}],\"failedToInsert\":[{{\"rn\":\"456\",\"spec\":null,\"status\":\"ready\",\"queue\":\"\",\"progress\":0.0,\"priority\":0,\"created\":\"{0:?}\",\"updated\":\"{0:?}\",\"deadline\":null,\"owner\":null,\"errors\":[],\"history\":[],\"metrics\":null}}]}}", t_now).as_str()).unwrap(), tb);
+        assert_eq!(serde_json::from_str::<TaskBatch>(format!("{{\"inserted\":[{{\"rn\":\"123\",\"spec\":null,\"status\":\"ready\",\"queue\":\"\",\"progress\":0.0,\"priority\":0,\"created\":\"{0:?}\",\"updated\":\"{0:?}\",\"deadline\":null,\"owner\":null,\"errors\":[],\"history\":[],\"metrics\":null,\"result\":null,\"notBefore\":null,\"attempts\":0,\"maxAttempts\":null,\"dependsOn\":[],\"onDependencyFailure\":\"abort\",\"expiresAt\":null,\"labels\":{{}},\"supersessionKey\":null,\"concurrencyKey\":null,\"previousRn\":null,\"resubmittedAs\":null,\"cancelRequested\":null,\"version\":0,\"leaseEpoch\":0}},{{\"rn\":\"789\",\"spec\":null,\"status\":\"ready\",\"queue\":\"\",\"progress\":0.0,\"priority\":0,\"created\":\"{0:?}\",\"updated\":\"{0:?}\",\"deadline\":null,\"owner\":null,\"errors\":[],\"history\":[],\"metrics\":null,\"result\":null,\"notBefore\":null,\"attempts\":0,\"maxAttempts\":null,\"dependsOn\":[],\"onDependencyFailure\":\"abort\",\"expiresAt\":null,\"labels\":{{}},\"supersessionKey\":null,\"concurrencyKey\":null,\"previousRn\":null,\"resubmittedAs\":null,\"cancelRequested\":null,\"version\":0,\"leaseEpoch\":0}}],\"failedToInsert\":[{{\"rn\":\"456\",\"spec\":null,\"status\":\"ready\",\"queue\":\"\",\"progress\":0.0,\"priority\":0,\"created\":\"{0:?}\",\"updated\":\"{0:?}\",\"deadline\":null,\"owner\":null,\"errors\":[],\"history\":[],\"metrics\":null,\"result\":null,\"notBefore\":null,\"attempts\":0,\"maxAttempts\":null,\"dependsOn\":[],\"onDependencyFailure\":\"abort\",\"expiresAt\":null,\"labels\":{{}},\"supersessionKey\":null,\"concurrencyKey\":null,\"previousRn\":null,\"resubmittedAs\":null,\"cancelRequested\":null,\"version\":0,\"leaseEpoch\":0}}]}}", t_now).as_str()).unwrap(), tb);
+    }
+
+    #[test]
+    fn queue() {
+        let q = Queue {
+            name: "settlement".to_string(),
+            max_attempts: Some(3),
+            priority_aging: Some(PriorityAging {
+                seconds_per_point: 10,
+                max_boost: 50,
+            }),
+            lease_timeout_in_secs: Some(30),
+            retention_period_in_secs: Some(86400),
+            max_priority: Some(50),
+            max_running_tasks: Some(10),
+            max_running_tasks_per_key: Some(2),
+            rate_limit: Some(RateLimit {
+                max_starts: 100,
+                interval_in_secs: 60,
+            }),
+            description: Some("end of day settlement".to_string()),
+        };
+        // debug trait
+        assert_eq!(
+            format!("{:?}", q),
+            "Queue { name: \"settlement\", max_attempts: Some(3), priority_aging: Some(PriorityAging { seconds_per_point: 10, max_boost: 50 }), \
+            lease_timeout_in_secs: Some(30), retention_period_in_secs: Some(86400), max_priority: Some(50), \
+            max_running_tasks: Some(10), max_running_tasks_per_key: Some(2), rate_limit: Some(RateLimit { max_starts: 100, interval_in_secs: 60 }), \
+            description: Some(\"end of day settlement\") }"
+        );
+        // serialize trait
+        assert_eq!(
+            serde_json::to_string(&q).unwrap(),
+            "{\"name\":\"settlement\",\"maxAttempts\":3,\"priorityAging\":{\"secondsPerPoint\":10,\"maxBoost\":50},\"leaseTimeoutInSecs\":30,\
+            \"retentionPeriodInSecs\":86400,\"maxPriority\":50,\"maxRunningTasks\":10,\"maxRunningTasksPerKey\":2,\
+            \"rateLimit\":{\"maxStarts\":100,\"intervalInSecs\":60},\"description\":\"end of day settlement\"}"
+        );
+        // deserialize trait
+        assert_eq!(
+            serde_json::from_str::<Queue>(
+                "{\"name\":\"settlement\",\"maxAttempts\":3,\"priorityAging\":{\"secondsPerPoint\":10,\"maxBoost\":50},\"leaseTimeoutInSecs\":30,\
+                \"retentionPeriodInSecs\":86400,\"maxPriority\":50,\"maxRunningTasks\":10,\"maxRunningTasksPerKey\":2,\
+                \"rateLimit\":{\"maxStarts\":100,\"intervalInSecs\":60},\"description\":\"end of day settlement\"}"
+            )
+            .unwrap(),
+            q
+        );
+        // queues stored before lease timeout, retention, max priority and description were introduced
+        assert_eq!(
+            serde_json::from_str::<Queue>("{\"name\":\"settlement\",\"maxAttempts\":3,\"priorityAging\":null}").unwrap(),
+            Queue {
+                name: "settlement".to_string(),
+                max_attempts: Some(3),
+                ..Queue::default()
+            }
+        );
+        // default
+        assert_eq!(Queue::default().max_attempts, None);
+        assert_eq!(Queue::default().priority_aging, None);
+        assert_eq!(Queue::default().lease_timeout_in_secs, None);
+    }
+
+    #[test]
+    fn queue_state() {
+        let qs = QueueState {
+            name: "settlement".to_string(),
+            mode: QueueMode::Draining,
+        };
+        // debug trait
+        assert_eq!(format!("{:?}", qs), "QueueState { name: \"settlement\", mode: Draining }");
+        // serialize trait
+        assert_eq!(serde_json::to_string(&qs).unwrap(), "{\"name\":\"settlement\",\"mode\":\"draining\"}");
+        // deserialize trait
+        assert_eq!(
+            serde_json::from_str::<QueueState>("{\"name\":\"settlement\",\"mode\":\"draining\"}").unwrap(),
+            qs
+        );
+        // default
+        assert_eq!(QueueState::default().mode, QueueMode::Active);
+    }
+
+    #[test]
+    fn rate_limit_budget() {
+        let t_now = Utc::now();
+        let budget = RateLimitBudget {
+            name: "settlement".to_string(),
+            remaining: 0,
+            available_at: t_now,
+        };
+        // debug trait
+        assert_eq!(
+            format!("{:?}", budget),
+            format!("RateLimitBudget {{ name: \"settlement\", remaining: 0, available_at: {t_now:?} }}")
+        );
+        // serialize trait
+        assert_eq!(
+            serde_json::to_string(&budget).unwrap(),
+            format!("{{\"name\":\"settlement\",\"remaining\":0,\"availableAt\":\"{t_now:?}\"}}")
+        );
+        // deserialize trait
+        assert_eq!(
+            serde_json::from_str::<RateLimitBudget>(format!("{{\"name\":\"settlement\",\"remaining\":0,\"availableAt\":\"{t_now:?}\"}}").as_str()).unwrap(),
+            budget
+        );
+    }
+
+    #[test]
+    fn schedule() {
+        let t_now = Utc::now();
+        let s = Schedule {
+            name: "nightly".to_string(),
+            cron: "0 0 * * *".to_string(),
+            queue: "settlement".to_string(),
+            created: t_now,
+            ..Schedule::default()
+        };
+        // debug trait
+        assert_eq!(
+            format!("{:?}", s),
+            format!("Schedule {{ name: \"nightly\", cron: \"0 0 * * *\", timezone: \"UTC\", queue: \"settlement\", priority: 0, spec: Null, created: {t_now:?}, last_fired: None }}")
+        );
+        // serialize trait
+        assert_eq!(
+            serde_json::to_string(&s).unwrap(),
+            format!("{{\"name\":\"nightly\",\"cron\":\"0 0 * * *\",\"timezone\":\"UTC\",\"queue\":\"settlement\",\"priority\":0,\"spec\":null,\"created\":\"{t_now:?}\",\"lastFired\":null}}")
+        );
+        // deserialize trait
+        assert_eq!(serde_json::from_str::<Schedule>(serde_json::to_string(&s).unwrap().as_str()).unwrap(), s);
+    }
+
+    #[test]
+    fn worker() {
+        let t_now = Utc::now();
+        let w = Worker {
+            id: "worker-27".to_string(),
+            hostname: "node-1".to_string(),
+            version: "1.4.0".to_string(),
+            registered: t_now,
+            heartbeat: t_now,
+            draining: false,
+        };
+        // debug trait
+        assert_eq!(
+            format!("{:?}", w),
+            format!("Worker {{ id: \"worker-27\", hostname: \"node-1\", version: \"1.4.0\", registered: {t_now:?}, heartbeat: {t_now:?}, draining: false }}")
+        );
+        // serialize trait
+        assert_eq!(
+            serde_json::to_string(&w).unwrap(),
+            format!("{{\"id\":\"worker-27\",\"hostname\":\"node-1\",\"version\":\"1.4.0\",\"registered\":\"{t_now:?}\",\"heartbeat\":\"{t_now:?}\",\"draining\":false}}")
+        );
+        // deserialize trait
+        assert_eq!(serde_json::from_str::<Worker>(serde_json::to_string(&w).unwrap().as_str()).unwrap(), w);
+        // default
+        let w = Worker::default();
+        assert_eq!(w.registered, w.heartbeat);
+        assert!(!w.draining);
+        // workers registered before draining was introduced
+        let json = format!("{{\"id\":\"worker-27\",\"hostname\":\"node-1\",\"version\":\"1.4.0\",\"registered\":\"{t_now:?}\",\"heartbeat\":\"{t_now:?}\"}}");
+        assert!(!serde_json::from_str::<Worker>(&json).unwrap().draining);
+        assert_eq!(
+            serde_json::from_str::<WorkerMetadata>("{\"hostname\":\"node-1\",\"version\":\"1.4.0\"}").unwrap(),
+            WorkerMetadata {
+                hostname: "node-1".to_string(),
+                version: "1.4.0".to_string()
+            }
+        );
     }
 }
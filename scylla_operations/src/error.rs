@@ -12,6 +12,10 @@ pub enum ScyllaOperationsError {
     InvalidOperation(UpdateOperation, TaskStatus, TaskStatus),
     #[error("Validation failed: {0}")]
     ValidationFailed(String),
+    #[error("Worker {0} does not hold the lease of the task. Current owner is {1:?}.")]
+    LeaseNotOwned(String, Option<String>),
+    #[error("Lease epoch {0} is stale. Task has been leased again at epoch {1}.")]
+    StaleLease(i64, i64),
 }
 // $coverage:ignore-start
 #[cfg(test)]
@@ -41,5 +45,13 @@ mod tests {
             ScyllaOperationsError::ValidationFailed("field validation failed".to_string()).to_string(),
             "Validation failed: field validation failed".to_string()
         );
+        assert_eq!(
+            ScyllaOperationsError::LeaseNotOwned("worker1".to_string(), Some("worker2".to_string())).to_string(),
+            "Worker worker1 does not hold the lease of the task. Current owner is Some(\"worker2\").".to_string()
+        );
+        assert_eq!(
+            ScyllaOperationsError::StaleLease(1, 2).to_string(),
+            "Lease epoch 1 is stale. Task has been leased again at epoch 2.".to_string()
+        );
     }
 }
@@ -1,3 +1,6 @@
 pub mod error;
+pub mod queue;
+pub mod schedule;
 pub mod task;
 pub mod update_task;
+pub mod worker;
@@ -0,0 +1,78 @@
+//! Per queue defaults and limits, applied to tasks of registered queues.
+use crate::error::ScyllaOperationsError;
+use scylla_models::Queue;
+
+/// Lease timeout of leases and heartbeats which pass none, for queues without their own default.
+pub const DEFAULT_LEASE_TIMEOUT_IN_SECS: i64 = 10;
+
+/// # Errors
+/// Returns `ScyllaOperationsError::ValidationFailed` if name is empty or any of the defaults is out of range
+pub fn validate_queue(queue: &Queue) -> Result<(), ScyllaOperationsError> {
+    if queue.name.is_empty() {
+        return Err(ScyllaOperationsError::ValidationFailed("Queue name cannot be empty".to_string()));
+    }
+    if queue.lease_timeout_in_secs.is_some_and(|timeout| timeout < 1) {
+        return Err(ScyllaOperationsError::ValidationFailed("lease_timeout_in_secs should be positive".to_string()));
+    }
+    if queue.retention_period_in_secs.is_some_and(|retention| retention < 0) {
+        return Err(ScyllaOperationsError::ValidationFailed(
+            "retention_period_in_secs cannot be negative".to_string(),
+        ));
+    }
+    if queue.max_running_tasks.is_some_and(|max| max < 1) {
+        return Err(ScyllaOperationsError::ValidationFailed("max_running_tasks should be positive".to_string()));
+    }
+    if queue.max_running_tasks_per_key.is_some_and(|max| max < 1) {
+        return Err(ScyllaOperationsError::ValidationFailed(
+            "max_running_tasks_per_key should be positive".to_string(),
+        ));
+    }
+    if let Some(rate_limit) = &queue.rate_limit {
+        if rate_limit.max_starts < 1 {
+            return Err(ScyllaOperationsError::ValidationFailed("rate_limit.max_starts should be positive".to_string()));
+        }
+        if rate_limit.interval_in_secs < 1 {
+            return Err(ScyllaOperationsError::ValidationFailed(
+                "rate_limit.interval_in_secs should be positive".to_string(),
+            ));
+        }
+    }
+    if let Some(priority_aging) = &queue.priority_aging {
+        if priority_aging.seconds_per_point < 1 {
+            return Err(ScyllaOperationsError::ValidationFailed(
+                "priority_aging.seconds_per_point should be positive".to_string(),
+            ));
+        }
+        if priority_aging.max_boost < 0 {
+            return Err(ScyllaOperationsError::ValidationFailed(
+                "priority_aging.max_boost cannot be negative".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Lease timeout passed by the worker, otherwise the default of the queue, otherwise `DEFAULT_LEASE_TIMEOUT_IN_SECS`.
+pub fn lease_timeout_in_secs(task_timeout_in_secs: Option<i64>, queue: Option<&Queue>) -> i64 {
+    task_timeout_in_secs
+        .or_else(|| queue.and_then(|q| q.lease_timeout_in_secs))
+        .unwrap_or(DEFAULT_LEASE_TIMEOUT_IN_SECS)
+}
+
+/// # Errors
+/// Returns `ScyllaOperationsError::ValidationFailed` if priority exceeds the max priority of the queue
+pub fn validate_priority(priority: i8, queue: Option<&Queue>) -> Result<(), ScyllaOperationsError> {
+    match queue {
+        Some(Queue {
+            name,
+            max_priority: Some(max_priority),
+            ..
+        }) if priority > *max_priority => Err(ScyllaOperationsError::ValidationFailed(format!(
+            "Priority {priority} exceeds max priority {max_priority} of queue {name}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,139 @@
+// $coverage:ignore-start
+use crate::error::ScyllaOperationsError;
+use crate::queue::*;
+use scylla_models::{PriorityAging, Queue, RateLimit};
+
+fn settlement_queue() -> Queue {
+    Queue {
+        name: "settlement".to_string(),
+        lease_timeout_in_secs: Some(30),
+        retention_period_in_secs: Some(0),
+        max_priority: Some(50),
+        max_running_tasks: Some(10),
+        max_running_tasks_per_key: Some(1),
+        rate_limit: Some(RateLimit {
+            max_starts: 100,
+            interval_in_secs: 60,
+        }),
+        priority_aging: Some(PriorityAging {
+            seconds_per_point: 60,
+            max_boost: 0,
+        }),
+        ..Queue::default()
+    }
+}
+
+#[test]
+fn validate_queue_cases() {
+    validate_queue(&settlement_queue()).unwrap();
+    validate_queue(&Queue {
+        name: "settlement".to_string(),
+        ..Queue::default()
+    })
+    .unwrap();
+    assert_eq!(
+        validate_queue(&Queue::default()),
+        Err(ScyllaOperationsError::ValidationFailed("Queue name cannot be empty".to_string()))
+    );
+    assert_eq!(
+        validate_queue(&Queue {
+            lease_timeout_in_secs: Some(0),
+            ..settlement_queue()
+        }),
+        Err(ScyllaOperationsError::ValidationFailed("lease_timeout_in_secs should be positive".to_string()))
+    );
+    assert_eq!(
+        validate_queue(&Queue {
+            retention_period_in_secs: Some(-1),
+            ..settlement_queue()
+        }),
+        Err(ScyllaOperationsError::ValidationFailed(
+            "retention_period_in_secs cannot be negative".to_string()
+        ))
+    );
+    assert_eq!(
+        validate_queue(&Queue {
+            max_running_tasks: Some(0),
+            ..settlement_queue()
+        }),
+        Err(ScyllaOperationsError::ValidationFailed("max_running_tasks should be positive".to_string()))
+    );
+    assert_eq!(
+        validate_queue(&Queue {
+            max_running_tasks_per_key: Some(0),
+            ..settlement_queue()
+        }),
+        Err(ScyllaOperationsError::ValidationFailed(
+            "max_running_tasks_per_key should be positive".to_string()
+        ))
+    );
+    assert_eq!(
+        validate_queue(&Queue {
+            rate_limit: Some(RateLimit {
+                max_starts: 0,
+                interval_in_secs: 1
+            }),
+            ..settlement_queue()
+        }),
+        Err(ScyllaOperationsError::ValidationFailed("rate_limit.max_starts should be positive".to_string()))
+    );
+    assert_eq!(
+        validate_queue(&Queue {
+            rate_limit: Some(RateLimit {
+                max_starts: 10,
+                interval_in_secs: 0
+            }),
+            ..settlement_queue()
+        }),
+        Err(ScyllaOperationsError::ValidationFailed(
+            "rate_limit.interval_in_secs should be positive".to_string()
+        ))
+    );
+    assert_eq!(
+        validate_queue(&Queue {
+            priority_aging: Some(PriorityAging {
+                seconds_per_point: 0,
+                max_boost: 10
+            }),
+            ..settlement_queue()
+        }),
+        Err(ScyllaOperationsError::ValidationFailed(
+            "priority_aging.seconds_per_point should be positive".to_string()
+        ))
+    );
+    assert_eq!(
+        validate_queue(&Queue {
+            priority_aging: Some(PriorityAging {
+                seconds_per_point: 60,
+                max_boost: -1
+            }),
+            ..settlement_queue()
+        }),
+        Err(ScyllaOperationsError::ValidationFailed(
+            "priority_aging.max_boost cannot be negative".to_string()
+        ))
+    );
+}
+
+#[test]
+fn lease_timeout_in_secs_cases() {
+    let queue = settlement_queue();
+    assert_eq!(lease_timeout_in_secs(Some(5), Some(&queue)), 5);
+    assert_eq!(lease_timeout_in_secs(None, Some(&queue)), 30);
+    assert_eq!(lease_timeout_in_secs(None, Some(&Queue::default())), DEFAULT_LEASE_TIMEOUT_IN_SECS);
+    assert_eq!(lease_timeout_in_secs(None, None), DEFAULT_LEASE_TIMEOUT_IN_SECS);
+}
+
+#[test]
+fn validate_priority_cases() {
+    let queue = settlement_queue();
+    validate_priority(50, Some(&queue)).unwrap();
+    validate_priority(100, Some(&Queue::default())).unwrap();
+    validate_priority(100, None).unwrap();
+    assert_eq!(
+        validate_priority(51, Some(&queue)),
+        Err(ScyllaOperationsError::ValidationFailed(
+            "Priority 51 exceeds max priority 50 of queue settlement".to_string()
+        ))
+    );
+}
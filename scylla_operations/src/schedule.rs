@@ -0,0 +1,65 @@
+//! Evaluation of recurring task schedules. Monitor uses these to add tasks for schedules which are due.
+use std::str::FromStr;
+
+use crate::error::ScyllaOperationsError;
+use crate::task::ScyllaOperations;
+use chrono::{DateTime, SecondsFormat, Utc};
+use chrono_tz::Tz;
+use scylla_models::{AddTaskModel, Schedule, Task};
+
+/// Standard 5 field cron expressions are accepted along with 6 and 7 field ones which include seconds and year.
+fn parse_cron(expression: &str) -> Result<cron::Schedule, ScyllaOperationsError> {
+    let expression = if expression.split_whitespace().count() == 5 {
+        format!("0 {expression}")
+    } else {
+        expression.to_string()
+    };
+    cron::Schedule::from_str(&expression).map_err(|e| ScyllaOperationsError::ValidationFailed(format!("Invalid cron expression {expression}: {e}")))
+}
+
+fn parse_timezone(timezone: &str) -> Result<Tz, ScyllaOperationsError> {
+    Tz::from_str(timezone).map_err(|_| ScyllaOperationsError::ValidationFailed(format!("Invalid timezone {timezone}")))
+}
+
+/// # Errors
+/// Returns `ScyllaOperationsError::ValidationFailed` if name is empty or cron expression or timezone cannot be parsed
+pub fn validate_schedule(schedule: &Schedule) -> Result<(), ScyllaOperationsError> {
+    if schedule.name.is_empty() {
+        return Err(ScyllaOperationsError::ValidationFailed("Schedule name cannot be empty".to_string()));
+    }
+    parse_cron(&schedule.cron)?;
+    parse_timezone(&schedule.timezone)?;
+    Ok(())
+}
+
+/// Latest occurrence after `last_fired` (or `created` if schedule never fired) which is not later than `now`.
+/// Occurrences missed before it, e.g. while monitor was down, are skipped.
+/// # Errors
+/// Returns `ScyllaOperationsError::ValidationFailed` if cron expression or timezone cannot be parsed
+pub fn due_fire_time(schedule: &Schedule, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, ScyllaOperationsError> {
+    let cron = parse_cron(&schedule.cron)?;
+    let timezone = parse_timezone(&schedule.timezone)?;
+    let after = schedule.last_fired.unwrap_or(schedule.created).with_timezone(&timezone);
+    Ok(cron
+        .after(&after)
+        .take_while(|fire_time| *fire_time <= now)
+        .last()
+        .map(|fire_time| fire_time.with_timezone(&Utc)))
+}
+
+/// Task for an occurrence of the schedule. rn is derived from schedule name and fire time so that it is added only once
+/// even if several monitors are running.
+/// # Errors
+/// Returns `ScyllaOperationsError` if the task cannot be added
+pub fn schedule_task(schedule: &Schedule, fire_time: DateTime<Utc>) -> Result<Task, ScyllaOperationsError> {
+    ScyllaOperations::add_task_operation(&AddTaskModel {
+        rn: format!("{}:{}", schedule.name, fire_time.to_rfc3339_opts(SecondsFormat::Secs, true)),
+        spec: schedule.spec.clone(),
+        priority: schedule.priority,
+        queue: schedule.queue.clone(),
+        ..AddTaskModel::default()
+    })
+}
+
+#[cfg(test)]
+mod tests;
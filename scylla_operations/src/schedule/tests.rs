@@ -0,0 +1,92 @@
+// $coverage:ignore-start
+use crate::error::ScyllaOperationsError;
+use crate::schedule::*;
+use chrono::{Duration, TimeZone, Utc};
+use scylla_models::{Schedule, TaskStatus};
+
+fn nightly_schedule() -> Schedule {
+    Schedule {
+        name: "nightly".to_string(),
+        cron: "30 2 * * *".to_string(),
+        queue: "settlement".to_string(),
+        priority: 5,
+        spec: serde_json::json!({"a": "b"}),
+        created: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        ..Schedule::default()
+    }
+}
+
+#[test]
+fn validate_schedule_cases() {
+    validate_schedule(&nightly_schedule()).unwrap();
+    validate_schedule(&Schedule {
+        cron: "0 30 2 * * * 2030".to_string(),
+        timezone: "Australia/Melbourne".to_string(),
+        ..nightly_schedule()
+    })
+    .unwrap();
+    assert_eq!(
+        validate_schedule(&Schedule {
+            name: String::new(),
+            ..nightly_schedule()
+        }),
+        Err(ScyllaOperationsError::ValidationFailed("Schedule name cannot be empty".to_string()))
+    );
+    assert!(validate_schedule(&Schedule {
+        cron: "every night".to_string(),
+        ..nightly_schedule()
+    })
+    .is_err());
+    assert_eq!(
+        validate_schedule(&Schedule {
+            timezone: "Mars/Olympus".to_string(),
+            ..nightly_schedule()
+        }),
+        Err(ScyllaOperationsError::ValidationFailed("Invalid timezone Mars/Olympus".to_string()))
+    );
+}
+
+#[test]
+fn due_fire_time_cases() {
+    let schedule = nightly_schedule();
+    // not yet due
+    let now = Utc.with_ymd_and_hms(2024, 1, 1, 2, 0, 0).unwrap();
+    assert_eq!(due_fire_time(&schedule, now).unwrap(), None);
+    // due
+    let now = Utc.with_ymd_and_hms(2024, 1, 1, 2, 30, 5).unwrap();
+    assert_eq!(
+        due_fire_time(&schedule, now).unwrap(),
+        Some(Utc.with_ymd_and_hms(2024, 1, 1, 2, 30, 0).unwrap())
+    );
+    // already fired
+    let fired = Schedule {
+        last_fired: Some(Utc.with_ymd_and_hms(2024, 1, 1, 2, 30, 0).unwrap()),
+        ..schedule.clone()
+    };
+    assert_eq!(due_fire_time(&fired, now).unwrap(), None);
+    // only latest missed occurrence is due
+    let now = Utc.with_ymd_and_hms(2024, 1, 4, 3, 0, 0).unwrap();
+    assert_eq!(due_fire_time(&fired, now).unwrap(), Some(Utc.with_ymd_and_hms(2024, 1, 4, 2, 30, 0).unwrap()));
+    // evaluated in timezone of the schedule
+    let melbourne = Schedule {
+        timezone: "Australia/Melbourne".to_string(),
+        ..schedule
+    };
+    let now = Utc.with_ymd_and_hms(2024, 1, 1, 16, 0, 0).unwrap();
+    assert_eq!(
+        due_fire_time(&melbourne, now).unwrap(),
+        Some(Utc.with_ymd_and_hms(2024, 1, 1, 15, 30, 0).unwrap())
+    );
+    assert_eq!(due_fire_time(&melbourne, now - Duration::hours(1)).unwrap(), None);
+}
+
+#[test]
+fn schedule_task_cases() {
+    let schedule = nightly_schedule();
+    let task = schedule_task(&schedule, Utc.with_ymd_and_hms(2024, 1, 1, 2, 30, 0).unwrap()).unwrap();
+    assert_eq!(task.rn, "nightly:2024-01-01T02:30:00Z".to_string());
+    assert_eq!(task.queue, schedule.queue);
+    assert_eq!(task.priority, schedule.priority);
+    assert_eq!(task.spec, schedule.spec);
+    assert_eq!(task.status, TaskStatus::Ready);
+}
@@ -1,30 +1,121 @@
 //! Scylla Operations
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::error::ScyllaOperationsError;
 use crate::update_task::request_handler;
 use async_trait::async_trait;
-use scylla_models::{AddTaskModel, GetTaskModel, Task, TaskBatch, UpdateTaskModel};
+use chrono::{DateTime, Utc};
+use scylla_models::{
+    AddTaskModel, GetTaskModel, HeartbeatOutcome, LabelSelector, Queue, QueueState, RateLimitBudget, ResubmitOverrides, Schedule, Task, TaskBatch,
+    TaskHeartbeat, TaskStatus, UpdateTaskModel, Worker,
+};
 
 pub struct ScyllaOperations {}
 
 impl ScyllaOperations {
-    pub fn add_task_operation(add_task_model: &AddTaskModel) -> Task {
-        Task {
+    /// Task with dependencies starts as `Blocked` and is moved to `Ready` by monitor once all of them are completed
+    /// # Errors
+    /// Returns `ScyllaOperationsError` if the task depends on itself
+    pub fn add_task_operation(add_task_model: &AddTaskModel) -> Result<Task, ScyllaOperationsError> {
+        if add_task_model.depends_on.contains(&add_task_model.rn) {
+            return Err(ScyllaOperationsError::ValidationFailed(format!(
+                "Task {} cannot depend on itself",
+                add_task_model.rn
+            )));
+        }
+        Ok(Task {
             rn: add_task_model.rn.clone(),
+            status: if add_task_model.depends_on.is_empty() {
+                TaskStatus::Ready
+            } else {
+                TaskStatus::Blocked
+            },
             spec: add_task_model.spec.clone(),
             queue: add_task_model.queue.clone(),
             priority: add_task_model.priority,
+            not_before: add_task_model.not_before,
+            max_attempts: add_task_model.max_attempts,
+            depends_on: add_task_model.depends_on.clone(),
+            on_dependency_failure: add_task_model.on_dependency_failure.clone(),
+            expires_at: add_task_model.expires_at,
+            labels: add_task_model.labels.clone(),
+            supersession_key: add_task_model.supersession_key.clone(),
+            concurrency_key: add_task_model.concurrency_key.clone(),
             ..Task::default()
+        })
+    }
+
+    /// # Errors
+    /// Returns `ScyllaOperationsError` if the task has no supersession key or depends on itself
+    pub fn supersede_task_operation(add_task_model: &AddTaskModel) -> Result<Task, ScyllaOperationsError> {
+        match &add_task_model.supersession_key {
+            Some(key) if !key.is_empty() => Self::add_task_operation(add_task_model),
+            _ => Err(ScyllaOperationsError::ValidationFailed(
+                "supersession_key is mandatory to supersede a task".to_string(),
+            )),
+        }
+    }
+
+    /// Ready clone of an aborted, cancelled or expired task, linked to it by `previous_rn`.
+    /// # Errors
+    /// Returns `ScyllaOperationsError` if the task cannot be resubmitted
+    pub fn resubmit_task_operation(previous: &Task, new_rn: String, overrides: &ResubmitOverrides) -> Result<Task, ScyllaOperationsError> {
+        if !matches!(previous.status, TaskStatus::Aborted | TaskStatus::Cancelled | TaskStatus::Expired) {
+            return Err(ScyllaOperationsError::ValidationFailed(format!(
+                "Task in {} status cannot be resubmitted",
+                previous.status
+            )));
         }
+        if let Some(resubmitted_as) = &previous.resubmitted_as {
+            return Err(ScyllaOperationsError::ValidationFailed(format!(
+                "Task has already been resubmitted as {resubmitted_as}"
+            )));
+        }
+        Ok(Task {
+            rn: new_rn,
+            spec: overrides.spec.clone().unwrap_or_else(|| previous.spec.clone()),
+            queue: overrides.queue.clone().unwrap_or_else(|| previous.queue.clone()),
+            priority: overrides.priority.unwrap_or(previous.priority),
+            max_attempts: overrides.max_attempts.or(previous.max_attempts),
+            labels: previous.labels.clone(),
+            concurrency_key: previous.concurrency_key.clone(),
+            previous_rn: Some(previous.rn.clone()),
+            ..Task::default()
+        })
     }
 
-    pub fn add_task_operations(add_task_models: &Vec<AddTaskModel>) -> Vec<Task> {
+    /// Outcome of a heartbeat of `worker` for its lease `lease_epoch`, given the task as stored after the heartbeat,
+    /// or after the heartbeat has been rejected.
+    pub fn heart_beat_outcome(task: Task, worker: &str, lease_epoch: i64) -> HeartbeatOutcome {
+        let holds_lease = task.owner.as_deref() == Some(worker) && task.lease_epoch == lease_epoch;
+        match task.status {
+            TaskStatus::Completed if holds_lease => HeartbeatOutcome::Completed,
+            TaskStatus::Running if holds_lease => HeartbeatOutcome::Extended { task },
+            TaskStatus::Cancelling | TaskStatus::Cancelled if holds_lease => HeartbeatOutcome::Cancelled { task },
+            TaskStatus::Running | TaskStatus::Cancelling => HeartbeatOutcome::LeaseLost {
+                new_owner: task.owner,
+                status: task.status,
+            },
+            status => HeartbeatOutcome::LeaseLost { new_owner: None, status },
+        }
+    }
+
+    /// # Errors
+    /// Returns `ScyllaOperationsError` if any task depends on itself, directly or through other tasks of the batch
+    pub fn add_task_operations(add_task_models: &Vec<AddTaskModel>) -> Result<Vec<Task>, ScyllaOperationsError> {
         let mut by_rn = BTreeMap::new();
         for model in add_task_models {
-            by_rn.entry(model.rn.clone()).or_insert_with(|| ScyllaOperations::add_task_operation(model));
+            if !by_rn.contains_key(&model.rn) {
+                by_rn.insert(model.rn.clone(), ScyllaOperations::add_task_operation(model)?);
+            }
         }
-        by_rn.into_values().collect()
+        if let Some(cycle) = dependency_cycle(&by_rn) {
+            return Err(ScyllaOperationsError::ValidationFailed(format!(
+                "Tasks {} depend on each other",
+                cycle.join(", ")
+            )));
+        }
+        Ok(by_rn.into_values().collect())
     }
 
     /// # Errors
@@ -33,6 +124,31 @@ impl ScyllaOperations {
         request_handler(task_to_update, update_task_model)
     }
 }
+/// rns of the first cycle found among the dependencies of the tasks, which would keep all of them blocked
+fn dependency_cycle(by_rn: &BTreeMap<String, Task>) -> Option<Vec<String>> {
+    fn visit<'a>(rn: &'a String, by_rn: &'a BTreeMap<String, Task>, path: &mut Vec<&'a String>, acyclic: &mut BTreeSet<&'a String>) -> Option<Vec<String>> {
+        if acyclic.contains(rn) {
+            return None;
+        }
+        if let Some(start) = path.iter().position(|visited| *visited == rn) {
+            return Some(path[start..].iter().map(|visited| (*visited).clone()).collect());
+        }
+        // dependencies outside the batch exist already, so they cannot depend on the batch
+        let task = by_rn.get(rn)?;
+        path.push(rn);
+        for parent in &task.depends_on {
+            if let Some(cycle) = visit(parent, by_rn, path, acyclic) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        acyclic.insert(rn);
+        None
+    }
+    let mut acyclic = BTreeSet::new();
+    by_rn.keys().find_map(|rn| visit(rn, by_rn, &mut Vec::new(), &mut acyclic))
+}
+
 #[async_trait]
 pub trait Persistence
 where
@@ -40,15 +156,71 @@ where
 {
     type PersistenceError;
 
+    /// Tasks are not inserted into draining queues, nor if a task they depend on does not exist or they depend on themselves.
     async fn insert(&self, task: Task) -> Result<Task, Self::PersistenceError>;
+    /// Batch containing a task for a draining queue, depending on a task which neither exists nor is in the batch,
+    /// or depending on itself, is rejected as a whole.
     async fn batch_insert(&self, tasks: Vec<Task>) -> Result<TaskBatch, Self::PersistenceError>;
+    /// Replaces spec and priority of ready tasks with the same supersession key in the queue, otherwise inserts the task
+    /// unless the queue is draining.
+    /// Cancellation of running tasks with the same supersession key is requested when `cancel_running` is set.
+    async fn supersede(&self, task: Task, cancel_running: bool) -> Result<Task, Self::PersistenceError>;
+    /// Inserts the task and links the task it has been resubmitted from to it, if that is still at `previous_version`
+    /// and the queue of the task is not draining.
+    async fn resubmit(&self, task: Task, previous_version: i64) -> Result<Task, Self::PersistenceError>;
     async fn update(&self, task: Task) -> Result<Task, Self::PersistenceError>;
+    /// Updates the leased task, unless its queue is paused or its queue or its concurrency key has reached the concurrency
+    /// limits of the queue.
+    async fn lease(&self, task: Task) -> Result<Task, Self::PersistenceError>;
     async fn query(&self, get_task_model: &GetTaskModel) -> Result<Vec<Task>, Self::PersistenceError>;
     async fn query_by_rn(&self, rn: String) -> Result<Task, Self::PersistenceError>;
     async fn reset_batch(&self) -> Result<Vec<Task>, Self::PersistenceError>;
-
-    async fn lease_batch(&self, queue: String, limit: i32, worker: String, task_timeout_in_secs: i64) -> Result<Vec<Task>, Self::PersistenceError>;
+    /// Resets the running tasks owned by `worker` regardless of their deadline, as if they had timed out.
+    async fn reset_worker_batch(&self, worker: String, actor: String) -> Result<Vec<Task>, Self::PersistenceError>;
+    /// Yields the running tasks owned by `worker`, passing their deadline so that the monitor resets them on its next poll.
+    async fn yield_worker_batch(&self, worker: String) -> Result<Vec<Task>, Self::PersistenceError>;
+    async fn resolve_blocked_batch(&self) -> Result<Vec<Task>, Self::PersistenceError>;
+    async fn expire_batch(&self) -> Result<Vec<Task>, Self::PersistenceError>;
+    /// Cancels cancelling tasks whose worker has not acknowledged the cancellation within the grace period or whose deadline has passed.
+    async fn cancel_batch(&self, grace_period_in_secs: i64) -> Result<Vec<Task>, Self::PersistenceError>;
+    /// Tasks of paused queues are not leased. Lease timeout defaults to the one of the queue of each task.
+    async fn lease_batch(
+        &self,
+        queue: String,
+        limit: i32,
+        worker: String,
+        task_timeout_in_secs: Option<i64>,
+        labels: Vec<LabelSelector>,
+    ) -> Result<Vec<Task>, Self::PersistenceError>;
+    /// Extends in a single statement the leases `worker` holds on the tasks of `heartbeats`, with the lease timeout
+    /// defaulting to the one of the queue of each task. Returns the tasks found, extended or not.
+    async fn heartbeat_batch(
+        &self,
+        worker: String,
+        heartbeats: Vec<TaskHeartbeat>,
+        task_timeout_in_secs: Option<i64>,
+    ) -> Result<Vec<Task>, Self::PersistenceError>;
+    /// Deletes terminated tasks older than the retention period of their queue, or `retention_time_in_secs` if it has none.
     async fn delete_batch(&self, retention_time_in_secs: i64) -> Result<u64, Self::PersistenceError>;
+    async fn upsert_queue(&self, queue: Queue) -> Result<Queue, Self::PersistenceError>;
+    async fn query_queue_by_name(&self, name: String) -> Result<Queue, Self::PersistenceError>;
+    async fn upsert_queue_state(&self, queue_state: QueueState) -> Result<QueueState, Self::PersistenceError>;
+    /// Queue without a stored state is active.
+    async fn query_queue_state(&self, name: String) -> Result<QueueState, Self::PersistenceError>;
+    /// Tokens left in the bucket of a rate limited queue.
+    async fn query_rate_limit_budget(&self, name: String) -> Result<RateLimitBudget, Self::PersistenceError>;
+    async fn upsert_schedule(&self, schedule: Schedule) -> Result<Schedule, Self::PersistenceError>;
+    async fn query_schedules(&self) -> Result<Vec<Schedule>, Self::PersistenceError>;
+    async fn query_schedule_by_name(&self, name: String) -> Result<Schedule, Self::PersistenceError>;
+    async fn delete_schedule(&self, name: String) -> Result<u64, Self::PersistenceError>;
+    async fn mark_schedule_fired(&self, name: String, fire_time: DateTime<Utc>) -> Result<u64, Self::PersistenceError>;
+    /// Inserts the worker with an id issued from the worker sequence.
+    async fn register_worker(&self, worker: Worker) -> Result<Worker, Self::PersistenceError>;
+    async fn heartbeat_worker(&self, id: String) -> Result<Worker, Self::PersistenceError>;
+    async fn query_worker_by_id(&self, id: String) -> Result<Worker, Self::PersistenceError>;
+    async fn query_workers(&self) -> Result<Vec<Worker>, Self::PersistenceError>;
+    async fn set_worker_draining(&self, id: String, draining: bool) -> Result<Worker, Self::PersistenceError>;
+    async fn delete_worker(&self, id: String) -> Result<u64, Self::PersistenceError>;
 }
 
 #[cfg(test)]
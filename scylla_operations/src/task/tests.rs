@@ -1,7 +1,7 @@
 use chrono::{Duration, Utc};
 // $coverage:ignore-start
 use crate::task::{AddTaskModel, ScyllaOperations, ScyllaOperationsError};
-use scylla_models::{Task, TaskStatus, UpdateOperation, UpdateTaskModel};
+use scylla_models::{DependencyFailurePolicy, HeartbeatOutcome, ResubmitOverrides, Task, TaskStatus, UpdateOperation, UpdateTaskModel};
 
 #[test]
 fn insert_returns_task() {
@@ -10,13 +10,21 @@ fn insert_returns_task() {
         priority: 1,
         queue: "ss".to_string(),
         spec: serde_json::Value::default(),
+        not_before: Some(Utc::now() + Duration::minutes(15)),
+        max_attempts: Some(3),
+        concurrency_key: Some("account:42".to_string()),
+        ..AddTaskModel::default()
     };
     let default_task: Task = Task::default();
-    let returned_task = ScyllaOperations::add_task_operation(&add_task_model);
+    let returned_task = ScyllaOperations::add_task_operation(&add_task_model).unwrap();
     assert_eq!(&returned_task.rn, &add_task_model.rn);
     assert_eq!(&returned_task.priority, &add_task_model.priority);
     assert_eq!(&returned_task.queue, &add_task_model.queue);
     assert_eq!(&returned_task.spec, &add_task_model.spec);
+    assert_eq!(&returned_task.not_before, &add_task_model.not_before);
+    assert_eq!(&returned_task.max_attempts, &add_task_model.max_attempts);
+    assert_eq!(&returned_task.concurrency_key, &add_task_model.concurrency_key);
+    assert_eq!(returned_task.attempts, 0);
     // default values assigned
     assert_eq!(&returned_task.progress, &default_task.progress);
     assert_eq!(&returned_task.errors, &default_task.errors);
@@ -25,6 +33,234 @@ fn insert_returns_task() {
     assert_eq!(&returned_task.status, &default_task.status);
 }
 
+#[test]
+fn insert_with_dependencies_returns_blocked_task() {
+    let add_task_model = AddTaskModel {
+        rn: "1234".to_string(),
+        priority: 1,
+        queue: "ss".to_string(),
+        spec: serde_json::Value::default(),
+        depends_on: vec!["123".to_string()],
+        on_dependency_failure: DependencyFailurePolicy::Cancel,
+        ..AddTaskModel::default()
+    };
+    let returned_task = ScyllaOperations::add_task_operation(&add_task_model).unwrap();
+    assert_eq!(returned_task.status, TaskStatus::Blocked);
+    assert_eq!(returned_task.depends_on, vec!["123".to_string()]);
+    assert_eq!(returned_task.on_dependency_failure, DependencyFailurePolicy::Cancel);
+}
+
+#[test]
+fn insert_depending_on_itself_fails() {
+    let add_task_model = AddTaskModel {
+        rn: "1234".to_string(),
+        priority: 1,
+        queue: "ss".to_string(),
+        spec: serde_json::Value::default(),
+        depends_on: vec!["123".to_string(), "1234".to_string()],
+        ..AddTaskModel::default()
+    };
+    let expected_error = || ScyllaOperationsError::ValidationFailed("Task 1234 cannot depend on itself".to_string());
+    assert_eq!(ScyllaOperations::add_task_operation(&add_task_model), Err(expected_error()));
+    assert_eq!(ScyllaOperations::add_task_operations(&vec![add_task_model]), Err(expected_error()));
+}
+
+#[test]
+fn batch_with_dependency_cycle_fails() {
+    let add_task_model = |rn: &str, depends_on: &[&str]| AddTaskModel {
+        rn: rn.to_string(),
+        priority: 1,
+        queue: "ss".to_string(),
+        spec: serde_json::Value::default(),
+        depends_on: depends_on.iter().map(|rn| (*rn).to_string()).collect(),
+        ..AddTaskModel::default()
+    };
+    let tasks = ScyllaOperations::add_task_operations(&vec![
+        add_task_model("a", &["b", "c"]),
+        add_task_model("b", &["c", "existing"]),
+        add_task_model("c", &[]),
+    ])
+    .unwrap();
+    assert_eq!(tasks.len(), 3);
+    assert_eq!(
+        ScyllaOperations::add_task_operations(&vec![
+            add_task_model("a", &["b"]),
+            add_task_model("b", &["c"]),
+            add_task_model("c", &["a", "existing"]),
+        ]),
+        Err(ScyllaOperationsError::ValidationFailed("Tasks a, b, c depend on each other".to_string()))
+    );
+}
+
+#[test]
+fn supersede_requires_supersession_key() {
+    let mut add_task_model = AddTaskModel {
+        rn: "1234".to_string(),
+        priority: 1,
+        queue: "ss".to_string(),
+        spec: serde_json::Value::default(),
+        ..AddTaskModel::default()
+    };
+    let expected_error = ScyllaOperationsError::ValidationFailed("supersession_key is mandatory to supersede a task".to_string());
+    assert_eq!(ScyllaOperations::supersede_task_operation(&add_task_model).unwrap_err(), expected_error);
+    add_task_model.supersession_key = Some(String::new());
+    assert_eq!(ScyllaOperations::supersede_task_operation(&add_task_model).unwrap_err(), expected_error);
+    add_task_model.supersession_key = Some("account:42".to_string());
+    let returned_task = ScyllaOperations::supersede_task_operation(&add_task_model).unwrap();
+    assert_eq!(returned_task.rn, "1234".to_string());
+    assert_eq!(returned_task.supersession_key, Some("account:42".to_string()));
+    assert_eq!(returned_task.status, TaskStatus::Ready);
+}
+
+#[test]
+fn resubmit_task_operation_cases() {
+    let previous = Task {
+        rn: "old".to_string(),
+        queue: "q".to_string(),
+        priority: 3,
+        spec: serde_json::json!({"a": 1}),
+        status: TaskStatus::Aborted,
+        attempts: 2,
+        max_attempts: Some(5),
+        labels: [("region".to_string(), "eu".to_string())].into(),
+        concurrency_key: Some("account:42".to_string()),
+        ..Task::default()
+    };
+    let task = ScyllaOperations::resubmit_task_operation(&previous, "new".to_string(), &ResubmitOverrides::default()).unwrap();
+    assert_eq!(task.rn, "new".to_string());
+    assert_eq!(task.status, TaskStatus::Ready);
+    assert_eq!(task.queue, previous.queue);
+    assert_eq!(task.priority, previous.priority);
+    assert_eq!(task.spec, previous.spec);
+    assert_eq!(task.labels, previous.labels);
+    assert_eq!(task.concurrency_key, previous.concurrency_key);
+    assert_eq!(task.attempts, 0);
+    assert_eq!(task.max_attempts, Some(5)); // retry budget of the task is kept
+    assert_eq!(task.previous_rn, Some("old".to_string()));
+    /*********************************/
+    // overrides
+    let overrides = ResubmitOverrides {
+        spec: Some(serde_json::json!({"a": 2})),
+        queue: Some("other".to_string()),
+        priority: Some(9),
+        max_attempts: Some(1),
+    };
+    let task = ScyllaOperations::resubmit_task_operation(&previous, "new".to_string(), &overrides).unwrap();
+    assert_eq!(task.spec, serde_json::json!({"a": 2}));
+    assert_eq!(task.queue, "other".to_string());
+    assert_eq!(task.priority, 9);
+    assert_eq!(task.max_attempts, Some(1));
+    /*********************************/
+    // only aborted, cancelled or expired task can be resubmitted, and only once
+    for status in [TaskStatus::Ready, TaskStatus::Running, TaskStatus::Blocked, TaskStatus::Completed] {
+        let task = Task {
+            status: status.clone(),
+            ..previous.clone()
+        };
+        assert_eq!(
+            ScyllaOperations::resubmit_task_operation(&task, "new".to_string(), &ResubmitOverrides::default()),
+            Err(ScyllaOperationsError::ValidationFailed(format!(
+                "Task in {status} status cannot be resubmitted"
+            )))
+        );
+    }
+    let resubmitted = Task {
+        resubmitted_as: Some("other".to_string()),
+        ..previous
+    };
+    assert_eq!(
+        ScyllaOperations::resubmit_task_operation(&resubmitted, "new".to_string(), &ResubmitOverrides::default()),
+        Err(ScyllaOperationsError::ValidationFailed(
+            "Task has already been resubmitted as other".to_string()
+        ))
+    );
+}
+
+#[test]
+fn heart_beat_outcome_cases() {
+    let t_running = Task {
+        status: TaskStatus::Running,
+        owner: Some("worker1".to_string()),
+        lease_epoch: 2,
+        ..Task::default()
+    };
+    assert_eq!(
+        ScyllaOperations::heart_beat_outcome(t_running.clone(), "worker1", 2),
+        HeartbeatOutcome::Extended { task: t_running.clone() }
+    );
+    // leased again by another worker, or by the same worker
+    assert_eq!(
+        ScyllaOperations::heart_beat_outcome(t_running.clone(), "worker2", 2),
+        HeartbeatOutcome::LeaseLost {
+            new_owner: Some("worker1".to_string()),
+            status: TaskStatus::Running,
+        }
+    );
+    assert_eq!(
+        ScyllaOperations::heart_beat_outcome(t_running.clone(), "worker1", 1),
+        HeartbeatOutcome::LeaseLost {
+            new_owner: Some("worker1".to_string()),
+            status: TaskStatus::Running,
+        }
+    );
+    // reset by monitor
+    let t_ready = Task {
+        status: TaskStatus::Ready,
+        owner: None,
+        ..t_running.clone()
+    };
+    assert_eq!(
+        ScyllaOperations::heart_beat_outcome(t_ready, "worker1", 2),
+        HeartbeatOutcome::LeaseLost {
+            new_owner: None,
+            status: TaskStatus::Ready,
+        }
+    );
+    // aborted once out of attempts, or expired, is told apart from a reset
+    for status in [TaskStatus::Aborted, TaskStatus::Expired] {
+        let task = Task {
+            status: status.clone(),
+            owner: None,
+            ..t_running.clone()
+        };
+        assert_eq!(
+            ScyllaOperations::heart_beat_outcome(task, "worker1", 2),
+            HeartbeatOutcome::LeaseLost { new_owner: None, status }
+        );
+    }
+    // cancellation requested or cancelled already
+    for status in [TaskStatus::Cancelling, TaskStatus::Cancelled] {
+        let task = Task { status, ..t_running.clone() };
+        assert_eq!(
+            ScyllaOperations::heart_beat_outcome(task.clone(), "worker1", 2),
+            HeartbeatOutcome::Cancelled { task }
+        );
+    }
+    let t_completed = Task {
+        status: TaskStatus::Completed,
+        ..t_running
+    };
+    assert_eq!(
+        ScyllaOperations::heart_beat_outcome(t_completed.clone(), "worker1", 2),
+        HeartbeatOutcome::Completed
+    );
+    // completed by another worker, or by another lease of the same worker
+    assert_eq!(
+        ScyllaOperations::heart_beat_outcome(t_completed.clone(), "worker2", 2),
+        HeartbeatOutcome::LeaseLost {
+            new_owner: None,
+            status: TaskStatus::Completed,
+        }
+    );
+    assert_eq!(
+        ScyllaOperations::heart_beat_outcome(t_completed, "worker1", 1),
+        HeartbeatOutcome::LeaseLost {
+            new_owner: None,
+            status: TaskStatus::Completed,
+        }
+    );
+}
+
 #[test]
 fn add_task_operations() {
     let add_task_models = vec![
@@ -33,16 +269,18 @@ fn add_task_operations() {
             priority: 1,
             queue: "aa".to_string(),
             spec: serde_json::Value::default(),
+            ..AddTaskModel::default()
         },
         AddTaskModel {
             rn: "456".to_string(),
             priority: 2,
             queue: "bb".to_string(),
             spec: serde_json::Value::default(),
+            ..AddTaskModel::default()
         },
     ];
     let default_task: Task = Task::default();
-    let returned_tasks = ScyllaOperations::add_task_operations(&add_task_models);
+    let returned_tasks = ScyllaOperations::add_task_operations(&add_task_models).unwrap();
     // first task
     assert_eq!(&returned_tasks[0].rn, &add_task_models[0].rn);
     assert_eq!(&returned_tasks[0].priority, &add_task_models[0].priority);
@@ -72,27 +310,31 @@ fn add_task_operations_dedupes_and_sorts_by_rn() {
             priority: 2,
             queue: "bb".to_string(),
             spec: serde_json::Value::default(),
+            ..AddTaskModel::default()
         },
         AddTaskModel {
             rn: "123".to_string(),
             priority: 9,
             queue: "duplicate".to_string(),
             spec: serde_json::json!({"dup": true}),
+            ..AddTaskModel::default()
         },
         AddTaskModel {
             rn: "001".to_string(),
             priority: 3,
             queue: "ab".to_string(),
             spec: serde_json::Value::default(),
+            ..AddTaskModel::default()
         },
         AddTaskModel {
             rn: "123".to_string(),
             priority: 1,
             queue: "aa".to_string(),
             spec: serde_json::Value::default(),
+            ..AddTaskModel::default()
         },
     ];
-    let returned_tasks = ScyllaOperations::add_task_operations(&add_task_models);
+    let returned_tasks = ScyllaOperations::add_task_operations(&add_task_models).unwrap();
     assert_eq!(returned_tasks.len(), 3);
     assert_eq!(returned_tasks[0].rn, "001");
     assert_eq!(returned_tasks[0].priority, 3);
@@ -1,16 +1,36 @@
 use crate::error::ScyllaOperationsError;
+use crate::queue::DEFAULT_LEASE_TIMEOUT_IN_SECS;
 use chrono::{Duration, Utc};
-use scylla_models::{Task, TaskHistory, TaskHistoryType, TaskStatus, TaskStatusExt, UpdateOperation, UpdateTaskModel};
+use scylla_models::{CancelRequest, Task, TaskError, TaskHistory, TaskHistoryType, TaskStatus, TaskStatusExt, UpdateOperation, UpdateTaskModel};
+use serde_json::json;
 
-const NON_TERMINAL_STATUSES: &[TaskStatus] = &[TaskStatus::Ready, TaskStatus::Running];
+const NON_TERMINAL_STATUSES: &[TaskStatus] = &[TaskStatus::Ready, TaskStatus::Running, TaskStatus::Blocked, TaskStatus::Cancelling];
+pub const MAX_ATTEMPTS_EXCEEDED_ERROR_CODE: &str = "max_attempts_exceeded";
+pub const DEPENDENCY_FAILED_ERROR_CODE: &str = "dependency_failed";
+pub const SUPERSEDED_ERROR_CODE: &str = "superseded";
+/// Reason recorded in history for tasks expired by the monitor.
+pub const EXPIRED_REASON: &str = "expired";
+/// Reason recorded in history for cancelling tasks cancelled by the monitor after the grace period.
+pub const CANCELLATION_NOT_ACKNOWLEDGED_REASON: &str = "cancellation_not_acknowledged";
+/// Reason recorded in history for tasks of a worker reset by an operator, e.g. once the worker is known to be dead.
+pub const WORKER_RESET_REASON: &str = "worker_reset";
+/// Actor recorded in history for transitions made by the monitor.
+pub const MONITOR_ACTOR: &str = "monitor";
+/// Actor recorded in history for tasks replaced, or requested to be cancelled, by a task with the same supersession key.
+pub const SUPERSESSION_ACTOR: &str = "supersession";
+/// Results are stored with the task, larger outputs are to be stored elsewhere and referenced by the result.
+pub const MAX_RESULT_SIZE_IN_BYTES: usize = 64 * 1024;
 
 /// # Errors
 /// Returns `ScyllaOperationsError`
 pub fn validate_status_operation(task: &Task, update_task_model: &UpdateTaskModel) -> Result<(), ScyllaOperationsError> {
-    let status_value = update_task_model
-        .status
-        .as_ref()
-        .ok_or_else(|| ScyllaOperationsError::MandatoryFieldMissing("status".to_string(), UpdateOperation::Status))?;
+    let status_value = &requested_status(
+        task,
+        update_task_model
+            .status
+            .as_ref()
+            .ok_or_else(|| ScyllaOperationsError::MandatoryFieldMissing("status".to_string(), UpdateOperation::Status))?,
+    );
 
     if !NON_TERMINAL_STATUSES.contains(&task.status) {
         return Err(ScyllaOperationsError::TerminalTaskStatus(task.status.clone(), NON_TERMINAL_STATUSES.into()));
@@ -26,30 +46,136 @@ pub fn validate_status_operation(task: &Task, update_task_model: &UpdateTaskMode
         return Err(ScyllaOperationsError::MandatoryFieldMissing("error".to_string(), UpdateOperation::Status));
     }
 
+    if let Some(result) = &update_task_model.result {
+        validate_result(result, status_value)?;
+    }
+
+    if *status_value == TaskStatus::Completed || *status_value == TaskStatus::Aborted || task.status == TaskStatus::Cancelling {
+        // only requesting cancellation is allowed for anyone other than the worker, the worker acknowledges it
+        validate_lease_owner(task, update_task_model, &UpdateOperation::Status)?;
+    }
+
     Ok(())
 }
 
+/// Cancellation of a running task is only requested, the task is cancelled once its worker acknowledges it.
+fn requested_status(task: &Task, status: &TaskStatus) -> TaskStatus {
+    if task.status == TaskStatus::Running && *status == TaskStatus::Cancelled {
+        TaskStatus::Cancelling
+    } else {
+        status.clone()
+    }
+}
+
+/// # Errors
+/// Returns `ScyllaOperationsError::ValidationFailed` if the result is not for completion or exceeds `MAX_RESULT_SIZE_IN_BYTES`.
+fn validate_result(result: &serde_json::Value, status: &TaskStatus) -> Result<(), ScyllaOperationsError> {
+    if *status != TaskStatus::Completed {
+        return Err(ScyllaOperationsError::ValidationFailed("result can only be set on completion".to_string()));
+    }
+    let size = result.to_string().len();
+    if size > MAX_RESULT_SIZE_IN_BYTES {
+        return Err(ScyllaOperationsError::ValidationFailed(format!(
+            "result of {size} bytes exceeds the limit of {MAX_RESULT_SIZE_IN_BYTES} bytes"
+        )));
+    }
+    Ok(())
+}
+
+/// # Errors
+/// Returns `ScyllaOperationsError::LeaseNotOwned` if the worker is not the current owner of the task.
+/// This is the case when the lease of the worker has been reset and the task possibly leased by another worker.
+fn validate_lease_owner(task: &Task, update_task_model: &UpdateTaskModel, operation: &UpdateOperation) -> Result<(), ScyllaOperationsError> {
+    let worker = update_task_model
+        .worker
+        .as_ref()
+        .ok_or_else(|| ScyllaOperationsError::MandatoryFieldMissing("worker".to_string(), operation.clone()))?;
+    if task.owner.as_ref() == Some(worker) {
+        validate_lease_epoch(task, update_task_model, operation)
+    } else {
+        Err(ScyllaOperationsError::LeaseNotOwned(worker.clone(), task.owner.clone()))
+    }
+}
+
+/// # Errors
+/// Returns `ScyllaOperationsError::StaleLease` if the task has been leased again since the worker leased it.
+/// This is the case even if it has been leased again by the same worker.
+fn validate_lease_epoch(task: &Task, update_task_model: &UpdateTaskModel, operation: &UpdateOperation) -> Result<(), ScyllaOperationsError> {
+    let lease_epoch = update_task_model
+        .lease_epoch
+        .ok_or_else(|| ScyllaOperationsError::MandatoryFieldMissing("lease_epoch".to_string(), operation.clone()))?;
+    if lease_epoch == task.lease_epoch {
+        Ok(())
+    } else {
+        Err(ScyllaOperationsError::StaleLease(lease_epoch, task.lease_epoch))
+    }
+}
+
 /// # Panics
 /// In case status is missing. However this function is to be used after `validate_status_task`
 fn prepare_status_task(mut task: Task, update_task_model: &UpdateTaskModel) -> Task {
-    task.status = update_task_model.status.clone().unwrap();
+    task.status = requested_status(&task, update_task_model.status.as_ref().unwrap());
     task.updated = Utc::now();
+    if task.status == TaskStatus::Cancelling {
+        task.cancel_requested = Some(CancelRequest {
+            requester: update_task_model.actor.clone(),
+            reason: update_task_model.reason.clone(),
+            time: task.updated,
+        });
+    }
     if let Some(metrics) = &update_task_model.metrics {
         task.metrics = Some(metrics.clone());
     }
+    if let Some(result) = &update_task_model.result {
+        task.result = Some(result.clone());
+    }
     if let Some(error) = update_task_model.error.clone() {
         if task.status == TaskStatus::Aborted {
             task.errors.push(error);
         }
     }
+    if let Some(typ) = lifecycle_history_type(&task.status) {
+        task.history.push(TaskHistory {
+            typ,
+            time: task.updated,
+            worker: task.owner.clone().unwrap_or_default(),
+            progress: Some(task.progress),
+            actor: update_task_model.actor.clone(),
+            reason: update_task_model.reason.clone(),
+        });
+    }
     task
 }
 
+/// History entry recorded when a task moves to a terminal status or its cancellation is requested.
+fn lifecycle_history_type(status: &TaskStatus) -> Option<TaskHistoryType> {
+    match status {
+        TaskStatus::Completed => Some(TaskHistoryType::Completion),
+        TaskStatus::Aborted => Some(TaskHistoryType::Abortion),
+        TaskStatus::Cancelled => Some(TaskHistoryType::Cancellation),
+        TaskStatus::Expired => Some(TaskHistoryType::Expiry),
+        TaskStatus::Cancelling => Some(TaskHistoryType::CancellationRequest),
+        TaskStatus::Ready | TaskStatus::Running | TaskStatus::Blocked => None,
+    }
+}
+
+/// History entry for a terminal status set by the monitor.
+fn monitor_history_entry(task: &Task, typ: TaskHistoryType, reason: &str) -> TaskHistory {
+    TaskHistory {
+        typ,
+        time: task.updated,
+        worker: task.owner.clone().unwrap_or_default(),
+        progress: Some(task.progress),
+        actor: Some(MONITOR_ACTOR.to_string()),
+        reason: Some(reason.to_string()),
+    }
+}
+
 /// # Errors
 /// Returns `ScyllaOperationsError`
-fn validate_yield_operation(task: &Task) -> Result<(), ScyllaOperationsError> {
+fn validate_yield_operation(task: &Task, update_task_model: &UpdateTaskModel) -> Result<(), ScyllaOperationsError> {
     if task.status == TaskStatus::Running {
-        Ok(())
+        validate_lease_owner(task, update_task_model, &UpdateOperation::Yield)
     } else {
         Err(ScyllaOperationsError::InvalidOperation(
             UpdateOperation::Yield,
@@ -65,6 +191,8 @@ fn prepare_yield_task(mut task: Task) -> Task {
         time: Utc::now(),
         worker: task.owner.clone().unwrap(),
         progress: Some(task.progress),
+        actor: None,
+        reason: None,
     };
     task.updated = Utc::now();
     task.deadline = Some(Utc::now() - Duration::seconds(1));
@@ -74,10 +202,12 @@ fn prepare_yield_task(mut task: Task) -> Task {
 
 /// # Errors
 /// Returns `ScyllaOperationsError`
+/// Heartbeats of a cancelling task are accepted, so that the worker learns of the requested cancellation.
 fn validate_heart_beat_operation(task: &Task, utm: &UpdateTaskModel) -> Result<(), ScyllaOperationsError> {
-    if task.status == TaskStatus::Running && task.owner == utm.worker {
-        Ok(())
-    } else if task.status != TaskStatus::Running {
+    let is_leased = task.status == TaskStatus::Running || task.status == TaskStatus::Cancelling;
+    if is_leased && task.owner == utm.worker {
+        validate_lease_epoch(task, utm, &UpdateOperation::HeartBeat)
+    } else if !is_leased {
         Err(ScyllaOperationsError::InvalidOperation(
             UpdateOperation::HeartBeat,
             TaskStatus::Running,
@@ -90,7 +220,7 @@ fn validate_heart_beat_operation(task: &Task, utm: &UpdateTaskModel) -> Result<(
 
 fn prepare_heart_beat_task(mut task: Task, update_task_model: &UpdateTaskModel) -> Task {
     task.updated = Utc::now();
-    task.deadline = Some(Utc::now() + Duration::seconds(update_task_model.task_timeout_in_secs.unwrap_or(10)));
+    task.deadline = Some(Utc::now() + Duration::seconds(update_task_model.task_timeout_in_secs.unwrap_or(DEFAULT_LEASE_TIMEOUT_IN_SECS)));
     if let Some(progress) = update_task_model.progress {
         task.progress = progress;
     }
@@ -108,6 +238,12 @@ fn validate_lease_operation(task: &Task, update_task_model: &UpdateTaskModel) ->
         ))
     } else if update_task_model.worker.is_none() {
         Err(ScyllaOperationsError::MandatoryFieldMissing("worker".to_string(), UpdateOperation::Lease))
+    } else if task.not_before.is_some_and(|not_before| not_before > Utc::now()) {
+        Err(ScyllaOperationsError::ValidationFailed(
+            "not_before not yet reached for lease operation".to_string(),
+        ))
+    } else if has_expired(task) {
+        Err(ScyllaOperationsError::ValidationFailed("task expired before lease operation".to_string()))
     } else {
         Ok(())
     }
@@ -119,13 +255,17 @@ fn prepare_lease_task(mut task: Task, update_task_model: &UpdateTaskModel) -> Ta
         time: Utc::now(),
         worker: update_task_model.worker.clone().unwrap(),
         progress: Some(0.0),
+        actor: None,
+        reason: None,
     };
     task.updated = Utc::now();
     task.status = TaskStatus::Running;
     task.owner = update_task_model.worker.clone();
-    task.deadline = Some(Utc::now() + Duration::seconds(update_task_model.task_timeout_in_secs.unwrap_or(10)));
+    task.deadline = Some(Utc::now() + Duration::seconds(update_task_model.task_timeout_in_secs.unwrap_or(DEFAULT_LEASE_TIMEOUT_IN_SECS)));
 
     task.history.push(task_assignment_history);
+    task.attempts = count_attempts(&task);
+    task.lease_epoch += 1;
     task
 }
 
@@ -149,27 +289,122 @@ fn validate_reset_operation(task: &Task) -> Result<(), ScyllaOperationsError> {
     }
 }
 
+/// # Errors
+/// Returns `ScyllaOperationsError`
+fn validate_amend_operation(task: &Task, update_task_model: &UpdateTaskModel) -> Result<(), ScyllaOperationsError> {
+    let operation = &update_task_model.operation;
+    let (field, value_missing) = match operation {
+        UpdateOperation::Reprioritise => ("priority", update_task_model.priority.is_none()),
+        _ => ("spec", update_task_model.spec.is_none()),
+    };
+    if task.status != TaskStatus::Ready {
+        Err(ScyllaOperationsError::InvalidOperation(
+            operation.clone(),
+            TaskStatus::Ready,
+            task.status.clone(),
+        ))
+    } else if value_missing {
+        Err(ScyllaOperationsError::MandatoryFieldMissing(field.to_string(), operation.clone()))
+    } else if update_task_model.actor.is_none() {
+        Err(ScyllaOperationsError::MandatoryFieldMissing("actor".to_string(), operation.clone()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Fields of an object spec are merged into the current spec, any other spec replaces it.
+/// # Panics
+/// In case actor, priority or spec is missing. However this function is to be used after `validate_amend_operation`
+fn prepare_amend_task(mut task: Task, update_task_model: &UpdateTaskModel) -> Task {
+    let typ = if update_task_model.operation == UpdateOperation::Reprioritise {
+        task.priority = update_task_model.priority.unwrap();
+        TaskHistoryType::Reprioritisation
+    } else {
+        match (&mut task.spec, update_task_model.spec.clone().unwrap()) {
+            (serde_json::Value::Object(spec), serde_json::Value::Object(amendment)) => spec.extend(amendment),
+            (spec, amendment) => *spec = amendment,
+        }
+        TaskHistoryType::SpecAmendment
+    };
+    task.history.push(TaskHistory {
+        typ,
+        worker: String::new(),
+        progress: None,
+        time: Utc::now(),
+        actor: update_task_model.actor.clone(),
+        reason: update_task_model.reason.clone(),
+    });
+    task.updated = Utc::now();
+    task
+}
+
+/// Every assignment in history is an attempt to process the task, unless the worker yielded the task.
+fn count_attempts(task: &Task) -> i32 {
+    let ends: Vec<&TaskHistoryType> = task
+        .history
+        .iter()
+        .map(|history_value| &history_value.typ)
+        .filter(|typ| matches!(typ, TaskHistoryType::Assignment | TaskHistoryType::Yield))
+        .collect();
+    let assignments = ends
+        .iter()
+        .enumerate()
+        .filter(|(i, typ)| **typ == &TaskHistoryType::Assignment && ends.get(i + 1) != Some(&&TaskHistoryType::Yield))
+        .count();
+    i32::try_from(assignments).unwrap_or(i32::MAX)
+}
+
+fn has_been_yielded(task: &Task) -> bool {
+    task.history.last().is_some_and(|history_value| history_value.typ == TaskHistoryType::Yield)
+}
+
+fn has_expired(task: &Task) -> bool {
+    task.expires_at.is_some_and(|expires_at| expires_at <= Utc::now())
+}
+
+/// Task which timed out on its last allowed attempt is not retried anymore.
+fn has_exhausted_attempts(task: &Task) -> bool {
+    task.max_attempts.is_some_and(|max_attempts| task.attempts >= max_attempts)
+}
+
 /// # Panics
 /// if `owner` is missing. This function is supposed to be used after `validate_reset_task`. Which has required checks
 fn prepare_reset_task(mut task: Task) -> Task {
     let task_timeout_history = TaskHistory {
-        worker: task.owner.unwrap(),
+        worker: task.owner.clone().unwrap(),
         progress: Some(task.progress),
         typ: TaskHistoryType::Timeout,
         time: Utc::now(),
+        actor: None,
+        reason: None,
     };
+    task.updated = Utc::now();
+    // a yielded task has not timed out, nor is its attempt counted
+    let yielded = has_been_yielded(&task);
+    if !yielded {
+        task.history.push(task_timeout_history);
+    }
+    if !yielded && has_exhausted_attempts(&task) {
+        task.errors.push(TaskError {
+            code: MAX_ATTEMPTS_EXCEEDED_ERROR_CODE.to_string(),
+            args: json!({ "attempts": task.attempts, "maxAttempts": task.max_attempts }),
+            description: "Task timed out on its last allowed attempt".to_string(),
+        });
+        task.status = TaskStatus::Aborted;
+        task.history
+            .push(monitor_history_entry(&task, TaskHistoryType::Abortion, MAX_ATTEMPTS_EXCEEDED_ERROR_CODE));
+        return task;
+    }
+    if has_expired(&task) {
+        // it cannot be started again
+        task.status = TaskStatus::Expired;
+        task.history.push(monitor_history_entry(&task, TaskHistoryType::Expiry, EXPIRED_REASON));
+        return task;
+    }
     task.deadline = None;
     task.owner = None;
     task.progress = 0.0;
     task.status = TaskStatus::Ready;
-    task.updated = Utc::now();
-    let last_history_entry = task.history.last();
-
-    if let Some(history_value) = last_history_entry {
-        if history_value.typ != TaskHistoryType::Yield {
-            task.history.push(task_timeout_history);
-        }
-    }
     task
 }
 
@@ -208,7 +443,7 @@ pub fn request_handler(task: Task, update_task_model: &UpdateTaskModel) -> Resul
             Ok(prepare_heart_beat_task(task, update_task_model))
         }
         UpdateOperation::Yield => {
-            validate_yield_operation(&task)?;
+            validate_yield_operation(&task, update_task_model)?;
             Ok(prepare_yield_task(task))
         }
         UpdateOperation::Lease => {
@@ -219,6 +454,10 @@ pub fn request_handler(task: Task, update_task_model: &UpdateTaskModel) -> Resul
             validate_reset_operation(&task)?;
             Ok(prepare_reset_task(task))
         }
+        UpdateOperation::Reprioritise | UpdateOperation::AmendSpec => {
+            validate_amend_operation(&task, update_task_model)?;
+            Ok(prepare_amend_task(task, update_task_model))
+        }
     }
 }
 
@@ -30,6 +30,12 @@ fn validate_status_failure_scenarios() {
         progress: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let utm_running_status = UpdateTaskModel {
         operation: UpdateOperation::Status,
@@ -40,6 +46,12 @@ fn validate_status_failure_scenarios() {
         progress: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let utm_completed_status = UpdateTaskModel {
         operation: UpdateOperation::Status,
@@ -50,6 +62,12 @@ fn validate_status_failure_scenarios() {
         progress: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let t_ready = Task {
         status: TaskStatus::Ready,
@@ -79,6 +97,12 @@ fn validate_status_failure_scenarios() {
         progress: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let t_running = Task {
         status: TaskStatus::Running,
@@ -88,7 +112,7 @@ fn validate_status_failure_scenarios() {
         validate_status_operation(&t_running, &utm_ready_status),
         Err(ScyllaOperationsError::InvalidStatusTransition(
             TaskStatus::Running,
-            vec![TaskStatus::Completed, TaskStatus::Cancelled, TaskStatus::Aborted],
+            vec![TaskStatus::Completed, TaskStatus::Cancelling, TaskStatus::Aborted],
         ))
     );
 
@@ -103,6 +127,12 @@ fn validate_status_failure_scenarios() {
         progress: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let t_aborted = Task {
         status: TaskStatus::Aborted,
@@ -120,21 +150,21 @@ fn validate_status_failure_scenarios() {
         validate_status_operation(&t_aborted, &utm_ready),
         Err(ScyllaOperationsError::TerminalTaskStatus(
             TaskStatus::Aborted,
-            vec![TaskStatus::Ready, TaskStatus::Running],
+            vec![TaskStatus::Ready, TaskStatus::Running, TaskStatus::Blocked, TaskStatus::Cancelling],
         ))
     );
     assert_eq!(
         validate_status_operation(&t_cancelled, &utm_ready),
         Err(ScyllaOperationsError::TerminalTaskStatus(
             TaskStatus::Cancelled,
-            vec![TaskStatus::Ready, TaskStatus::Running],
+            vec![TaskStatus::Ready, TaskStatus::Running, TaskStatus::Blocked, TaskStatus::Cancelling],
         ))
     );
     assert_eq!(
         validate_status_operation(&t_completed, &utm_ready),
         Err(ScyllaOperationsError::TerminalTaskStatus(
             TaskStatus::Completed,
-            vec![TaskStatus::Ready, TaskStatus::Running],
+            vec![TaskStatus::Ready, TaskStatus::Running, TaskStatus::Blocked, TaskStatus::Cancelling],
         ))
     );
 
@@ -149,6 +179,12 @@ fn validate_status_failure_scenarios() {
         progress: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let t_running = Task {
         status: TaskStatus::Running,
@@ -173,6 +209,12 @@ fn validate_state_success_scenarios() {
         progress: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let t_ready = Task {
         status: TaskStatus::Ready,
@@ -181,6 +223,15 @@ fn validate_state_success_scenarios() {
 
     validate_status_operation(&t_ready, &utm_cancelled_status).unwrap();
 
+    /*********************************/
+    //  Blocked --> Cancelled
+    let t_blocked = Task {
+        status: TaskStatus::Blocked,
+        ..Task::default()
+    };
+
+    validate_status_operation(&t_blocked, &utm_cancelled_status).unwrap();
+
     /*********************************/
     //  Running --> Cancelled
     let utm_cancelled_status = UpdateTaskModel {
@@ -192,6 +243,12 @@ fn validate_state_success_scenarios() {
         progress: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let utm_completed_status = UpdateTaskModel {
         operation: UpdateOperation::Status,
@@ -202,6 +259,12 @@ fn validate_state_success_scenarios() {
         progress: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let utm_aborted_status = UpdateTaskModel {
         operation: UpdateOperation::Status,
@@ -216,6 +279,12 @@ fn validate_state_success_scenarios() {
         progress: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let t_running = Task {
         status: TaskStatus::Running,
@@ -226,6 +295,195 @@ fn validate_state_success_scenarios() {
     validate_status_operation(&t_running, &utm_cancelled_status).unwrap();
     validate_status_operation(&t_running, &utm_completed_status).unwrap();
     validate_status_operation(&t_running, &utm_aborted_status).unwrap();
+
+    /*********************************/
+    //  Running --> Completed / Aborted by owner
+    let utm_completed_by_owner = UpdateTaskModel {
+        operation: UpdateOperation::Status,
+        status: Some(TaskStatus::Completed),
+        rn: "abc".to_string(),
+        worker: Some("worker1".to_string()),
+        lease_epoch: Some(0),
+        ..UpdateTaskModel::default()
+    };
+    let utm_aborted_by_owner = UpdateTaskModel {
+        operation: UpdateOperation::Status,
+        status: Some(TaskStatus::Aborted),
+        rn: "abc".to_string(),
+        error: Some(TaskError {
+            code: "123".to_string(),
+            args: serde_json::Value::default(),
+            description: "failed".to_string(),
+        }),
+        worker: Some("worker1".to_string()),
+        lease_epoch: Some(0),
+        ..UpdateTaskModel::default()
+    };
+    validate_status_operation(&t_running, &utm_completed_by_owner).unwrap();
+    validate_status_operation(&t_running, &utm_aborted_by_owner).unwrap();
+}
+
+#[test]
+fn validate_status_result_scenarios() {
+    let t_running = Task {
+        status: TaskStatus::Running,
+        owner: Some("worker1".to_string()),
+        ..Task::default()
+    };
+    let utm_completed = UpdateTaskModel {
+        operation: UpdateOperation::Status,
+        status: Some(TaskStatus::Completed),
+        rn: "abc".to_string(),
+        worker: Some("worker1".to_string()),
+        lease_epoch: Some(0),
+        result: Some(serde_json::json!({"output": "s3://bucket/key"})),
+        ..UpdateTaskModel::default()
+    };
+    validate_status_operation(&t_running, &utm_completed).unwrap();
+    /*********************************/
+    // result is limited in size
+    let utm_completed_large_result = UpdateTaskModel {
+        result: Some(serde_json::Value::String("a".repeat(MAX_RESULT_SIZE_IN_BYTES))),
+        ..utm_completed
+    };
+    assert_eq!(
+        validate_status_operation(&t_running, &utm_completed_large_result),
+        Err(ScyllaOperationsError::ValidationFailed(format!(
+            "result of {} bytes exceeds the limit of {MAX_RESULT_SIZE_IN_BYTES} bytes",
+            MAX_RESULT_SIZE_IN_BYTES + 2
+        )))
+    );
+    /*********************************/
+    // result is only set on completion
+    let utm_cancelled = UpdateTaskModel {
+        operation: UpdateOperation::Status,
+        status: Some(TaskStatus::Cancelled),
+        rn: "abc".to_string(),
+        result: Some(serde_json::json!({})),
+        ..UpdateTaskModel::default()
+    };
+    assert_eq!(
+        validate_status_operation(&t_running, &utm_cancelled),
+        Err(ScyllaOperationsError::ValidationFailed("result can only be set on completion".to_string()))
+    );
+    /*********************************/
+    // result is stored with the task
+    let prepared_task = prepare_status_task(
+        t_running,
+        &UpdateTaskModel {
+            result: Some(serde_json::json!({"rows": 3})),
+            ..utm_completed_large_result
+        },
+    );
+    assert_eq!(prepared_task.result, Some(serde_json::json!({"rows": 3})));
+}
+
+#[test]
+fn validate_status_lease_owner_scenarios() {
+    let t_running = Task {
+        status: TaskStatus::Running,
+        owner: Some("worker1".to_string()),
+        ..Task::default()
+    };
+    /*********************************/
+    // worker is mandatory to complete or abort a task
+    let utm_completed_without_worker = UpdateTaskModel {
+        operation: UpdateOperation::Status,
+        status: Some(TaskStatus::Completed),
+        rn: "abc".to_string(),
+        ..UpdateTaskModel::default()
+    };
+    assert_eq!(
+        validate_status_operation(&t_running, &utm_completed_without_worker),
+        Err(ScyllaOperationsError::MandatoryFieldMissing("worker".to_string(), UpdateOperation::Status))
+    );
+    /*********************************/
+    // superseded worker cannot complete or abort the task
+    let utm_completed_superseded = UpdateTaskModel {
+        operation: UpdateOperation::Status,
+        status: Some(TaskStatus::Completed),
+        rn: "abc".to_string(),
+        worker: Some("worker2".to_string()),
+        ..UpdateTaskModel::default()
+    };
+    let utm_aborted_superseded = UpdateTaskModel {
+        operation: UpdateOperation::Status,
+        status: Some(TaskStatus::Aborted),
+        rn: "abc".to_string(),
+        error: Some(TaskError {
+            code: "123".to_string(),
+            args: serde_json::Value::default(),
+            description: "failed".to_string(),
+        }),
+        worker: Some("worker2".to_string()),
+        ..UpdateTaskModel::default()
+    };
+    assert_eq!(
+        validate_status_operation(&t_running, &utm_completed_superseded),
+        Err(ScyllaOperationsError::LeaseNotOwned("worker2".to_string(), Some("worker1".to_string())))
+    );
+    assert_eq!(
+        validate_status_operation(&t_running, &utm_aborted_superseded),
+        Err(ScyllaOperationsError::LeaseNotOwned("worker2".to_string(), Some("worker1".to_string())))
+    );
+}
+
+#[test]
+fn status_cancellation_request_scenarios() {
+    let t_running = Task {
+        status: TaskStatus::Running,
+        owner: Some("worker1".to_string()),
+        lease_epoch: 1,
+        ..Task::default()
+    };
+    /*********************************/
+    // cancelling a running task requests its cancellation
+    let utm_cancel = UpdateTaskModel {
+        operation: UpdateOperation::Status,
+        status: Some(TaskStatus::Cancelled),
+        rn: "abc".to_string(),
+        actor: Some("client".to_string()),
+        reason: Some("no longer needed".to_string()),
+        ..UpdateTaskModel::default()
+    };
+    let t_cancelling = request_handler(t_running, &utm_cancel).unwrap();
+    assert_eq!(t_cancelling.status, TaskStatus::Cancelling);
+    assert_eq!(t_cancelling.owner, Some("worker1".to_string()));
+    let cancel_requested = t_cancelling.cancel_requested.clone().unwrap();
+    assert_eq!(cancel_requested.requester, Some("client".to_string()));
+    assert_eq!(cancel_requested.reason, Some("no longer needed".to_string()));
+    assert_eq!(cancel_requested.time, t_cancelling.updated);
+    assert_eq!(t_cancelling.history.last().unwrap().typ, TaskHistoryType::CancellationRequest);
+    /*********************************/
+    // only the worker can acknowledge the cancellation
+    assert_eq!(
+        validate_status_operation(&t_cancelling, &utm_cancel),
+        Err(ScyllaOperationsError::MandatoryFieldMissing("worker".to_string(), UpdateOperation::Status))
+    );
+    let utm_completed = UpdateTaskModel {
+        operation: UpdateOperation::Status,
+        status: Some(TaskStatus::Completed),
+        rn: "abc".to_string(),
+        worker: Some("worker1".to_string()),
+        lease_epoch: Some(1),
+        ..UpdateTaskModel::default()
+    };
+    assert_eq!(
+        validate_status_operation(&t_cancelling, &utm_completed),
+        Err(ScyllaOperationsError::InvalidStatusTransition(
+            TaskStatus::Cancelling,
+            vec![TaskStatus::Cancelled]
+        ))
+    );
+    let utm_acknowledged = UpdateTaskModel {
+        status: Some(TaskStatus::Cancelled),
+        actor: Some("worker1".to_string()),
+        ..utm_completed
+    };
+    let t_cancelled = request_handler(t_cancelling, &utm_acknowledged).unwrap();
+    assert_eq!(t_cancelled.status, TaskStatus::Cancelled);
+    assert_eq!(t_cancelled.history.last().unwrap().typ, TaskHistoryType::Cancellation);
+    assert_eq!(t_cancelled.history.last().unwrap().actor, Some("worker1".to_string()));
 }
 
 #[test]
@@ -246,6 +504,12 @@ fn prepare_status_task_cases() {
         progress: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: Some("worker1".to_string()),
+        reason: None,
     };
     let task = Task {
         errors: vec![TaskError {
@@ -253,12 +517,21 @@ fn prepare_status_task_cases() {
             args: serde_json::from_str("{\"a\": \"b\"}").unwrap(),
             description: "new task superceeded current one.".to_string(),
         }],
+        owner: Some("worker1".to_string()),
+        progress: 0.5,
         ..Task::default()
     };
     let prepared_task = prepare_status_task(task, &utm_aborted);
     assert_eq!(prepared_task.status, utm_aborted.status.unwrap());
     // error added to list
     assert_eq!(prepared_task.errors.len(), 2);
+    // abortion recorded in history
+    assert_eq!(prepared_task.history.len(), 1);
+    assert_eq!(prepared_task.history[0].typ, TaskHistoryType::Abortion);
+    assert_eq!(prepared_task.history[0].worker, "worker1".to_string());
+    assert_eq!(prepared_task.history[0].progress, Some(0.5));
+    assert_eq!(prepared_task.history[0].actor, Some("worker1".to_string()));
+    assert_eq!(prepared_task.history[0].time, prepared_task.updated);
 
     /*********************************/
     //  Any other cased --> error does not being added to list.
@@ -276,6 +549,12 @@ fn prepare_status_task_cases() {
         progress: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: Some("client".to_string()),
+        reason: Some("no longer needed".to_string()),
     };
     let task = Task {
         errors: vec![TaskError {
@@ -289,6 +568,12 @@ fn prepare_status_task_cases() {
     assert_eq!(prepared_task.status, utm_cancelled.status.unwrap());
     // error added to list
     assert_eq!(prepared_task.errors.len(), 1);
+    // cancellation of a task not leased recorded in history
+    assert_eq!(prepared_task.history.len(), 1);
+    assert_eq!(prepared_task.history[0].typ, TaskHistoryType::Cancellation);
+    assert_eq!(prepared_task.history[0].worker, String::new());
+    assert_eq!(prepared_task.history[0].actor, Some("client".to_string()));
+    assert_eq!(prepared_task.history[0].reason, Some("no longer needed".to_string()));
 
     /*********************************/
     //  Test metrics functionality when incoming metrics is not None
@@ -302,6 +587,12 @@ fn prepare_status_task_cases() {
         progress: None,
         task_timeout_in_secs: None,
         metrics: Some(serde_json::from_str("{\"execution_time\": 150, \"memory_usage\": \"256MB\"}").unwrap()),
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let task_with_metrics = Task {
         status: TaskStatus::Running,
@@ -312,6 +603,9 @@ fn prepare_status_task_cases() {
     assert_eq!(prepared_task_with_metrics.status, utm_with_metrics.status.unwrap());
     // metrics should be replaced by the new value
     assert_eq!(prepared_task_with_metrics.metrics, utm_with_metrics.metrics);
+    // completion recorded in history
+    assert_eq!(prepared_task_with_metrics.history.len(), 1);
+    assert_eq!(prepared_task_with_metrics.history[0].typ, TaskHistoryType::Completion);
 
     /*********************************/
     //  Test metrics functionality when incoming metrics is None (should preserve existing metrics)
@@ -325,6 +619,12 @@ fn prepare_status_task_cases() {
         progress: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let task_with_existing_metrics = Task {
         status: TaskStatus::Running,
@@ -348,6 +648,7 @@ fn validate_yield_operation_cases() {
     };
     let t_running = Task {
         status: TaskStatus::Running,
+        owner: Some("worker1".to_string()),
         ..Task::default()
     };
     let t_aborted = Task {
@@ -362,8 +663,15 @@ fn validate_yield_operation_cases() {
         status: TaskStatus::Completed,
         ..Task::default()
     };
+    let utm_yield = UpdateTaskModel {
+        operation: UpdateOperation::Yield,
+        rn: "abc".to_string(),
+        worker: Some("worker1".to_string()),
+        lease_epoch: Some(0),
+        ..UpdateTaskModel::default()
+    };
     assert_eq!(
-        validate_yield_operation(&t_ready),
+        validate_yield_operation(&t_ready, &utm_yield),
         Err(ScyllaOperationsError::InvalidOperation(
             UpdateOperation::Yield,
             TaskStatus::Running,
@@ -371,7 +679,7 @@ fn validate_yield_operation_cases() {
         ))
     );
     assert_eq!(
-        validate_yield_operation(&t_aborted),
+        validate_yield_operation(&t_aborted, &utm_yield),
         Err(ScyllaOperationsError::InvalidOperation(
             UpdateOperation::Yield,
             TaskStatus::Running,
@@ -379,7 +687,7 @@ fn validate_yield_operation_cases() {
         ))
     );
     assert_eq!(
-        validate_yield_operation(&t_cancelled),
+        validate_yield_operation(&t_cancelled, &utm_yield),
         Err(ScyllaOperationsError::InvalidOperation(
             UpdateOperation::Yield,
             TaskStatus::Running,
@@ -387,7 +695,7 @@ fn validate_yield_operation_cases() {
         ))
     );
     assert_eq!(
-        validate_yield_operation(&t_completed),
+        validate_yield_operation(&t_completed, &utm_yield),
         Err(ScyllaOperationsError::InvalidOperation(
             UpdateOperation::Yield,
             TaskStatus::Running,
@@ -395,7 +703,29 @@ fn validate_yield_operation_cases() {
         ))
     );
     // only running task can be yielded
-    validate_yield_operation(&t_running).unwrap();
+    validate_yield_operation(&t_running, &utm_yield).unwrap();
+
+    /*********************************/
+    //  Only owner can yield the task
+    let utm_yield_without_worker = UpdateTaskModel {
+        operation: UpdateOperation::Yield,
+        rn: "abc".to_string(),
+        ..UpdateTaskModel::default()
+    };
+    assert_eq!(
+        validate_yield_operation(&t_running, &utm_yield_without_worker),
+        Err(ScyllaOperationsError::MandatoryFieldMissing("worker".to_string(), UpdateOperation::Yield))
+    );
+    let utm_yield_superseded = UpdateTaskModel {
+        operation: UpdateOperation::Yield,
+        rn: "abc".to_string(),
+        worker: Some("worker2".to_string()),
+        ..UpdateTaskModel::default()
+    };
+    assert_eq!(
+        validate_yield_operation(&t_running, &utm_yield_superseded),
+        Err(ScyllaOperationsError::LeaseNotOwned("worker2".to_string(), Some("worker1".to_string())))
+    );
 }
 
 #[test]
@@ -449,6 +779,12 @@ fn validate_heart_beat_operation_cases() {
         status: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: Some(0),
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let utm_wrong_worker = UpdateTaskModel {
         operation: HeartBeat,
@@ -459,6 +795,12 @@ fn validate_heart_beat_operation_cases() {
         status: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     assert_eq!(
         validate_heart_beat_operation(&t_ready, &utm),
@@ -498,6 +840,81 @@ fn validate_heart_beat_operation_cases() {
     );
     // only running task can send heartbeat
     validate_heart_beat_operation(&t_running, &utm).unwrap();
+    // worker of cancelling task learns of the cancellation through heartbeats
+    let t_cancelling = Task {
+        status: TaskStatus::Cancelling,
+        ..t_running
+    };
+    validate_heart_beat_operation(&t_cancelling, &utm).unwrap();
+}
+
+#[test]
+fn validate_lease_epoch_cases() {
+    // worker1 leased the task twice, the first lease was reset in between
+    let t_running = Task {
+        status: TaskStatus::Running,
+        owner: Some("worker1".to_string()),
+        lease_epoch: 2,
+        ..Task::default()
+    };
+    let utm_heart_beat = |lease_epoch| UpdateTaskModel {
+        operation: UpdateOperation::HeartBeat,
+        rn: "abc".to_string(),
+        worker: Some("worker1".to_string()),
+        lease_epoch,
+        ..UpdateTaskModel::default()
+    };
+    let utm_completed = |lease_epoch| UpdateTaskModel {
+        operation: UpdateOperation::Status,
+        status: Some(TaskStatus::Completed),
+        rn: "abc".to_string(),
+        worker: Some("worker1".to_string()),
+        lease_epoch,
+        ..UpdateTaskModel::default()
+    };
+    let utm_yield = |lease_epoch| UpdateTaskModel {
+        operation: UpdateOperation::Yield,
+        rn: "abc".to_string(),
+        worker: Some("worker1".to_string()),
+        lease_epoch,
+        ..UpdateTaskModel::default()
+    };
+    /*********************************/
+    // lease epoch is mandatory
+    assert_eq!(
+        validate_heart_beat_operation(&t_running, &utm_heart_beat(None)),
+        Err(ScyllaOperationsError::MandatoryFieldMissing(
+            "lease_epoch".to_string(),
+            UpdateOperation::HeartBeat
+        ))
+    );
+    assert_eq!(
+        validate_status_operation(&t_running, &utm_completed(None)),
+        Err(ScyllaOperationsError::MandatoryFieldMissing("lease_epoch".to_string(), UpdateOperation::Status))
+    );
+    assert_eq!(
+        validate_yield_operation(&t_running, &utm_yield(None)),
+        Err(ScyllaOperationsError::MandatoryFieldMissing("lease_epoch".to_string(), UpdateOperation::Yield))
+    );
+    /*********************************/
+    // stale lease epoch is rejected even though the worker is the owner
+    assert_eq!(
+        validate_heart_beat_operation(&t_running, &utm_heart_beat(Some(1))),
+        Err(ScyllaOperationsError::StaleLease(1, 2))
+    );
+    assert_eq!(
+        validate_status_operation(&t_running, &utm_completed(Some(1))),
+        Err(ScyllaOperationsError::StaleLease(1, 2))
+    );
+    assert_eq!(
+        validate_yield_operation(&t_running, &utm_yield(Some(1))),
+        Err(ScyllaOperationsError::StaleLease(1, 2))
+    );
+    /*********************************/
+    // current lease epoch is accepted
+    validate_heart_beat_operation(&t_running, &utm_heart_beat(Some(2))).unwrap();
+    validate_status_operation(&t_running, &utm_completed(Some(2))).unwrap();
+    validate_yield_operation(&t_running, &utm_yield(Some(2))).unwrap();
 }
 
 #[test]
@@ -512,6 +929,12 @@ fn prepare_heart_beat_task_cases() {
         worker: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let task_2 = Task::default();
     let utm_with_progress = UpdateTaskModel {
@@ -523,6 +946,12 @@ fn prepare_heart_beat_task_cases() {
         worker: None,
         task_timeout_in_secs: Some(10),
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let prepared_task = prepare_heart_beat_task(task_1, &utm_without_progress);
     // just updated
@@ -570,6 +999,12 @@ fn validate_lease_operation_cases() {
         worker: Some("worker".to_string()),
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let utm_without_worker = UpdateTaskModel {
         operation: UpdateOperation::Lease,
@@ -580,6 +1015,12 @@ fn validate_lease_operation_cases() {
         worker: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     assert_eq!(
         validate_lease_operation(&t_running, &utm_without_worker),
@@ -619,6 +1060,44 @@ fn validate_lease_operation_cases() {
     );
     // only ready task with worker can be leased out
     validate_lease_operation(&t_ready, &utm_with_worker).unwrap();
+
+    /*********************************/
+    //  Task with not_before in future cannot be leased yet
+    let t_ready_not_before_future = Task {
+        status: TaskStatus::Ready,
+        not_before: Some(Utc::now() + Duration::minutes(15)),
+        ..Task::default()
+    };
+    let t_ready_not_before_past = Task {
+        status: TaskStatus::Ready,
+        not_before: Some(Utc::now() - Duration::seconds(1)),
+        ..Task::default()
+    };
+    assert_eq!(
+        validate_lease_operation(&t_ready_not_before_future, &utm_with_worker),
+        Err(ScyllaOperationsError::ValidationFailed(
+            "not_before not yet reached for lease operation".to_string()
+        ))
+    );
+    validate_lease_operation(&t_ready_not_before_past, &utm_with_worker).unwrap();
+
+    /*********************************/
+    //  Task past its expiry cannot be leased
+    let t_ready_expired = Task {
+        status: TaskStatus::Ready,
+        expires_at: Some(Utc::now() - Duration::seconds(1)),
+        ..Task::default()
+    };
+    let t_ready_not_expired = Task {
+        status: TaskStatus::Ready,
+        expires_at: Some(Utc::now() + Duration::minutes(15)),
+        ..Task::default()
+    };
+    assert_eq!(
+        validate_lease_operation(&t_ready_expired, &utm_with_worker),
+        Err(ScyllaOperationsError::ValidationFailed("task expired before lease operation".to_string()))
+    );
+    validate_lease_operation(&t_ready_not_expired, &utm_with_worker).unwrap();
 }
 
 #[test]
@@ -632,6 +1111,12 @@ fn prepare_lease_task_cases() {
         worker: Some("worker".to_string()),
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let t = Task {
         status: TaskStatus::Ready,
@@ -640,7 +1125,10 @@ fn prepare_lease_task_cases() {
             progress: Some(0.7),
             time: Utc::now() - Duration::minutes(1),
             worker: "worker1".to_string(),
+            actor: None,
+            reason: None,
         }],
+        lease_epoch: 1,
         ..Task::default()
     };
     let prepared_task = prepare_lease_task(t, &utm);
@@ -653,6 +1141,30 @@ fn prepare_lease_task_cases() {
     assert!(Utc::now() - prepared_task.history[1].time < Duration::milliseconds(1));
     assert_eq!(prepared_task.history[1].worker, "worker".to_string());
     assert_eq!(prepared_task.history[1].progress, Some(0.0)); // initial progess set as 0.0
+    assert_eq!(prepared_task.attempts, 1); // only assignments are counted as attempts
+    assert_eq!(prepared_task.lease_epoch, 2); // every lease gets a new fencing token
+
+    // assignments which have been yielded are not counted as attempts
+    let entry = |typ: TaskHistoryType| TaskHistory {
+        typ,
+        progress: None,
+        time: Utc::now(),
+        worker: "worker1".to_string(),
+        actor: None,
+        reason: None,
+    };
+    let t = Task {
+        status: TaskStatus::Ready,
+        history: vec![
+            entry(TaskHistoryType::Assignment),
+            entry(TaskHistoryType::Timeout),
+            entry(TaskHistoryType::Assignment),
+            entry(TaskHistoryType::Yield),
+            entry(TaskHistoryType::Yield),
+        ],
+        ..Task::default()
+    };
+    assert_eq!(prepare_lease_task(t, &utm).attempts, 2);
 }
 
 #[test]
@@ -757,6 +1269,8 @@ fn prepare_reset_task_cases() {
             progress: Some(0.7),
             time: Utc::now() - Duration::minutes(1),
             worker: "worker1".to_string(),
+            actor: None,
+            reason: None,
         }],
         ..Task::default()
     };
@@ -771,6 +1285,8 @@ fn prepare_reset_task_cases() {
             progress: Some(0.7),
             time: Utc::now() - Duration::seconds(2),
             worker: "worker1".to_string(),
+            actor: None,
+            reason: None,
         }],
         ..Task::default()
     };
@@ -796,6 +1312,200 @@ fn prepare_reset_task_cases() {
     assert_eq!(pt.history[0].typ, TaskHistoryType::Yield);
 }
 
+#[test]
+fn prepare_reset_task_aborts_when_attempts_exhausted() {
+    let t = Task {
+        status: TaskStatus::Running,
+        deadline: Some(Utc::now() - Duration::seconds(2)),
+        progress: 0.8,
+        owner: Some("worker2".to_string()),
+        attempts: 3,
+        max_attempts: Some(3),
+        ..Task::default()
+    };
+    let pt = prepare_reset_task(t.clone());
+    assert_eq!(pt.status, TaskStatus::Aborted);
+    assert_eq!(pt.owner, Some("worker2".to_string()));
+    assert_eq!(pt.history.len(), 2);
+    assert_eq!(pt.history[0].typ, TaskHistoryType::Timeout);
+    assert_eq!(pt.history[1].typ, TaskHistoryType::Abortion);
+    assert_eq!(pt.history[1].worker, "worker2".to_string());
+    assert_eq!(pt.history[1].actor, Some(MONITOR_ACTOR.to_string()));
+    assert_eq!(pt.history[1].reason, Some(MAX_ATTEMPTS_EXCEEDED_ERROR_CODE.to_string()));
+    assert_eq!(pt.errors.len(), 1);
+    assert_eq!(pt.errors[0].code, MAX_ATTEMPTS_EXCEEDED_ERROR_CODE.to_string());
+    assert_eq!(pt.errors[0].args, serde_json::json!({ "attempts": 3, "maxAttempts": 3 }));
+
+    // attempts left, task is retried
+    let pt = prepare_reset_task(Task { attempts: 2, ..t.clone() });
+    assert_eq!(pt.status, TaskStatus::Ready);
+    assert!(pt.errors.is_empty());
+
+    // last attempt has been yielded rather than timed out, task is retried
+    let yield_history = TaskHistory {
+        typ: TaskHistoryType::Yield,
+        progress: Some(0.8),
+        time: Utc::now() - Duration::seconds(2),
+        worker: "worker2".to_string(),
+        actor: None,
+        reason: None,
+    };
+    let pt = prepare_reset_task(Task {
+        history: vec![yield_history],
+        ..t
+    });
+    assert_eq!(pt.status, TaskStatus::Ready);
+    assert!(pt.errors.is_empty());
+    assert_eq!(pt.history.len(), 1);
+}
+
+#[test]
+fn prepare_reset_task_expires_when_expiry_passed() {
+    let t = Task {
+        status: TaskStatus::Running,
+        deadline: Some(Utc::now() - Duration::seconds(2)),
+        progress: 0.8,
+        owner: Some("worker2".to_string()),
+        expires_at: Some(Utc::now() - Duration::seconds(1)),
+        ..Task::default()
+    };
+    let pt = prepare_reset_task(t.clone());
+    assert_eq!(pt.status, TaskStatus::Expired);
+    assert_eq!(pt.history.len(), 2);
+    assert_eq!(pt.history[0].typ, TaskHistoryType::Timeout);
+    assert_eq!(pt.history[1].typ, TaskHistoryType::Expiry);
+    assert_eq!(pt.history[1].actor, Some(MONITOR_ACTOR.to_string()));
+    assert_eq!(pt.history[1].reason, Some(EXPIRED_REASON.to_string()));
+    assert!(pt.errors.is_empty());
+
+    // not yet expired, task is retried
+    let pt = prepare_reset_task(Task {
+        expires_at: Some(Utc::now() + Duration::minutes(1)),
+        ..t
+    });
+    assert_eq!(pt.status, TaskStatus::Ready);
+}
+
+#[test]
+fn validate_amend_operation_cases() {
+    let ready_task = Task {
+        status: TaskStatus::Ready,
+        ..Task::default()
+    };
+    let utm_reprioritise = UpdateTaskModel {
+        operation: UpdateOperation::Reprioritise,
+        rn: "123".to_string(),
+        actor: Some("operator".to_string()),
+        priority: Some(5),
+        ..UpdateTaskModel::default()
+    };
+    assert_eq!(validate_amend_operation(&ready_task, &utm_reprioritise), Ok(()));
+    /*********************************/
+    // only ready task can be amended
+    for status in [TaskStatus::Running, TaskStatus::Blocked, TaskStatus::Completed] {
+        let task = Task {
+            status: status.clone(),
+            ..Task::default()
+        };
+        assert_eq!(
+            validate_amend_operation(&task, &utm_reprioritise),
+            Err(ScyllaOperationsError::InvalidOperation(
+                UpdateOperation::Reprioritise,
+                TaskStatus::Ready,
+                status
+            ))
+        );
+    }
+    /*********************************/
+    // value to amend is mandatory
+    let utm_missing_priority = UpdateTaskModel {
+        priority: None,
+        spec: Some(serde_json::json!({"a": 1})),
+        ..utm_reprioritise
+    };
+    assert_eq!(
+        validate_amend_operation(&ready_task, &utm_missing_priority),
+        Err(ScyllaOperationsError::MandatoryFieldMissing(
+            "priority".to_string(),
+            UpdateOperation::Reprioritise
+        ))
+    );
+    let utm_missing_spec = UpdateTaskModel {
+        operation: UpdateOperation::AmendSpec,
+        rn: "123".to_string(),
+        actor: Some("operator".to_string()),
+        priority: Some(5),
+        ..UpdateTaskModel::default()
+    };
+    assert_eq!(
+        validate_amend_operation(&ready_task, &utm_missing_spec),
+        Err(ScyllaOperationsError::MandatoryFieldMissing("spec".to_string(), UpdateOperation::AmendSpec))
+    );
+    /*********************************/
+    // actor is mandatory to record it in history
+    let utm_missing_actor = UpdateTaskModel {
+        operation: UpdateOperation::AmendSpec,
+        rn: "123".to_string(),
+        worker: Some("operator".to_string()),
+        spec: Some(serde_json::json!({"a": 1})),
+        ..UpdateTaskModel::default()
+    };
+    assert_eq!(
+        validate_amend_operation(&ready_task, &utm_missing_actor),
+        Err(ScyllaOperationsError::MandatoryFieldMissing("actor".to_string(), UpdateOperation::AmendSpec))
+    );
+}
+
+#[test]
+fn prepare_amend_task_cases() {
+    let t = Task {
+        status: TaskStatus::Ready,
+        priority: 1,
+        spec: serde_json::json!({"account": "a", "amount": 10}),
+        ..Task::default()
+    };
+    /*********************************/
+    // reprioritise
+    let utm = UpdateTaskModel {
+        operation: UpdateOperation::Reprioritise,
+        rn: "123".to_string(),
+        actor: Some("operator".to_string()),
+        reason: Some("customer request".to_string()),
+        priority: Some(9),
+        ..UpdateTaskModel::default()
+    };
+    let updated_task = prepare_amend_task(t.clone(), &utm);
+    assert_eq!(updated_task.priority, 9);
+    assert_eq!(updated_task.spec, t.spec);
+    assert_eq!(updated_task.history.len(), 1);
+    assert_eq!(updated_task.history[0].typ, TaskHistoryType::Reprioritisation);
+    assert_eq!(updated_task.history[0].worker, String::new());
+    assert_eq!(updated_task.history[0].actor, Some("operator".to_string()));
+    assert_eq!(updated_task.history[0].reason, Some("customer request".to_string()));
+    assert!(updated_task.updated > t.updated);
+    /*********************************/
+    // fields of object spec are merged
+    let utm = UpdateTaskModel {
+        operation: UpdateOperation::AmendSpec,
+        rn: "123".to_string(),
+        actor: Some("operator".to_string()),
+        spec: Some(serde_json::json!({"amount": 20, "currency": "AUD"})),
+        ..UpdateTaskModel::default()
+    };
+    let updated_task = prepare_amend_task(t.clone(), &utm);
+    assert_eq!(updated_task.priority, 1);
+    assert_eq!(updated_task.spec, serde_json::json!({"account": "a", "amount": 20, "currency": "AUD"}));
+    assert_eq!(updated_task.history[0].typ, TaskHistoryType::SpecAmendment);
+    /*********************************/
+    // any other spec replaces it
+    let utm = UpdateTaskModel {
+        spec: Some(serde_json::json!([1, 2])),
+        ..utm
+    };
+    let updated_task = prepare_amend_task(t, &utm);
+    assert_eq!(updated_task.spec, serde_json::json!([1, 2]));
+}
+
 #[test]
 fn request_handler_cases() {
     /*********************************/
@@ -813,6 +1523,12 @@ fn request_handler_cases() {
         status: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let updated_task = request_handler(t, &utm).unwrap();
     assert_eq!(updated_task.status, TaskStatus::Running);
@@ -834,6 +1550,12 @@ fn request_handler_cases() {
         status: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let updated_task = request_handler(t, &utm).unwrap();
     assert_eq!(updated_task.status, TaskStatus::Ready);
@@ -850,12 +1572,18 @@ fn request_handler_cases() {
     let utm = UpdateTaskModel {
         operation: UpdateOperation::Yield,
         rn: "123".to_string(),
-        worker: None,
+        worker: Some("worker1".to_string()),
         error: None,
         progress: None,
         status: None,
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: Some(0),
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let updated_task = request_handler(t, &utm).unwrap();
     assert_eq!(updated_task.status, TaskStatus::Running);
@@ -878,6 +1606,12 @@ fn request_handler_cases() {
         status: None,
         task_timeout_in_secs: Some(5),
         metrics: None,
+        lease_epoch: Some(0),
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let updated_task = request_handler(t, &utm).unwrap();
     assert_eq!(updated_task.status, TaskStatus::Running);
@@ -899,6 +1633,12 @@ fn request_handler_cases() {
         status: Some(TaskStatus::Cancelled),
         task_timeout_in_secs: None,
         metrics: None,
+        lease_epoch: None,
+        priority: None,
+        spec: None,
+        result: None,
+        actor: None,
+        reason: None,
     };
     let updated_task = request_handler(t, &utm).unwrap();
     assert_eq!(updated_task.status, TaskStatus::Cancelled);
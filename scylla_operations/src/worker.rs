@@ -0,0 +1,28 @@
+//! Registration of worker processes, whose ids are issued by the database.
+use crate::error::ScyllaOperationsError;
+use chrono::Utc;
+use scylla_models::{Worker, WorkerMetadata};
+
+/// Worker to be registered, its id is assigned by the persistence layer.
+/// # Errors
+/// Returns `ScyllaOperationsError::ValidationFailed` if hostname or version is empty
+pub fn register_worker_operation(metadata: &WorkerMetadata) -> Result<Worker, ScyllaOperationsError> {
+    if metadata.hostname.is_empty() {
+        return Err(ScyllaOperationsError::ValidationFailed("Worker hostname cannot be empty".to_string()));
+    }
+    if metadata.version.is_empty() {
+        return Err(ScyllaOperationsError::ValidationFailed("Worker version cannot be empty".to_string()));
+    }
+    let now = Utc::now();
+    Ok(Worker {
+        id: String::new(),
+        hostname: metadata.hostname.clone(),
+        version: metadata.version.clone(),
+        registered: now,
+        heartbeat: now,
+        draining: false,
+    })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,35 @@
+// $coverage:ignore-start
+use crate::error::ScyllaOperationsError;
+use crate::worker::*;
+use scylla_models::WorkerMetadata;
+
+fn metadata() -> WorkerMetadata {
+    WorkerMetadata {
+        hostname: "node-1".to_string(),
+        version: "1.4.0".to_string(),
+    }
+}
+
+#[test]
+fn register_worker_operation_cases() {
+    let worker = register_worker_operation(&metadata()).unwrap();
+    assert_eq!(worker.id, String::new());
+    assert_eq!(worker.hostname, "node-1".to_string());
+    assert_eq!(worker.version, "1.4.0".to_string());
+    assert_eq!(worker.registered, worker.heartbeat);
+
+    assert_eq!(
+        register_worker_operation(&WorkerMetadata {
+            hostname: String::new(),
+            ..metadata()
+        }),
+        Err(ScyllaOperationsError::ValidationFailed("Worker hostname cannot be empty".to_string()))
+    );
+    assert_eq!(
+        register_worker_operation(&WorkerMetadata {
+            version: String::new(),
+            ..metadata()
+        }),
+        Err(ScyllaOperationsError::ValidationFailed("Worker version cannot be empty".to_string()))
+    );
+}
@@ -1,7 +1,7 @@
 import test from 'ava';
 
 import {v4 as uuid} from 'uuid';
-import Scylla, {AddTaskModel, Task, TaskBatch, TaskStatus} from "../index.js";
+import Scylla, {AddTaskModel, QueueMode, Task, TaskBatch, TaskHistoryType, TaskStatus} from "../index.js";
 
 let root_sc: Scylla | null = null;
 async function get_singleton_manager() {
@@ -35,19 +35,39 @@ test("add, lease and yield", async (t) => {
  t.is(taskAdded.rn, taskToAdd.rn);
  let leasedTask = await sc.leaseTask(taskAdded.rn, "worker");
  t.is(leasedTask.status, TaskStatus.running);
- let heartBeatTask = await sc.heartBeatTask(taskAdded.rn, "worker", 0.2);
- t.is(heartBeatTask.status, TaskStatus.running);
- t.is(heartBeatTask.progress, 0.2);
- try {
-     await sc.heartBeatTask(taskAdded.rn, "worker1", 0.3); // Only owner can extend the heartbeat
-     t.is(false, true); // if last step ran without error. test should fail
- } catch (e: any) {
-     t.is(e.message, "Validation failed: Only owner can extend the heartbeat.")
+ let heartBeat = await sc.heartBeatTask(taskAdded.rn, "worker", leasedTask.leaseEpoch, 0.2);
+ if (heartBeat.outcome !== "extended") {
+     return t.fail(`heartbeat is expected to extend the lease, got ${heartBeat.outcome}`);
  }
- let yieldedTask = await sc.yieldTask(taskAdded.rn);
+ t.is(heartBeat.task.status, TaskStatus.running);
+ t.is(heartBeat.task.progress, 0.2);
+ // Only owner can extend the heartbeat
+ t.deepEqual(await sc.heartBeatTask(taskAdded.rn, "worker1", leasedTask.leaseEpoch, 0.3), {outcome: "leaseLost", newOwner: "worker", status: TaskStatus.running});
+ let yieldedTask = await sc.yieldTask(taskAdded.rn, "worker", leasedTask.leaseEpoch);
   t.is(yieldedTask.status, TaskStatus.running); // this needs monitor to run in parallel
 })
 
+test("add, lease and heartbeat all tasks of worker", async (t) => {
+  let sc = await get_singleton_manager();
+  let rns = [uuid(), uuid()];
+  for (let rn of rns) {
+    await sc.addTask({rn, spec: {job: "1", output: "f"}, queue: "single", priority: 0.1});
+  }
+  let leased = await Promise.all(rns.map((rn) => sc.leaseTask(rn, "worker")));
+  let outcomes = await sc.heartBeatTasks("worker", [
+    {rn: leased[0].rn, leaseEpoch: leased[0].leaseEpoch, progress: 0.4},
+    {rn: leased[1].rn, leaseEpoch: leased[1].leaseEpoch - 1},
+    {rn: uuid(), leaseEpoch: 1},
+  ]);
+  t.is(outcomes[0].rn, leased[0].rn);
+  if (outcomes[0].outcome !== "extended") {
+    return t.fail(`heartbeat is expected to extend the lease, got ${outcomes[0].outcome}`);
+  }
+  t.is(outcomes[0].task.progress, 0.4);
+  t.deepEqual(outcomes[1], {rn: leased[1].rn, outcome: "leaseLost", newOwner: "worker", status: TaskStatus.running});
+  t.is(outcomes[2].outcome, "notFound");
+})
+
 test("add and cancel", async (t) => {
   let sc = await get_singleton_manager();
   let taskToAdd = {
@@ -60,7 +80,29 @@ test("add and cancel", async (t) => {
   let taskAdded: Task =await sc.addTask(taskToAdd);
 
   t.is(taskAdded.rn, taskToAdd.rn);
-  let taskCancelled = await sc.cancelTask(taskToAdd.rn);
+  let taskCancelled = await sc.cancelTask(taskToAdd.rn, "client");
+  t.is(taskCancelled.status, TaskStatus.cancelled);
+})
+
+test("add, lease, request cancellation and acknowledge", async (t) => {
+  let sc = await get_singleton_manager();
+  let taskToAdd = {
+    rn: uuid(),
+    spec: {job: "1", output: "f"},
+    queue: "single",
+    priority: 0.1
+  };
+
+  await sc.addTask(taskToAdd);
+  let leasedTask = await sc.leaseTask(taskToAdd.rn, "worker", 20);
+  let taskCancelling = await sc.cancelTask(taskToAdd.rn, "client", "no longer needed");
+  t.is(taskCancelling.status, TaskStatus.cancelling);
+  let heartBeat = await sc.heartBeatTask(taskToAdd.rn, "worker", leasedTask.leaseEpoch);
+  if (heartBeat.outcome !== "cancelled") {
+    return t.fail(`heartbeat is expected to report the cancellation, got ${heartBeat.outcome}`);
+  }
+  t.is(heartBeat.task.cancelRequested?.reason, "no longer needed");
+  let taskCancelled = await sc.acknowledgeCancellation(taskToAdd.rn, "worker", leasedTask.leaseEpoch);
   t.is(taskCancelled.status, TaskStatus.cancelled);
 })
 
@@ -78,7 +120,7 @@ test("add, lease with timeout and complete", async (t) => {
   t.is(taskAdded.rn, taskToAdd.rn);
   let leasedTask = await sc.leaseTask(taskAdded.rn, "worker", 20);
   t.is(leasedTask.status, TaskStatus.running);
-  let completedTask = await sc.completeTask(taskAdded.rn);
+  let completedTask = await sc.completeTask(taskAdded.rn, "worker", leasedTask.leaseEpoch);
   t.is(completedTask.status, TaskStatus.completed);
 })
 
@@ -116,9 +158,9 @@ test("add, lease N Tasks with timeout, complete and abort", async (t) => {
   t.is(leasedTask.length, 2);
   t.is(leasedTask[0].status, TaskStatus.running);
   t.is(leasedTask[1].status, TaskStatus.running);
-  let completedTask = await sc.completeTask(leasedTask[1].rn);
+  let completedTask = await sc.completeTask(leasedTask[1].rn, "worker", leasedTask[1].leaseEpoch);
 
-  let abortedTask = await sc.abortTask(leasedTask[0].rn, {
+  let abortedTask = await sc.abortTask(leasedTask[0].rn, "worker", leasedTask[0].leaseEpoch, {
     code: "UNIT TEST",
     args: {"key": "value"},
     description: "UNIT TEST ABORTED"
@@ -129,6 +171,119 @@ test("add, lease N Tasks with timeout, complete and abort", async (t) => {
 
 });
 
+test("add with labels and lease N Tasks by label", async (t) => {
+  let sc = await get_singleton_manager();
+  let region = uuid();
+  let taskToAdd: AddTaskModel = {
+    rn: uuid(),
+    spec: {job: "1", output: "f"},
+    queue: "single",
+    priority: 0.1,
+    labels: {region, tier: "gold"}
+  };
+
+  let taskAdded = await sc.addTask(taskToAdd);
+  t.deepEqual(taskAdded.labels, {region, tier: "gold"});
+  let tasks = await sc.getTasks({queue: "single", labels: [{key: "region", value: region}]});
+  t.is(tasks.length, 1);
+  let leasedTask = await sc.leaseNTasks("single", 2, "worker", 20, [
+    {key: "region", value: region},
+    {key: "tier", values: ["gold", "silver"]}
+  ]);
+  t.is(leasedTask.length, 1);
+  t.is(leasedTask[0].rn, taskAdded.rn);
+  await sc.completeTask(leasedTask[0].rn, "worker", leasedTask[0].leaseEpoch);
+});
+
+test("pause, drain and resume queue", async (t) => {
+  let sc = await get_singleton_manager();
+  let queue = uuid();
+  let taskToAdd: AddTaskModel = {
+    rn: uuid(),
+    spec: {job: "1", output: "g"},
+    queue,
+    priority: 0.1
+  };
+
+  t.is((await sc.getQueueState(queue)).mode, QueueMode.active);
+  let taskAdded = await sc.addTask(taskToAdd);
+  t.is((await sc.pauseQueue(queue)).mode, QueueMode.paused);
+  t.is((await sc.leaseNTasks(queue, 1, "worker")).length, 0);
+  await t.throwsAsync(sc.leaseTask(taskAdded.rn, "worker"), {message: `Queue ${queue} is paused, its tasks cannot be leased`});
+  t.is((await sc.drainQueue(queue)).mode, QueueMode.draining);
+  await t.throwsAsync(sc.addTask({...taskToAdd, rn: uuid()}), {message: `Queue ${queue} is draining, it does not accept new tasks`});
+  let leasedTask = await sc.leaseNTasks(queue, 1, "worker");
+  t.is(leasedTask.length, 1);
+  t.is((await sc.resumeQueue(queue)).mode, QueueMode.active);
+  await sc.completeTask(leasedTask[0].rn, "worker", leasedTask[0].leaseEpoch);
+});
+
+test("rate limited queue", async (t) => {
+  let sc = await get_singleton_manager();
+  let queue = uuid();
+  await sc.upsertQueue({name: queue, rateLimit: {maxStarts: 1, intervalInSecs: 3600}});
+  let taskToAdd: AddTaskModel = {
+    rn: uuid(),
+    spec: {job: "1", output: "g"},
+    queue,
+    priority: 0.1
+  };
+  await sc.addTask(taskToAdd);
+  let secondTask = await sc.addTask({...taskToAdd, rn: uuid()});
+
+  t.is((await sc.getRateLimitBudget(queue)).remaining, 1);
+  let leasedTask = await sc.leaseNTasks(queue, 2, "worker");
+  t.is(leasedTask.length, 1);
+  t.is((await sc.getRateLimitBudget(queue)).remaining, 0);
+  await t.throwsAsync(sc.leaseTask(secondTask.rn, "worker"), {message: `Queue ${queue} has reached its rate limit`});
+  await sc.completeTask(leasedTask[0].rn, "worker", leasedTask[0].leaseEpoch);
+});
+
+test("register, heartbeat and deregister worker", async (t) => {
+  let sc = await get_singleton_manager();
+  let worker = await sc.registerWorker({hostname: "node-1", version: "1.4.0"});
+  t.regex(worker.id, /^worker-\d+$/);
+  t.is(worker.hostname, "node-1");
+  t.is((await sc.heartbeatWorker(worker.id)).id, worker.id);
+  t.is((await sc.getWorker(worker.id)).version, "1.4.0");
+  t.true((await sc.getWorkers()).some((w) => w.id === worker.id));
+  await sc.deregisterWorker(worker.id);
+  await t.throwsAsync(sc.heartbeatWorker(worker.id), {message: `No worker found for ${worker.id}`});
+});
+
+test("drain and resume worker", async (t) => {
+  let sc = await get_singleton_manager();
+  let worker = await sc.registerWorker({hostname: "node-1", version: "1.4.0"});
+  let rn = uuid();
+  await sc.addTask({rn, spec: {job: "1", output: "f"}, queue: "single", priority: 0.1});
+  let leased = await sc.leaseTask(rn, worker.id, 600);
+  t.true((await sc.drainWorker(worker.id)).draining);
+  let heartBeat = await sc.heartBeatTask(rn, worker.id, leased.leaseEpoch);
+  t.is(heartBeat.outcome, "yieldRequested");
+  await t.throwsAsync(sc.leaseTask(uuid(), worker.id), {message: `Worker ${worker.id} is draining, it is not leased new tasks`});
+  await sc.yieldTask(rn, worker.id, leased.leaseEpoch);
+  t.false((await sc.resumeWorker(worker.id)).draining);
+  await sc.deregisterWorker(worker.id);
+});
+
+test("yield and reset all tasks of worker", async (t) => {
+  let sc = await get_singleton_manager();
+  let worker = await sc.registerWorker({hostname: "node-1", version: "1.4.0"});
+  let rns = [uuid(), uuid()];
+  for (let rn of rns) {
+    await sc.addTask({rn, spec: {job: "1", output: "f"}, queue: "single", priority: 0.1});
+  }
+  await sc.leaseTask(rns[0], worker.id, 600);
+  await sc.leaseTask(rns[1], `${worker.id}-dead`, 600);
+  let yielded = await sc.yieldWorkerTasks(worker.id);
+  t.deepEqual(yielded.map((task) => task.rn), [rns[0]]);
+  t.is(yielded[0].history[yielded[0].history.length - 1].typ, TaskHistoryType.yield);
+  let reset = await sc.resetWorkerTasks(`${worker.id}-dead`, "operator");
+  t.deepEqual(reset.map((task) => task.rn), [rns[1]]);
+  t.is(reset[0].status, TaskStatus.ready);
+  await sc.deregisterWorker(worker.id);
+});
+
 (() => {
   const testCases = [
     {
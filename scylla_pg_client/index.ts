@@ -1,4 +1,4 @@
-import {JsAddTaskModel, JsDbConfig, JsGetTasksModel, ScyllaManager} from "scylla_pg_js";
+import {JsAddTaskModel, JsDbConfig, JsGetTasksModel, JsQueue, ScyllaManager} from "scylla_pg_js";
 
 
 export enum TaskStatus {
@@ -6,21 +6,66 @@ export enum TaskStatus {
   ready="ready",
   cancelled="cancelled",
   completed="completed",
-  aborted="aborted"
+  aborted="aborted",
+  blocked="blocked",
+  expired="expired",
+  cancelling="cancelling"
+}
+
+export enum DependencyFailurePolicy {
+  abort = "abort",
+  cancel = "cancel"
+}
+
+export enum QueueMode {
+  active = "active",
+  paused = "paused",
+  draining = "draining"
 }
 
 export enum TaskHistoryType {
   assignment = "TaskAssignment",
   yield = "TaskYield",
-  timeout = "TaskTimeout"
+  timeout = "TaskTimeout",
+  reprioritisation = "TaskReprioritisation",
+  specAmendment = "TaskSpecAmendment",
+  completion = "TaskCompletion",
+  abortion = "TaskAbortion",
+  cancellation = "TaskCancellation",
+  expiry = "TaskExpiry",
+  cancellationRequest = "TaskCancellationRequest"
 }
 export declare type TaskHistory = {
   typ: TaskHistoryType
   worker: string
   time: string
   progress?: number
+  actor?: string
+  reason?: string
+}
+
+export declare type CancelRequest = {
+  requester?: string
+  reason?: string
+  time: string
+}
+
+export declare type HeartbeatOutcome =
+  | { outcome: "extended", task: Task }
+  | { outcome: "leaseLost", newOwner?: string, status: TaskStatus }
+  | { outcome: "cancelled", task: Task }
+  | { outcome: "yieldRequested", task: Task }
+  | { outcome: "completed" }
+  | { outcome: "notFound" };
+
+export declare type TaskHeartbeat = {
+  rn: string
+  leaseEpoch: number
+  progress?: number
 }
 
+export declare type TaskHeartbeatOutcome = { rn: string } & HeartbeatOutcome;
+
 export declare type TaskError = {
   code: string
   args: object
@@ -32,6 +77,27 @@ export declare type AddTaskModel = {
   queue: string
   spec: object
   priority: number
+  notBefore?: string
+  maxAttempts?: number
+  dependsOn?: string[]
+  onDependencyFailure?: DependencyFailurePolicy
+  expiresAt?: string
+  labels?: Record<string, string>
+  supersessionKey?: string
+  concurrencyKey?: string
+};
+
+export declare type ResubmitOverrides = {
+  spec?: object
+  queue?: string
+  priority?: number
+  maxAttempts?: number
+};
+
+export declare type LabelSelector = {
+  key: string
+  value?: string
+  values?: string[]
 };
 
 export declare type GetTaskModel = {
@@ -39,6 +105,7 @@ export declare type GetTaskModel = {
   queue?: string
   worker?: string
   limit?: number
+  labels?: LabelSelector[]
 };
 
 export declare type Task = {
@@ -55,6 +122,90 @@ export declare type Task = {
   errors: TaskError[]
   history: TaskHistory[]
   metrics?: object
+  result?: object
+  notBefore?: string
+  attempts: number
+  maxAttempts?: number
+  dependsOn: string[]
+  onDependencyFailure: DependencyFailurePolicy
+  expiresAt?: string
+  labels: Record<string, string>
+  supersessionKey?: string
+  concurrencyKey?: string
+  previousRn?: string
+  resubmittedAs?: string
+  cancelRequested?: CancelRequest
+  version: number
+  leaseEpoch: number
+  effectivePriority?: number
+};
+
+export declare type PriorityAging = {
+  secondsPerPoint: number
+  maxBoost: number
+};
+
+export declare type RateLimit = {
+  maxStarts: number
+  intervalInSecs: number
+};
+
+export declare type Queue = {
+  name: string
+  maxAttempts?: number
+  priorityAging?: PriorityAging
+  leaseTimeoutInSecs?: number
+  retentionPeriodInSecs?: number
+  maxPriority?: number
+  maxRunningTasks?: number
+  maxRunningTasksPerKey?: number
+  rateLimit?: RateLimit
+  description?: string
+};
+
+export declare type QueueState = {
+  name: string
+  mode: QueueMode
+};
+
+export declare type RateLimitBudget = {
+  name: string
+  remaining: number
+  availableAt: string
+};
+
+export declare type ScheduleModel = {
+  name: string
+  cron: string
+  timezone?: string
+  queue: string
+  spec: object
+  priority: number
+};
+
+export declare type Schedule = {
+  name: string
+  cron: string
+  timezone: string
+  queue: string
+  spec: object
+  priority: number
+  created: string
+  lastFired?: string
+};
+
+export declare type WorkerMetadata = {
+  hostname: string
+  version: string
+};
+
+export declare type Worker = {
+  id: string
+  hostname: string
+  version: string
+  registered: string
+  heartbeat: string
+  draining: boolean
 };
 
 export declare type TaskBatch = {
@@ -75,6 +226,8 @@ export declare type DbConfig = {
   pgPassword: string
   pgDatabase: string
   pgPoolSize: number
+  requireRegisteredQueues?: boolean
+  requireRegisteredWorkers?: boolean
 };
 
 class Scylla {
@@ -106,6 +259,17 @@ class Scylla {
     let response = await this.scyllaManager.addTask(atm);
     return JSON.parse(response);
   }
+  public async supersedeTask(addTaskModel: AddTaskModel, cancelRunning?: boolean): Promise<Task> {
+    if (!addTaskModel || !addTaskModel.spec) {
+      throw Error ( "Invalid argument. addTaskModel.spec cannot be undefined" );
+    }
+    let atm: JsAddTaskModel = {
+      ...addTaskModel,
+      spec: JSON.stringify(addTaskModel.spec),
+    }
+    let response = await this.scyllaManager.supersedeTask(atm, cancelRunning);
+    return JSON.parse(response);
+  }
   public async addTasks(addTaskModels: AddTaskModel[]): Promise<TaskBatchWithInvalidSpecs> {
     if (!addTaskModels || addTaskModels.length === 0) {
       return { inserted: [], failedToInsert: [], invalidSpecs: [] };
@@ -141,37 +305,167 @@ class Scylla {
     return JSON.parse(response);
   }
 
-    public async leaseNTasks(queue: string, limit: number, worker: string, taskTimeOutInSecs?: number): Promise<Task[]> {
-      let response = await this.scyllaManager.leaseNTasks(queue, limit, worker, taskTimeOutInSecs);
+    public async leaseNTasks(queue: string, limit: number, worker: string, taskTimeOutInSecs?: number, labels?: LabelSelector[]): Promise<Task[]> {
+      let response = await this.scyllaManager.leaseNTasks(queue, limit, worker, taskTimeOutInSecs, labels);
       return JSON.parse(response);
     }
 
-  public async heartBeatTask(rn: string, worker: string, progress?: number, taskTimeOutInSecs?: number): Promise<Task> {
-    let response = await this.scyllaManager.heartBeatTask(rn, worker, progress, taskTimeOutInSecs);
+  public async heartBeatTask(rn: string, worker: string, leaseEpoch: number, progress?: number, taskTimeOutInSecs?: number): Promise<HeartbeatOutcome> {
+    let response = await this.scyllaManager.heartBeatTask(rn, worker, leaseEpoch, progress, taskTimeOutInSecs);
+    return JSON.parse(response);
+  }
+
+  public async heartBeatTasks(worker: string, heartbeats: TaskHeartbeat[], taskTimeOutInSecs?: number): Promise<TaskHeartbeatOutcome[]> {
+    let response = await this.scyllaManager.heartBeatTasks(worker, heartbeats, taskTimeOutInSecs);
+    return JSON.parse(response);
+  }
+
+  public async cancelTask(rn: string, actor: string, reason?: string): Promise<Task> {
+    let response = await this.scyllaManager.cancelTask(rn, actor, reason);
+    return JSON.parse(response);
+  }
+
+  public async acknowledgeCancellation(rn: string, worker: string, leaseEpoch: number): Promise<Task> {
+    let response = await this.scyllaManager.acknowledgeCancellation(rn, worker, leaseEpoch);
+    return JSON.parse(response);
+  }
+
+  public async resubmitTask(rn: string, newRn: string, overrides: ResubmitOverrides = {}): Promise<Task> {
+    let response = await this.scyllaManager.resubmitTask(rn, newRn, {
+      ...overrides,
+      spec: overrides.spec === undefined ? undefined : JSON.stringify(overrides.spec),
+    });
     return JSON.parse(response);
   }
 
-  public async cancelTask(rn: string): Promise<Task> {
-    let response = await this.scyllaManager.cancelTask(rn);
+  public async reprioritiseTask(rn: string, priority: number, actor: string): Promise<Task> {
+    let response = await this.scyllaManager.reprioritiseTask(rn, priority, actor);
     return JSON.parse(response);
   }
 
-  public async completeTask(rn: string, metrics?: string): Promise<Task> {
-    let response = await this.scyllaManager.completeTask(rn, metrics);
+  public async amendTaskSpec(rn: string, spec: object, actor: string): Promise<Task> {
+    let response = await this.scyllaManager.amendTaskSpec(rn, JSON.stringify(spec), actor);
     return JSON.parse(response);
   }
 
-  public async yieldTask(rn: string): Promise<Task> {
-    let response = await this.scyllaManager.yieldTask(rn);
+  public async completeTask(rn: string, worker: string, leaseEpoch: number, metrics?: string, result?: object): Promise<Task> {
+    let response = await this.scyllaManager.completeTask(rn, worker, leaseEpoch, metrics, result === undefined ? undefined : JSON.stringify(result));
     return JSON.parse(response);
   }
 
-  public async abortTask(rn: string, taskError: TaskError): Promise<Task> {
+  public async yieldTask(rn: string, worker: string, leaseEpoch: number): Promise<Task> {
+    let response = await this.scyllaManager.yieldTask(rn, worker, leaseEpoch);
+    return JSON.parse(response);
+  }
+
+  public async yieldWorkerTasks(worker: string): Promise<Task[]> {
+    let response = await this.scyllaManager.yieldWorkerTasks(worker);
+    return JSON.parse(response);
+  }
+
+  public async resetWorkerTasks(worker: string, actor: string): Promise<Task[]> {
+    let response = await this.scyllaManager.resetWorkerTasks(worker, actor);
+    return JSON.parse(response);
+  }
+
+  public async abortTask(rn: string, worker: string, leaseEpoch: number, taskError: TaskError): Promise<Task> {
     if (!taskError || !taskError.args){
       throw Error ( "Invalid argument. taskError.args cannot be undefined" );
     }
-    let response = await this.scyllaManager.abortTask(rn, {...taskError, args: JSON.stringify(taskError.args)});
+    let response = await this.scyllaManager.abortTask(rn, worker, leaseEpoch, {...taskError, args: JSON.stringify(taskError.args)});
+    return JSON.parse(response);
+  }
+
+  public async upsertQueue(queue: Queue): Promise<Queue> {
+    let response = await this.scyllaManager.upsertQueue(queue as JsQueue);
+    return JSON.parse(response);
+  }
+
+  public async getQueue(name: string): Promise<Queue> {
+    let response = await this.scyllaManager.getQueue(name);
+    return JSON.parse(response);
+  }
+
+  public async getRateLimitBudget(name: string): Promise<RateLimitBudget> {
+    let response = await this.scyllaManager.getRateLimitBudget(name);
+    return JSON.parse(response);
+  }
+
+  public async pauseQueue(name: string): Promise<QueueState> {
+    let response = await this.scyllaManager.pauseQueue(name);
+    return JSON.parse(response);
+  }
+
+  public async drainQueue(name: string): Promise<QueueState> {
+    let response = await this.scyllaManager.drainQueue(name);
     return JSON.parse(response);
   }
+
+  public async resumeQueue(name: string): Promise<QueueState> {
+    let response = await this.scyllaManager.resumeQueue(name);
+    return JSON.parse(response);
+  }
+
+  public async getQueueState(name: string): Promise<QueueState> {
+    let response = await this.scyllaManager.getQueueState(name);
+    return JSON.parse(response);
+  }
+
+  public async upsertSchedule(scheduleModel: ScheduleModel): Promise<Schedule> {
+    if (!scheduleModel || !scheduleModel.spec) {
+      throw Error ( "Invalid argument. scheduleModel.spec cannot be undefined" );
+    }
+    let response = await this.scyllaManager.upsertSchedule({...scheduleModel, spec: JSON.stringify(scheduleModel.spec)});
+    return JSON.parse(response);
+  }
+
+  public async getSchedule(name: string): Promise<Schedule> {
+    let response = await this.scyllaManager.getSchedule(name);
+    return JSON.parse(response);
+  }
+
+  public async getSchedules(): Promise<Schedule[]> {
+    let response = await this.scyllaManager.getSchedules();
+    return JSON.parse(response);
+  }
+
+  public async deleteSchedule(name: string): Promise<number> {
+    let response = await this.scyllaManager.deleteSchedule(name);
+    return JSON.parse(response);
+  }
+
+  public async registerWorker(metadata: WorkerMetadata): Promise<Worker> {
+    let response = await this.scyllaManager.registerWorker(metadata);
+    return JSON.parse(response);
+  }
+
+  public async heartbeatWorker(id: string): Promise<Worker> {
+    let response = await this.scyllaManager.heartbeatWorker(id);
+    return JSON.parse(response);
+  }
+
+  public async getWorker(id: string): Promise<Worker> {
+    let response = await this.scyllaManager.getWorker(id);
+    return JSON.parse(response);
+  }
+
+  public async getWorkers(): Promise<Worker[]> {
+    let response = await this.scyllaManager.getWorkers();
+    return JSON.parse(response);
+  }
+
+  public async drainWorker(id: string): Promise<Worker> {
+    let response = await this.scyllaManager.drainWorker(id);
+    return JSON.parse(response);
+  }
+
+  public async resumeWorker(id: string): Promise<Worker> {
+    let response = await this.scyllaManager.resumeWorker(id);
+    return JSON.parse(response);
+  }
+
+  public async deregisterWorker(id: string): Promise<void> {
+    await this.scyllaManager.deregisterWorker(id);
+  }
 }
 export default Scylla;
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS public.queue
+(
+    data jsonb NOT NULL
+);
+
+
+CREATE UNIQUE INDEX IF NOT EXISTS queue_data_name_idx
+    ON public.queue USING btree
+    ((data ->> 'name'::text) ASC NULLS LAST);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS public.schedule
+(
+    data jsonb NOT NULL
+);
+
+
+CREATE UNIQUE INDEX IF NOT EXISTS schedule_data_name_idx
+    ON public.schedule USING btree
+    ((data ->> 'name'::text) ASC NULLS LAST);
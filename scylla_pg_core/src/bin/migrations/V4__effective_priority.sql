@@ -0,0 +1,12 @@
+-- Priority of the task raised by one point for every `secondsPerPoint` it has been waiting, up to `maxBoost` points,
+-- when its queue has a priority aging policy.
+CREATE OR REPLACE FUNCTION public.effective_priority(task jsonb, queue jsonb)
+    RETURNS int
+    LANGUAGE sql
+    STABLE
+AS $$
+    SELECT (task ->> 'priority')::int + COALESCE(LEAST(
+        GREATEST(floor(extract(epoch FROM now() - GREATEST((task ->> 'created')::timestamptz, (task ->> 'notBefore')::timestamptz))
+            / NULLIF((queue #>> '{priorityAging,secondsPerPoint}')::int, 0)), 0)::int,
+        (queue #>> '{priorityAging,maxBoost}')::int), 0)
+$$;
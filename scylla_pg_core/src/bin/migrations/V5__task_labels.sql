@@ -0,0 +1,2 @@
+-- Serves label selectors of task queries and leases, which match labels by containment.
+CREATE INDEX IF NOT EXISTS task_labels_idx ON task USING GIN ((data -> 'labels') jsonb_path_ops);
@@ -0,0 +1,3 @@
+-- Serves lookup of the tasks of a queue superseded by a newer submission.
+CREATE INDEX IF NOT EXISTS task_supersession_key_idx ON task USING btree ((data ->> 'queue'), (data ->> 'supersessionKey'))
+    WHERE data ->> 'supersessionKey' IS NOT NULL;
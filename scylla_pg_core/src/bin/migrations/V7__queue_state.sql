@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS public.queue_state
+(
+    data jsonb NOT NULL
+);
+
+
+CREATE UNIQUE INDEX IF NOT EXISTS queue_state_data_name_idx
+    ON public.queue_state USING btree
+    ((data ->> 'name'::text) ASC NULLS LAST);
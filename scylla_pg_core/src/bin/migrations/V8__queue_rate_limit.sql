@@ -0,0 +1,25 @@
+-- Token buckets of rate limited queues, shared by all workers.
+CREATE TABLE IF NOT EXISTS public.queue_rate_limit
+(
+    data jsonb NOT NULL
+);
+
+
+CREATE UNIQUE INDEX IF NOT EXISTS queue_rate_limit_data_name_idx
+    ON public.queue_rate_limit USING btree
+    ((data ->> 'name'::text) ASC NULLS LAST);
+
+-- Tokens left in the bucket of the queue, refilled by `maxStarts` every `intervalInSecs` since it was last taken from,
+-- up to `maxStarts` tokens. Null when the queue is not rate limited.
+CREATE OR REPLACE FUNCTION public.rate_limit_tokens(queue jsonb, bucket jsonb)
+    RETURNS float8
+    LANGUAGE sql
+    STABLE
+AS $$
+    SELECT CASE WHEN queue #>> '{rateLimit,maxStarts}' IS NULL THEN NULL ELSE LEAST(
+        (queue #>> '{rateLimit,maxStarts}')::float8,
+        COALESCE((bucket ->> 'tokens')::float8, (queue #>> '{rateLimit,maxStarts}')::float8)
+            + GREATEST(extract(epoch FROM statement_timestamp() - (bucket ->> 'refilled')::timestamptz), 0)
+            * (queue #>> '{rateLimit,maxStarts}')::float8 / (queue #>> '{rateLimit,intervalInSecs}')::float8)
+    END
+$$;
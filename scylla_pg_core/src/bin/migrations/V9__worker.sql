@@ -0,0 +1,12 @@
+-- Ids of worker processes are issued from the sequence, e.g. `worker-27`.
+CREATE SEQUENCE IF NOT EXISTS public.worker_id_seq;
+
+CREATE TABLE IF NOT EXISTS public.worker
+(
+    data jsonb NOT NULL
+);
+
+
+CREATE UNIQUE INDEX IF NOT EXISTS worker_data_id_idx
+    ON public.worker USING btree
+    ((data ->> 'id'::text) ASC NULLS LAST);
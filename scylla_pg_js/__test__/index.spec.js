@@ -35,16 +35,14 @@ test("add, lease and yield", async (t) => {
     t.is(taskAdded.rn, taskToAdd.rn);
     let leasedTask = JSON.parse(await sc.leaseTask(taskAdded.rn, "worker"));
     t.is(leasedTask.status, "running");
-    let heartBeatTask = JSON.parse(await sc.heartBeatTask(taskAdded.rn, "worker", 0.2));
-    t.is(heartBeatTask.status, "running");
-    t.is(heartBeatTask.progress, 0.2);
-    try {
-        await sc.heartBeatTask(taskAdded.rn, "worker1", 0.3);
-        t.is(false, true); // if last step ran without error. test should fail
-    } catch (e) {
-        t.is(e.message, "Validation failed: Only owner can extend the heartbeat.")
-    }
-    let yieldedTask = JSON.parse(await sc.yieldTask(taskAdded.rn));
+    let heartBeat = JSON.parse(await sc.heartBeatTask(taskAdded.rn, "worker", leasedTask.leaseEpoch, 0.2));
+    t.is(heartBeat.outcome, "extended");
+    t.is(heartBeat.task.status, "running");
+    t.is(heartBeat.task.progress, 0.2);
+    // Only owner can extend the heartbeat
+    let lostHeartBeat = JSON.parse(await sc.heartBeatTask(taskAdded.rn, "worker1", leasedTask.leaseEpoch, 0.3));
+    t.deepEqual(lostHeartBeat, {outcome: "leaseLost", newOwner: "worker", status: "running"});
+    let yieldedTask = JSON.parse(await sc.yieldTask(taskAdded.rn, "worker", leasedTask.leaseEpoch));
     t.is(yieldedTask.status, "running"); // this needs monitor to run in parallel
 })
 
@@ -60,7 +58,7 @@ test("add and cancel", async (t) => {
     let taskAdded = JSON.parse(await sc.addTask(taskToAdd));
 
     t.is(taskAdded.rn, taskToAdd.rn);
-    let taskCancelled = JSON.parse(await sc.cancelTask(taskToAdd.rn));
+    let taskCancelled = JSON.parse(await sc.cancelTask(taskToAdd.rn, "client"));
     t.is(taskCancelled.status, "cancelled");
 })
 
@@ -78,7 +76,7 @@ test("add, lease with timeout and complete", async (t) => {
     t.is(taskAdded.rn, taskToAdd.rn);
     let leasedTask = JSON.parse(await sc.leaseTask(taskAdded.rn, "worker", 20));
     t.is(leasedTask.status, "running");
-    let completedTask = JSON.parse(await sc.completeTask(taskAdded.rn));
+    let completedTask = JSON.parse(await sc.completeTask(taskAdded.rn, "worker", leasedTask.leaseEpoch));
     t.is(completedTask.status, "completed");
 })
 
@@ -106,8 +104,8 @@ test("add, lease N Tasks with timeout, complete and abort", async (t) => {
     t.is(leasedTask.length, 2);
     t.is(leasedTask[0].status, "running");
     t.is(leasedTask[1].status, "running");
-    let completedTask = JSON.parse(await sc.completeTask(leasedTask[1].rn));
-    let abortedTask = JSON.parse(await sc.abortTask(leasedTask[0].rn, {
+    let completedTask = JSON.parse(await sc.completeTask(leasedTask[1].rn, "worker", leasedTask[1].leaseEpoch));
+    let abortedTask = JSON.parse(await sc.abortTask(leasedTask[0].rn, "worker", leasedTask[0].leaseEpoch, {
         code: "UNIT TEST",
         args: JSON.stringify({"key": "value"}),
         description: "UNIT TEST ABORTED"
@@ -8,18 +8,75 @@ export interface JsAddTaskModel {
   spec: string
   priority: number
   queue: string
+  notBefore?: string
+  maxAttempts?: number
+  dependsOn?: Array<string>
+  onDependencyFailure?: string
+  expiresAt?: string
+  labels?: Record<string, string>
+  supersessionKey?: string
+  concurrencyKey?: string
+}
+export interface JsResubmitOverrides {
+  spec?: string
+  queue?: string
+  priority?: number
+  maxAttempts?: number
 }
 export interface JsGetTasksModel {
   worker?: string
   status?: string
   limit?: number
   queue?: string
+  labels?: Array<JsLabelSelector>
+}
+export interface JsLabelSelector {
+  key: string
+  value?: string
+  values?: Array<string>
+}
+export interface JsPriorityAging {
+  secondsPerPoint: number
+  maxBoost: number
+}
+export interface JsRateLimit {
+  maxStarts: number
+  intervalInSecs: number
+}
+export interface JsQueue {
+  name: string
+  maxAttempts?: number
+  priorityAging?: JsPriorityAging
+  leaseTimeoutInSecs?: number
+  retentionPeriodInSecs?: number
+  maxPriority?: number
+  maxRunningTasks?: number
+  maxRunningTasksPerKey?: number
+  rateLimit?: JsRateLimit
+  description?: string
+}
+export interface JsSchedule {
+  name: string
+  cron: string
+  timezone?: string
+  queue: string
+  spec: string
+  priority: number
 }
 export interface JsTaskError {
   code: string
   args: string
   description: string
 }
+export interface JsTaskHeartbeat {
+  rn: string
+  leaseEpoch: number
+  progress?: number
+}
+export interface JsWorkerMetadata {
+  hostname: string
+  version: string
+}
 export interface JsDbConfig {
   pgHost: string
   pgPort: number
@@ -27,6 +84,10 @@ export interface JsDbConfig {
   pgPassword: string
   pgDatabase: string
   pgPoolSize: number
+  /** Tasks are accepted only for queues registered with `upsert_queue` */
+  requireRegisteredQueues?: boolean
+  /** Tasks are leased only by workers registered with `register_worker` */
+  requireRegisteredWorkers?: boolean
 }
 export class ScyllaManager {
   /**
@@ -49,6 +110,7 @@ export class ScyllaManager {
    * Convert rust error into `napi::Error`
    */
   addTask(jsAtm: JsAddTaskModel): Promise<string>
+  supersedeTask(jsAtm: JsAddTaskModel, cancelRunning?: boolean | undefined | null): Promise<string>
   /**
    * # Errors
    * Convert rust error into `napi::Error`
@@ -59,30 +121,143 @@ export class ScyllaManager {
    * Convert rust error into `napi::Error`
    */
   leaseTask(rn: string, worker: string, taskTimeoutInSecs?: number | undefined | null): Promise<string>
-  leaseNTasks(queue: string, limit: number, worker: string, taskTimeoutInSecs?: number | undefined | null): Promise<string>
+  leaseNTasks(queue: string, limit: number, worker: string, taskTimeoutInSecs?: number | undefined | null, labels?: Array<JsLabelSelector> | undefined | null): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  yieldTask(rn: string, worker: string, leaseEpoch: number): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  yieldWorkerTasks(worker: string): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  resetWorkerTasks(worker: string, actor: string): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  completeTask(rn: string, worker: string, leaseEpoch: number, metrics?: string | undefined | null, result?: string | undefined | null): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  cancelTask(rn: string, actor: string, reason?: string | undefined | null): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  acknowledgeCancellation(rn: string, worker: string, leaseEpoch: number): Promise<string>
+  resubmitTask(rn: string, newRn: string, jsOverrides?: JsResubmitOverrides | undefined | null): Promise<string>
+  reprioritiseTask(rn: string, priority: number, actor: string): Promise<string>
+  amendTaskSpec(rn: string, spec: string, actor: string): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  abortTask(rn: string, worker: string, leaseEpoch: number, jsError: JsTaskError): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  heartBeatTask(rn: string, worker: string, leaseEpoch: number, progress?: number | undefined | null, taskTimeoutInSecs?: number | undefined | null): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  heartBeatTasks(worker: string, jsHeartbeats: Array<JsTaskHeartbeat>, taskTimeoutInSecs?: number | undefined | null): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  upsertQueue(jsQueue: JsQueue): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  getQueue(name: string): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  getRateLimitBudget(name: string): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  pauseQueue(name: string): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  drainQueue(name: string): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  resumeQueue(name: string): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  getQueueState(name: string): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  upsertSchedule(jsSchedule: JsSchedule): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  getSchedule(name: string): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  getSchedules(): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  deleteSchedule(name: string): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  registerWorker(jsMetadata: JsWorkerMetadata): Promise<string>
+  /**
+   * # Errors
+   * Convert rust error into `napi::Error`
+   */
+  heartbeatWorker(id: string): Promise<string>
   /**
    * # Errors
    * Convert rust error into `napi::Error`
    */
-  yieldTask(rn: string): Promise<string>
+  getWorker(id: string): Promise<string>
   /**
    * # Errors
    * Convert rust error into `napi::Error`
    */
-  completeTask(rn: string, metrics?: string | undefined | null): Promise<string>
+  getWorkers(): Promise<string>
   /**
    * # Errors
    * Convert rust error into `napi::Error`
    */
-  cancelTask(rn: string): Promise<string>
+  drainWorker(id: string): Promise<string>
   /**
    * # Errors
    * Convert rust error into `napi::Error`
    */
-  abortTask(rn: string, jsError: JsTaskError): Promise<string>
+  resumeWorker(id: string): Promise<string>
   /**
    * # Errors
    * Convert rust error into `napi::Error`
    */
-  heartBeatTask(rn: string, worker: string, progress?: number | undefined | null, taskTimeoutInSecs?: number | undefined | null): Promise<string>
+  deregisterWorker(id: string): Promise<string>
 }
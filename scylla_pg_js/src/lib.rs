@@ -3,14 +3,19 @@ mod models;
 mod validator;
 
 use napi_derive::napi;
-use scylla_models::{AddTaskModel, GetTaskModel, TaskError};
+use scylla_models::{
+    AddTaskModel, DependencyFailurePolicy, GetTaskModel, LabelSelector, Queue, RateLimit, ResubmitOverrides, Schedule, TaskError, TaskHeartbeat, WorkerMetadata,
+};
 use scylla_pg_core::config::PGConfig;
 use scylla_pg_lib::manager::PgManager;
 use std::fmt::Display;
 
 use crate::validator::validate_pool_size;
-use models::{JsAddTaskModel, JsGetTasksModel, JsTaskError};
-use validator::{validate_json, validate_port, validate_status, JSScyllaError};
+use models::{JsAddTaskModel, JsGetTasksModel, JsLabelSelector, JsQueue, JsResubmitOverrides, JsSchedule, JsTaskError, JsTaskHeartbeat, JsWorkerMetadata};
+use validator::{
+    validate_date_time, validate_dependency_failure_policy, validate_depends_on, validate_json, validate_label_selector, validate_max_attempts, validate_port,
+    validate_priority_aging, validate_status, JSScyllaError,
+};
 
 #[napi(object)]
 pub struct JsDbConfig {
@@ -20,6 +25,10 @@ pub struct JsDbConfig {
     pub pg_password: String,
     pub pg_database: String,
     pub pg_pool_size: u32,
+    /// Tasks are accepted only for queues registered with `upsert_queue`
+    pub require_registered_queues: Option<bool>,
+    /// Tasks are leased only by workers registered with `register_worker`
+    pub require_registered_workers: Option<bool>,
 }
 macro_rules! map_lib_response {
     ($task_result: expr) => {
@@ -51,7 +60,10 @@ impl ScyllaManager {
             pg_pool_size,
         };
         Ok(Self {
-            pg_manager: PgManager::from_config(&pg_config).map_err(map_error_to_napi_error)?,
+            pg_manager: PgManager::from_config(&pg_config)
+                .map_err(map_error_to_napi_error)?
+                .require_registered_queues(js_db_config.require_registered_queues.unwrap_or(false))
+                .require_registered_workers(js_db_config.require_registered_workers.unwrap_or(false)),
         })
     }
     /// # Errors
@@ -75,6 +87,7 @@ impl ScyllaManager {
             status: status_val,
             limit: js_gtm.limit,
             queue: js_gtm.queue,
+            labels: map_label_selectors(js_gtm.labels)?,
         };
         let task_result = self.pg_manager.fetch_tasks(gtm).await;
         map_lib_response!(task_result)
@@ -83,32 +96,25 @@ impl ScyllaManager {
     /// Convert rust error into `napi::Error`
     #[napi]
     pub async fn add_task(&self, js_atm: JsAddTaskModel) -> napi::Result<String> {
-        let spec = validate_json(js_atm.spec.as_str(), "spec")?;
-        let atm = AddTaskModel {
-            rn: js_atm.rn,
-            priority: js_atm.priority,
-            spec,
-            queue: js_atm.queue,
-        };
+        let atm = map_add_task_model(js_atm)?;
         let task_result = self.pg_manager.insert_task(atm).await;
         map_lib_response!(task_result)
     }
     /// # Errors
     /// Convert rust error into `napi::Error`
     #[napi]
+    pub async fn supersede_task(&self, js_atm: JsAddTaskModel, cancel_running: Option<bool>) -> napi::Result<String> {
+        let atm = map_add_task_model(js_atm)?;
+        let task_result = self.pg_manager.supersede_task(atm, cancel_running.unwrap_or(false)).await;
+        map_lib_response!(task_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
     pub async fn add_tasks(&self, js_atms: Vec<JsAddTaskModel>) -> napi::Result<String> {
         let atms = js_atms
-            .iter()
-            .map(|js_atm| {
-                let spec = validate_json(js_atm.spec.as_str(), "spec")?;
-                let atm = AddTaskModel {
-                    rn: js_atm.rn.clone(),
-                    priority: js_atm.priority,
-                    spec,
-                    queue: js_atm.queue.clone(),
-                };
-                Ok(atm)
-            })
+            .into_iter()
+            .map(map_add_task_model)
             .collect::<Result<Vec<AddTaskModel>, JSScyllaError>>()?;
 
         let tasks_result = self.pg_manager.batch_insert_tasks(atms).await;
@@ -122,56 +128,349 @@ impl ScyllaManager {
         map_lib_response!(task_result)
     }
 
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn lease_n_tasks(
+        &self,
+        queue: String,
+        limit: i32,
+        worker: String,
+        task_timeout_in_secs: Option<i64>,
+        labels: Option<Vec<JsLabelSelector>>,
+    ) -> napi::Result<String> {
+        let labels = map_label_selectors(labels)?;
+        let task_result = self.pg_manager.lease_n_tasks(queue, limit, worker, task_timeout_in_secs, labels).await;
+        map_lib_response!(task_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn yield_task(&self, rn: String, worker: String, lease_epoch: i64) -> napi::Result<String> {
+        let task_result = self.pg_manager.yield_task(rn, worker, lease_epoch).await;
+        map_lib_response!(task_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
     #[napi]
-    pub async fn lease_n_tasks(&self, queue: String, limit: i32, worker: String, task_timeout_in_secs: Option<i64>) -> napi::Result<String> {
-        let task_result = self.pg_manager.lease_n_tasks(queue, limit, worker, task_timeout_in_secs).await;
+    pub async fn yield_worker_tasks(&self, worker: String) -> napi::Result<String> {
+        let task_result = self.pg_manager.yield_worker_tasks(worker).await;
         map_lib_response!(task_result)
     }
     /// # Errors
     /// Convert rust error into `napi::Error`
     #[napi]
-    pub async fn yield_task(&self, rn: String) -> napi::Result<String> {
-        let task_result = self.pg_manager.yield_task(rn).await;
+    pub async fn reset_worker_tasks(&self, worker: String, actor: String) -> napi::Result<String> {
+        let task_result = self.pg_manager.reset_worker_tasks(worker, actor).await;
         map_lib_response!(task_result)
     }
     /// # Errors
     /// Convert rust error into `napi::Error`
     #[napi]
-    pub async fn complete_task(&self, rn: String, metrics: Option<String>) -> napi::Result<String> {
-        let task_result = self.pg_manager.complete_task(rn, metrics).await;
+    pub async fn complete_task(&self, rn: String, worker: String, lease_epoch: i64, metrics: Option<String>, result: Option<String>) -> napi::Result<String> {
+        let task_result = self.pg_manager.complete_task(rn, worker, lease_epoch, metrics, result).await;
         map_lib_response!(task_result)
     }
     /// # Errors
     /// Convert rust error into `napi::Error`
     #[napi]
-    pub async fn cancel_task(&self, rn: String) -> napi::Result<String> {
-        let task_result = self.pg_manager.cancel_task(rn).await;
+    pub async fn cancel_task(&self, rn: String, actor: String, reason: Option<String>) -> napi::Result<String> {
+        let task_result = self.pg_manager.cancel_task(rn, actor, reason).await;
         map_lib_response!(task_result)
     }
     /// # Errors
     /// Convert rust error into `napi::Error`
     #[napi]
-    pub async fn abort_task(&self, rn: String, js_error: JsTaskError) -> napi::Result<String> {
+    pub async fn acknowledge_cancellation(&self, rn: String, worker: String, lease_epoch: i64) -> napi::Result<String> {
+        let task_result = self.pg_manager.acknowledge_cancellation(rn, worker, lease_epoch).await;
+        map_lib_response!(task_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn resubmit_task(&self, rn: String, new_rn: String, js_overrides: Option<JsResubmitOverrides>) -> napi::Result<String> {
+        let overrides = match js_overrides {
+            None => ResubmitOverrides::default(),
+            Some(js_overrides) => ResubmitOverrides {
+                spec: match js_overrides.spec {
+                    None => None,
+                    Some(spec) => Some(validate_json(spec.as_str(), "spec")?),
+                },
+                queue: js_overrides.queue,
+                priority: js_overrides.priority,
+                max_attempts: validate_max_attempts(js_overrides.max_attempts)?,
+            },
+        };
+        let task_result = self.pg_manager.resubmit_task(rn, new_rn, overrides).await;
+        map_lib_response!(task_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn reprioritise_task(&self, rn: String, priority: i8, actor: String) -> napi::Result<String> {
+        let task_result = self.pg_manager.reprioritise_task(rn, priority, actor).await;
+        map_lib_response!(task_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn amend_task_spec(&self, rn: String, spec: String, actor: String) -> napi::Result<String> {
+        let spec = validate_json(spec.as_str(), "spec")?;
+        let task_result = self.pg_manager.amend_task_spec(rn, spec, actor).await;
+        map_lib_response!(task_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn abort_task(&self, rn: String, worker: String, lease_epoch: i64, js_error: JsTaskError) -> napi::Result<String> {
         let error_args = validate_json(js_error.args.as_str(), "args")?;
         let task_error = TaskError {
             code: js_error.code,
             args: error_args,
             description: js_error.description,
         };
-        let task_result = self.pg_manager.abort_task(rn, task_error).await;
+        let task_result = self.pg_manager.abort_task(rn, worker, lease_epoch, task_error).await;
         map_lib_response!(task_result)
     }
     /// # Errors
     /// Convert rust error into `napi::Error`
     #[napi]
-    pub async fn heart_beat_task(&self, rn: String, worker: String, progress: Option<f64>, task_timeout_in_secs: Option<i64>) -> napi::Result<String> {
+    pub async fn heart_beat_task(
+        &self,
+        rn: String,
+        worker: String,
+        lease_epoch: i64,
+        progress: Option<f64>,
+        task_timeout_in_secs: Option<i64>,
+    ) -> napi::Result<String> {
         let mut progress_value = None;
         if let Some(p) = progress {
             progress_value = Some(p as f32);
         }
-        let task_result = self.pg_manager.heartbeat_task(rn, worker, progress_value, task_timeout_in_secs).await;
+        let task_result = self
+            .pg_manager
+            .heartbeat_task(rn, worker, lease_epoch, progress_value, task_timeout_in_secs)
+            .await;
         map_lib_response!(task_result)
     }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn heart_beat_tasks(&self, worker: String, js_heartbeats: Vec<JsTaskHeartbeat>, task_timeout_in_secs: Option<i64>) -> napi::Result<String> {
+        let heartbeats = js_heartbeats
+            .into_iter()
+            .map(|js_heartbeat| TaskHeartbeat {
+                rn: js_heartbeat.rn,
+                lease_epoch: js_heartbeat.lease_epoch,
+                progress: js_heartbeat.progress.map(|p| p as f32),
+            })
+            .collect();
+        let outcome_result = self.pg_manager.heartbeat_tasks(worker, heartbeats, task_timeout_in_secs).await;
+        map_lib_response!(outcome_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn upsert_queue(&self, js_queue: JsQueue) -> napi::Result<String> {
+        let priority_aging = match js_queue.priority_aging {
+            None => None,
+            Some(aging) => Some(validate_priority_aging(aging.seconds_per_point, aging.max_boost)?),
+        };
+        let queue = Queue {
+            name: js_queue.name,
+            max_attempts: validate_max_attempts(js_queue.max_attempts)?,
+            priority_aging,
+            lease_timeout_in_secs: js_queue.lease_timeout_in_secs,
+            retention_period_in_secs: js_queue.retention_period_in_secs,
+            max_priority: js_queue.max_priority,
+            max_running_tasks: js_queue.max_running_tasks,
+            max_running_tasks_per_key: js_queue.max_running_tasks_per_key,
+            rate_limit: js_queue.rate_limit.map(|rate_limit| RateLimit {
+                max_starts: rate_limit.max_starts,
+                interval_in_secs: rate_limit.interval_in_secs,
+            }),
+            description: js_queue.description,
+        };
+        let queue_result = self.pg_manager.upsert_queue(queue).await;
+        map_lib_response!(queue_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn get_queue(&self, name: String) -> napi::Result<String> {
+        let queue_result = self.pg_manager.fetch_queue(name).await;
+        map_lib_response!(queue_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn get_rate_limit_budget(&self, name: String) -> napi::Result<String> {
+        let budget_result = self.pg_manager.fetch_rate_limit_budget(name).await;
+        map_lib_response!(budget_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn pause_queue(&self, name: String) -> napi::Result<String> {
+        let queue_state_result = self.pg_manager.pause_queue(name).await;
+        map_lib_response!(queue_state_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn drain_queue(&self, name: String) -> napi::Result<String> {
+        let queue_state_result = self.pg_manager.drain_queue(name).await;
+        map_lib_response!(queue_state_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn resume_queue(&self, name: String) -> napi::Result<String> {
+        let queue_state_result = self.pg_manager.resume_queue(name).await;
+        map_lib_response!(queue_state_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn get_queue_state(&self, name: String) -> napi::Result<String> {
+        let queue_state_result = self.pg_manager.fetch_queue_state(name).await;
+        map_lib_response!(queue_state_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn upsert_schedule(&self, js_schedule: JsSchedule) -> napi::Result<String> {
+        let spec = validate_json(js_schedule.spec.as_str(), "spec")?;
+        let default_schedule = Schedule::default();
+        let schedule = Schedule {
+            name: js_schedule.name,
+            cron: js_schedule.cron,
+            timezone: js_schedule.timezone.unwrap_or(default_schedule.timezone),
+            queue: js_schedule.queue,
+            spec,
+            priority: js_schedule.priority,
+            ..default_schedule
+        };
+        let schedule_result = self.pg_manager.upsert_schedule(schedule).await;
+        map_lib_response!(schedule_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn get_schedule(&self, name: String) -> napi::Result<String> {
+        let schedule_result = self.pg_manager.fetch_schedule(name).await;
+        map_lib_response!(schedule_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn get_schedules(&self) -> napi::Result<String> {
+        let schedules_result = self.pg_manager.fetch_schedules().await;
+        map_lib_response!(schedules_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn delete_schedule(&self, name: String) -> napi::Result<String> {
+        let delete_result = self.pg_manager.delete_schedule(name).await;
+        map_lib_response!(delete_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn register_worker(&self, js_metadata: JsWorkerMetadata) -> napi::Result<String> {
+        let metadata = WorkerMetadata {
+            hostname: js_metadata.hostname,
+            version: js_metadata.version,
+        };
+        let worker_result = self.pg_manager.register_worker(metadata).await;
+        map_lib_response!(worker_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn heartbeat_worker(&self, id: String) -> napi::Result<String> {
+        let worker_result = self.pg_manager.heartbeat_worker(id).await;
+        map_lib_response!(worker_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn get_worker(&self, id: String) -> napi::Result<String> {
+        let worker_result = self.pg_manager.fetch_worker(id).await;
+        map_lib_response!(worker_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn get_workers(&self) -> napi::Result<String> {
+        let workers_result = self.pg_manager.fetch_workers().await;
+        map_lib_response!(workers_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn drain_worker(&self, id: String) -> napi::Result<String> {
+        let worker_result = self.pg_manager.drain_worker(id).await;
+        map_lib_response!(worker_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn resume_worker(&self, id: String) -> napi::Result<String> {
+        let worker_result = self.pg_manager.resume_worker(id).await;
+        map_lib_response!(worker_result)
+    }
+    /// # Errors
+    /// Convert rust error into `napi::Error`
+    #[napi]
+    pub async fn deregister_worker(&self, id: String) -> napi::Result<String> {
+        let deregister_result = self.pg_manager.deregister_worker(id).await;
+        map_lib_response!(deregister_result)
+    }
+}
+
+/// # Errors
+/// Returns `JSScyllaError` in case any of the fields is invalid
+fn map_add_task_model(js_atm: JsAddTaskModel) -> Result<AddTaskModel, JSScyllaError> {
+    let spec = validate_json(js_atm.spec.as_str(), "spec")?;
+    let not_before = match js_atm.not_before {
+        None => None,
+        Some(not_before) => Some(validate_date_time(not_before.as_str(), "not_before")?),
+    };
+    let expires_at = match js_atm.expires_at {
+        None => None,
+        Some(expires_at) => Some(validate_date_time(expires_at.as_str(), "expires_at")?),
+    };
+    let depends_on = validate_depends_on(js_atm.rn.as_str(), js_atm.depends_on.unwrap_or_default())?;
+    let on_dependency_failure = match js_atm.on_dependency_failure {
+        None => DependencyFailurePolicy::default(),
+        Some(policy) => validate_dependency_failure_policy(policy.as_str())?,
+    };
+    Ok(AddTaskModel {
+        rn: js_atm.rn,
+        priority: js_atm.priority,
+        spec,
+        queue: js_atm.queue,
+        not_before,
+        max_attempts: validate_max_attempts(js_atm.max_attempts)?,
+        depends_on,
+        on_dependency_failure,
+        expires_at,
+        labels: js_atm.labels.unwrap_or_default().into_iter().collect(),
+        supersession_key: js_atm.supersession_key,
+        concurrency_key: js_atm.concurrency_key,
+    })
+}
+
+/// # Errors
+/// Returns `JSScyllaError` in case any of the selectors is invalid
+fn map_label_selectors(js_label_selectors: Option<Vec<JsLabelSelector>>) -> Result<Vec<LabelSelector>, JSScyllaError> {
+    js_label_selectors
+        .unwrap_or_default()
+        .into_iter()
+        .map(|s| validate_label_selector(s.key, s.value, s.values))
+        .collect()
 }
 
 /// # Errors
@@ -1,5 +1,6 @@
 // $coverage:ignore-start
 use napi_derive::napi;
+use std::collections::HashMap;
 
 #[napi(object)]
 pub struct JsAddTaskModel {
@@ -7,6 +8,21 @@ pub struct JsAddTaskModel {
     pub spec: String,
     pub priority: i8,
     pub queue: String,
+    pub not_before: Option<String>,
+    pub max_attempts: Option<i32>,
+    pub depends_on: Option<Vec<String>>,
+    pub on_dependency_failure: Option<String>,
+    pub expires_at: Option<String>,
+    pub labels: Option<HashMap<String, String>>,
+    pub supersession_key: Option<String>,
+    pub concurrency_key: Option<String>,
+}
+#[napi(object)]
+pub struct JsResubmitOverrides {
+    pub spec: Option<String>,
+    pub queue: Option<String>,
+    pub priority: Option<i8>,
+    pub max_attempts: Option<i32>,
 }
 #[napi(object)]
 pub struct JsGetTasksModel {
@@ -14,6 +30,45 @@ pub struct JsGetTasksModel {
     pub status: Option<String>,
     pub limit: Option<i32>,
     pub queue: Option<String>,
+    pub labels: Option<Vec<JsLabelSelector>>,
+}
+#[napi(object)]
+pub struct JsLabelSelector {
+    pub key: String,
+    pub value: Option<String>,
+    pub values: Option<Vec<String>>,
+}
+#[napi(object)]
+pub struct JsPriorityAging {
+    pub seconds_per_point: i32,
+    pub max_boost: i32,
+}
+#[napi(object)]
+pub struct JsRateLimit {
+    pub max_starts: i32,
+    pub interval_in_secs: i32,
+}
+#[napi(object)]
+pub struct JsQueue {
+    pub name: String,
+    pub max_attempts: Option<i32>,
+    pub priority_aging: Option<JsPriorityAging>,
+    pub lease_timeout_in_secs: Option<i64>,
+    pub retention_period_in_secs: Option<i64>,
+    pub max_priority: Option<i8>,
+    pub max_running_tasks: Option<i32>,
+    pub max_running_tasks_per_key: Option<i32>,
+    pub rate_limit: Option<JsRateLimit>,
+    pub description: Option<String>,
+}
+#[napi(object)]
+pub struct JsSchedule {
+    pub name: String,
+    pub cron: String,
+    pub timezone: Option<String>,
+    pub queue: String,
+    pub spec: String,
+    pub priority: i8,
 }
 #[napi(object)]
 pub struct JsTaskError {
@@ -21,3 +76,14 @@ pub struct JsTaskError {
     pub args: String,
     pub description: String,
 }
+#[napi(object)]
+pub struct JsTaskHeartbeat {
+    pub rn: String,
+    pub lease_epoch: i64,
+    pub progress: Option<f64>,
+}
+#[napi(object)]
+pub struct JsWorkerMetadata {
+    pub hostname: String,
+    pub version: String,
+}
@@ -1,5 +1,6 @@
 // $coverage:ignore-start
-use scylla_models::TaskStatus;
+use chrono::{DateTime, Utc};
+use scylla_models::{DependencyFailurePolicy, LabelSelector, PriorityAging, TaskStatus};
 
 #[derive(Debug, thiserror::Error)]
 pub enum JSScyllaError {
@@ -15,7 +16,10 @@ pub fn validate_status(status: &str) -> Result<TaskStatus, JSScyllaError> {
         "ready" => Ok(TaskStatus::Ready),
         "completed" => Ok(TaskStatus::Completed),
         "cancelled" => Ok(TaskStatus::Cancelled),
+        "cancelling" => Ok(TaskStatus::Cancelling),
         "aborted" => Ok(TaskStatus::Aborted),
+        "blocked" => Ok(TaskStatus::Blocked),
+        "expired" => Ok(TaskStatus::Expired),
         _ => Err(JSScyllaError::ArgumentValidationError("Invalid Task Status".to_string())),
     }
 }
@@ -27,6 +31,59 @@ pub fn validate_json(spec: &str, field: &str) -> Result<serde_json::Value, JSScy
     }
 }
 
+pub fn validate_date_time(value: &str, field: &str) -> Result<DateTime<Utc>, JSScyllaError> {
+    match DateTime::parse_from_rfc3339(value) {
+        Ok(t) => Ok(t.with_timezone(&Utc)),
+        Err(_) => Err(JSScyllaError::ArgumentValidationError(format!("Invalid RFC 3339 date time for {field}"))),
+    }
+}
+
+pub fn validate_max_attempts(max_attempts: Option<i32>) -> Result<Option<i32>, JSScyllaError> {
+    match max_attempts {
+        Some(t) if t < 1 => Err(JSScyllaError::ArgumentValidationError("Invalid value for max_attempts".to_string())),
+        _ => Ok(max_attempts),
+    }
+}
+
+pub fn validate_priority_aging(seconds_per_point: i32, max_boost: i32) -> Result<PriorityAging, JSScyllaError> {
+    if seconds_per_point < 1 {
+        Err(JSScyllaError::ArgumentValidationError("Invalid value for seconds_per_point".to_string()))
+    } else if max_boost < 0 {
+        Err(JSScyllaError::ArgumentValidationError("Invalid value for max_boost".to_string()))
+    } else {
+        Ok(PriorityAging { seconds_per_point, max_boost })
+    }
+}
+
+pub fn validate_depends_on(rn: &str, depends_on: Vec<String>) -> Result<Vec<String>, JSScyllaError> {
+    if depends_on.iter().any(|parent| parent == rn) {
+        Err(JSScyllaError::ArgumentValidationError("Task cannot depend on itself".to_string()))
+    } else {
+        Ok(depends_on)
+    }
+}
+
+pub fn validate_dependency_failure_policy(policy: &str) -> Result<DependencyFailurePolicy, JSScyllaError> {
+    match policy {
+        "abort" => Ok(DependencyFailurePolicy::Abort),
+        "cancel" => Ok(DependencyFailurePolicy::Cancel),
+        _ => Err(JSScyllaError::ArgumentValidationError("Invalid value for on_dependency_failure".to_string())),
+    }
+}
+
+pub fn validate_label_selector(key: String, value: Option<String>, values: Option<Vec<String>>) -> Result<LabelSelector, JSScyllaError> {
+    if key.is_empty() {
+        return Err(JSScyllaError::ArgumentValidationError("Invalid value for label key".to_string()));
+    }
+    match (value, values) {
+        (Some(value), None) => Ok(LabelSelector::Equals(key, value)),
+        (None, Some(values)) if !values.is_empty() => Ok(LabelSelector::In(key, values)),
+        _ => Err(JSScyllaError::ArgumentValidationError(format!(
+            "Label selector for {key} needs either a value or non empty values"
+        ))),
+    }
+}
+
 // pub fn validate_progress(progress: f64) -> Result<f32, JSScyllaError>{
 //   return match f32::try_from(progress) {
 //     Ok(t) => Ok(t),
@@ -46,3 +103,22 @@ pub fn validate_pool_size(pool_size: u32) -> Result<usize, JSScyllaError> {
         Err(_) => Err(JSScyllaError::ArgumentValidationError("Invalid value for pg_pool_size".to_string())),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::validator::validate_status;
+    use scylla_models::TaskStatus;
+
+    #[test]
+    fn validate_status_checks() {
+        assert_eq!(validate_status("ready").unwrap(), TaskStatus::Ready);
+        assert_eq!(validate_status("running").unwrap(), TaskStatus::Running);
+        assert_eq!(validate_status("cancelling").unwrap(), TaskStatus::Cancelling);
+        assert_eq!(validate_status("cancelled").unwrap(), TaskStatus::Cancelled);
+        assert_eq!(validate_status("expired").unwrap(), TaskStatus::Expired);
+        assert_eq!(
+            validate_status("unknown").unwrap_err().to_string(),
+            "Validation failed for fields: Invalid Task Status".to_string()
+        );
+    }
+}
@@ -4,87 +4,553 @@
 
 use crate::adapter_utils::{
     handle_batch_insert_tasks_return, handle_insert_return, handle_query_by_rn_return, handle_update_return, prepare_batch_insert_tasks, prepare_insert_task,
-    prepare_query_task, prepare_update_task,
+    prepare_label_selectors, prepare_query_task, prepare_update_task,
 };
 use crate::error::PgAdapterError;
 use async_trait::async_trait;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Utc};
 use deadpool_postgres::{Client, Pool};
 use log::debug;
-use scylla_models::{GetTaskModel, Task, TaskBatch, TaskHistory, TaskHistoryType};
+use scylla_models::{
+    GetTaskModel, LabelSelector, Queue, QueueMode, QueueState, RateLimitBudget, Schedule, Task, TaskBatch, TaskHeartbeat, TaskHistory, TaskHistoryType, Worker,
+};
+use scylla_operations::error::ScyllaOperationsError;
+use scylla_operations::queue::DEFAULT_LEASE_TIMEOUT_IN_SECS;
 use scylla_operations::task::Persistence;
-use serde_json::{from_value, json};
+use scylla_operations::update_task::{
+    CANCELLATION_NOT_ACKNOWLEDGED_REASON, DEPENDENCY_FAILED_ERROR_CODE, EXPIRED_REASON, MAX_ATTEMPTS_EXCEEDED_ERROR_CODE, MONITOR_ACTOR, SUPERSEDED_ERROR_CODE,
+    SUPERSESSION_ACTOR, WORKER_RESET_REASON,
+};
+use serde::de::DeserializeOwned;
+use serde_json::{from_value, json, to_value};
+use std::collections::BTreeSet;
 use tokio_postgres::error::SqlState;
 use tokio_postgres::types::{Json, ToSql};
 use tokio_postgres::IsolationLevel;
 
 const CONST_DELAY: u64 = 10;
 const INSERT_TASK_SQL: &str = "
-    INSERT INTO task(data) VALUES ($1) \
+    INSERT INTO task(data) \
+    SELECT t.data || jsonb_build_object('maxAttempts', COALESCE(NULLIF(t.data -> 'maxAttempts', 'null'::jsonb), \
+        (SELECT q.data -> 'maxAttempts' FROM queue q WHERE q.data ->> 'name' = t.data ->> 'queue'), 'null'::jsonb)) \
+    FROM (SELECT $1::jsonb AS data) t \
+    WHERE NOT EXISTS (SELECT 1 FROM queue_state s WHERE s.data ->> 'name' = t.data ->> 'queue' AND s.data ->> 'mode' = 'draining') \
+    AND NOT EXISTS (SELECT 1 FROM jsonb_array_elements_text(COALESCE(t.data -> 'dependsOn', '[]'::jsonb)) d(rn) \
+        WHERE NOT EXISTS (SELECT 1 FROM task p WHERE p.data ->> 'rn' = d.rn)) \
     ON CONFLICT ((data->>'rn')) \
     DO NOTHING
     RETURNING data::JSONB
   ";
 const INSERT_BATCH_TASKS_SQL: &str = "
+    WITH batch AS (SELECT data FROM unnest($1::jsonb[]) AS b(data)) \
     INSERT INTO task (data) \
-    SELECT unnest($1::jsonb[]) \
+    SELECT t.data || jsonb_build_object('maxAttempts', COALESCE(NULLIF(t.data -> 'maxAttempts', 'null'::jsonb), \
+        (SELECT q.data -> 'maxAttempts' FROM queue q WHERE q.data ->> 'name' = t.data ->> 'queue'), 'null'::jsonb)) \
+    FROM batch t \
+    WHERE NOT EXISTS (SELECT 1 FROM queue_state s WHERE s.data ->> 'name' = t.data ->> 'queue' AND s.data ->> 'mode' = 'draining') \
+    AND NOT EXISTS (SELECT 1 FROM batch b, jsonb_array_elements_text(COALESCE(b.data -> 'dependsOn', '[]'::jsonb)) d(rn) \
+        WHERE NOT EXISTS (SELECT 1 FROM batch n WHERE n.data ->> 'rn' = d.rn) AND NOT EXISTS (SELECT 1 FROM task p WHERE p.data ->> 'rn' = d.rn)) \
     ON CONFLICT ((data->>'rn')) DO NOTHING \
     RETURNING data::JSONB;
 ";
+const SUPERSEDE_TASK_SQL: &str = "
+    WITH replaced AS ( \
+        UPDATE task t SET data = t.data || jsonb_build_object('spec', $1::jsonb -> 'spec', 'priority', $1::jsonb -> 'priority', \
+            'updated', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ'), \
+            'history', COALESCE(t.data -> 'history', '[]'::jsonb) || jsonb_build_array(jsonb_build_object( \
+              'typ', 'TaskSpecAmendment', \
+              'time', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ'), \
+              'worker', '', \
+              'progress', (t.data ->> 'progress')::float, \
+              'actor', $4::text, \
+              'reason', $3::text)) \
+            || CASE WHEN t.data -> 'priority' = $1::jsonb -> 'priority' THEN '[]'::jsonb ELSE jsonb_build_array(jsonb_build_object( \
+              'typ', 'TaskReprioritisation', \
+              'time', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ'), \
+              'worker', '', \
+              'progress', (t.data ->> 'progress')::float, \
+              'actor', $4::text, \
+              'reason', $3::text)) END, \
+            'version', COALESCE((t.data ->> 'version')::bigint, 0) + 1) \
+        WHERE t.data ->> 'queue' = $1::jsonb ->> 'queue' AND t.data ->> 'supersessionKey' = $1::jsonb ->> 'supersessionKey' AND t.data ->> 'status' = 'ready' \
+        RETURNING t.data \
+    ), inserted AS ( \
+        INSERT INTO task(data) \
+        SELECT n.data || jsonb_build_object('maxAttempts', COALESCE(NULLIF(n.data -> 'maxAttempts', 'null'::jsonb), \
+            (SELECT q.data -> 'maxAttempts' FROM queue q WHERE q.data ->> 'name' = n.data ->> 'queue'), 'null'::jsonb)) \
+        FROM (SELECT $1::jsonb AS data) n \
+        WHERE NOT EXISTS (SELECT 1 FROM replaced) \
+        AND NOT EXISTS (SELECT 1 FROM queue_state s WHERE s.data ->> 'name' = n.data ->> 'queue' AND s.data ->> 'mode' = 'draining') \
+        AND NOT EXISTS (SELECT 1 FROM jsonb_array_elements_text(COALESCE(n.data -> 'dependsOn', '[]'::jsonb)) d(rn) \
+            WHERE NOT EXISTS (SELECT 1 FROM task p WHERE p.data ->> 'rn' = d.rn)) \
+        ON CONFLICT ((data->>'rn')) DO NOTHING \
+        RETURNING data \
+    ), successor AS ( \
+        SELECT data FROM replaced UNION ALL SELECT data FROM inserted LIMIT 1 \
+    ), cancelling AS ( \
+        UPDATE task t SET data = t.data || jsonb_build_object('status', 'cancelling', 'updated', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ'), \
+            'cancelRequested', jsonb_build_object('requester', $4::text, 'reason', $3::text, 'time', now()), \
+            'errors', COALESCE(t.data -> 'errors', '[]'::jsonb) || jsonb_build_array(jsonb_build_object( \
+              'code', $3::text, \
+              'args', jsonb_build_object('rn', s.data ->> 'rn'), \
+              'description', 'new task superseded')), \
+            'history', COALESCE(t.data -> 'history', '[]'::jsonb) || jsonb_build_array(jsonb_build_object( \
+              'typ', 'TaskCancellationRequest', \
+              'time', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ'), \
+              'worker', COALESCE(t.data ->> 'owner', ''), \
+              'progress', (t.data ->> 'progress')::float, \
+              'actor', $4::text, \
+              'reason', $3::text)), \
+            'version', COALESCE((t.data ->> 'version')::bigint, 0) + 1) \
+        FROM successor s \
+        WHERE $2 AND t.data ->> 'queue' = $1::jsonb ->> 'queue' AND t.data ->> 'supersessionKey' = $1::jsonb ->> 'supersessionKey' AND t.data ->> 'status' = 'running' \
+        RETURNING t.data \
+    ) \
+    SELECT data FROM successor";
+const RESUBMIT_TASK_SQL: &str = "
+    WITH previous AS ( \
+        UPDATE task t SET data = t.data || jsonb_build_object('resubmittedAs', $1::jsonb ->> 'rn', 'updated', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ'), \
+            'version', COALESCE((t.data ->> 'version')::bigint, 0) + 1) \
+        WHERE t.data ->> 'rn' = $1::jsonb ->> 'previousRn' AND COALESCE((t.data ->> 'version')::bigint, 0) = $2 \
+            AND NOT EXISTS (SELECT 1 FROM task n WHERE n.data ->> 'rn' = $1::jsonb ->> 'rn') \
+            AND NOT EXISTS (SELECT 1 FROM queue_state s WHERE s.data ->> 'name' = $1::jsonb ->> 'queue' AND s.data ->> 'mode' = 'draining') \
+        RETURNING t.data \
+    ) \
+    INSERT INTO task(data) \
+    SELECT n.data || jsonb_build_object('maxAttempts', COALESCE(NULLIF(n.data -> 'maxAttempts', 'null'::jsonb), \
+        (SELECT q.data -> 'maxAttempts' FROM queue q WHERE q.data ->> 'name' = n.data ->> 'queue'), 'null'::jsonb)) \
+    FROM (SELECT $1::jsonb AS data) n, previous \
+    ON CONFLICT ((data->>'rn')) DO NOTHING \
+    RETURNING data::JSONB";
 const UPDATE_TASK_SQL: &str = "
-    UPDATE task SET data = data || $1 where data ->> 'rn' = $2 returning data
+    UPDATE task t SET data = t.data || $1 || jsonb_build_object('version', COALESCE((t.data ->> 'version')::bigint, 0) + 1) \
+    where t.data ->> 'rn' = $2 AND COALESCE((t.data ->> 'version')::bigint, 0) = $3 returning t.data
   ";
 const GET_TASKS_SQL: &str = "
-        Select data::JSONB from task \
-        where data ->> 'status' like $1 \
-        AND data ->> 'queue' like $2 \
-        AND (('%' = $3 OR data ->> 'owner' like $3))
-        order by data ->> 'priority' desc, data -> 'created' desc
+        Select t.data || jsonb_build_object('effectivePriority', effective_priority(t.data, q.data)) from task t \
+        LEFT JOIN queue q ON q.data ->> 'name' = t.data ->> 'queue' \
+        where t.data ->> 'status' like $1 \
+        AND t.data ->> 'queue' like $2 \
+        AND (('%' = $3 OR t.data ->> 'owner' like $3)) \
+        AND ($5::jsonb = '{}'::jsonb OR t.data -> 'labels' @> $5::jsonb) \
+        AND NOT EXISTS (SELECT 1 FROM jsonb_array_elements($6::jsonb) s \
+            WHERE NOT COALESCE(t.data -> 'labels' ->> (s ->> 'key') IN (SELECT jsonb_array_elements_text(s -> 'values')), false)) \
+        order by effective_priority(t.data, q.data) desc, t.data -> 'created' desc
         limit $4::Int
       ";
 const GET_TASK_SQL: &str = "
         Select data::JSONB from task \
         where data ->> 'rn' = $1 \
       ";
+const LEASE_TASK_SQL: &str = "
+    WITH budget AS ( \
+        SELECT q.data ->> 'name' AS queue, rate_limit_tokens(q.data, b.data) AS tokens FROM queue q \
+        LEFT JOIN queue_rate_limit b ON b.data ->> 'name' = q.data ->> 'name' \
+        WHERE q.data ->> 'name' = $4 AND q.data ->> 'rateLimit' IS NOT NULL \
+    ), leased AS ( \
+        UPDATE task t SET data = t.data || $1 || jsonb_build_object('version', COALESCE((t.data ->> 'version')::bigint, 0) + 1) \
+        where t.data ->> 'rn' = $2 AND COALESCE((t.data ->> 'version')::bigint, 0) = $3 \
+        AND NOT EXISTS (SELECT 1 FROM queue_state s WHERE s.data ->> 'name' = t.data ->> 'queue' AND s.data ->> 'mode' = 'paused') \
+        AND NOT EXISTS (SELECT 1 FROM queue q WHERE q.data ->> 'name' = t.data ->> 'queue' \
+            AND ((q.data ->> 'maxRunningTasks')::bigint <= (SELECT count(*) FROM task r WHERE r.data ->> 'queue' = t.data ->> 'queue' \
+                    AND r.data ->> 'status' IN ('running', 'cancelling')) \
+                OR (q.data ->> 'maxRunningTasksPerKey')::bigint <= (SELECT count(*) FROM task r WHERE r.data ->> 'queue' = t.data ->> 'queue' \
+                    AND r.data ->> 'concurrencyKey' = t.data ->> 'concurrencyKey' AND r.data ->> 'status' IN ('running', 'cancelling')))) \
+        AND NOT EXISTS (SELECT 1 FROM budget WHERE tokens < 1) \
+        returning t.data \
+    ), consumed AS ( \
+        INSERT INTO queue_rate_limit(data) \
+        SELECT jsonb_build_object('name', b.queue, 'tokens', b.tokens - count(*), 'refilled', statement_timestamp()) \
+        FROM budget b JOIN leased l ON l.data ->> 'queue' = b.queue GROUP BY b.queue, b.tokens \
+        ON CONFLICT ((data ->> 'name')) DO UPDATE SET data = EXCLUDED.data \
+    ) \
+    SELECT data FROM leased
+  ";
+// registry rows of limited and rate limited queues are locked by a statement of its own, so that the lease statement following it
+// in the same read committed transaction sees the tasks leased by transactions which held the lock before
+const LOCK_LIMITED_QUEUES_SQL: &str = "
+    SELECT q.data FROM queue q \
+    WHERE q.data ->> 'name' like $1 \
+    AND (q.data ->> 'maxRunningTasks' IS NOT NULL OR q.data ->> 'maxRunningTasksPerKey' IS NOT NULL OR q.data ->> 'rateLimit' IS NOT NULL) \
+    ORDER BY q.data ->> 'name' FOR UPDATE
+  ";
 const LEASE_N_TASK_SQL: &str = "
-    UPDATE task t SET data = jsonb_set(jsonb_set(jsonb_set(jsonb_set( \
-            jsonb_set(t.data, '{status}', '\"running\"'), \
-         '{owner}', $3), '{deadline}', $4), '{updated}', $5), '{history, 100}', $6) where t.data ->> 'rn' IN (Select data::JSONB ->> 'rn' from task \
-        where data ->> 'status' = 'ready' \
-        AND data ->> 'queue' like $1 \
-        order by data ->> 'priority' desc, data -> 'created' asc
-        limit $2::Int FOR UPDATE SKIP LOCKED) returning t.data";
+    WITH running_by_queue AS ( \
+        SELECT r.data ->> 'queue' AS queue, count(*) AS count FROM task r \
+        WHERE r.data ->> 'status' IN ('running', 'cancelling') AND r.data ->> 'queue' like $1 \
+        GROUP BY r.data ->> 'queue' \
+    ), running_by_key AS ( \
+        SELECT r.data ->> 'queue' AS queue, r.data ->> 'concurrencyKey' AS concurrency_key, count(*) AS count FROM task r \
+        WHERE r.data ->> 'status' IN ('running', 'cancelling') AND r.data ->> 'queue' like $1 AND r.data ->> 'concurrencyKey' IS NOT NULL \
+        GROUP BY r.data ->> 'queue', r.data ->> 'concurrencyKey' \
+    ), budget AS ( \
+        SELECT q.data ->> 'name' AS queue, rate_limit_tokens(q.data, b.data) AS tokens FROM queue q \
+        LEFT JOIN queue_rate_limit b ON b.data ->> 'name' = q.data ->> 'name' \
+        WHERE q.data ->> 'name' like $1 AND q.data ->> 'rateLimit' IS NOT NULL \
+    ), candidates AS ( \
+        Select c.data ->> 'rn' AS rn, c.data ->> 'queue' AS queue, c.data ->> 'concurrencyKey' AS concurrency_key, \
+            LEAST((q.data ->> 'maxRunningTasks')::bigint - COALESCE(rq.count, 0), floor(rb.tokens)::bigint) AS queue_capacity, \
+            (q.data ->> 'maxRunningTasksPerKey')::bigint - COALESCE(rk.count, 0) AS key_capacity, \
+            effective_priority(c.data, q.data) AS priority, c.data -> 'created' AS created from task c \
+        LEFT JOIN queue q ON q.data ->> 'name' = c.data ->> 'queue' \
+        LEFT JOIN running_by_queue rq ON rq.queue = c.data ->> 'queue' \
+        LEFT JOIN running_by_key rk ON rk.queue = c.data ->> 'queue' AND rk.concurrency_key = c.data ->> 'concurrencyKey' \
+        LEFT JOIN budget rb ON rb.queue = c.data ->> 'queue' \
+        where c.data ->> 'status' = 'ready' \
+        AND c.data ->> 'queue' like $1 \
+        AND NOT EXISTS (SELECT 1 FROM queue_state s WHERE s.data ->> 'name' = c.data ->> 'queue' AND s.data ->> 'mode' = 'paused') \
+        AND (c.data ->> 'notBefore' IS NULL OR (c.data ->> 'notBefore')::timestamptz <= now()) \
+        AND (c.data ->> 'expiresAt' IS NULL OR (c.data ->> 'expiresAt')::timestamptz > now()) \
+        AND ($7::jsonb = '{}'::jsonb OR c.data -> 'labels' @> $7::jsonb) \
+        AND NOT EXISTS (SELECT 1 FROM jsonb_array_elements($8::jsonb) s \
+            WHERE NOT COALESCE(c.data -> 'labels' ->> (s ->> 'key') IN (SELECT jsonb_array_elements_text(s -> 'values')), false)) \
+        AND ((q.data ->> 'maxRunningTasks')::bigint IS NULL OR (q.data ->> 'maxRunningTasks')::bigint > COALESCE(rq.count, 0)) \
+        AND ((q.data ->> 'maxRunningTasksPerKey')::bigint IS NULL OR c.data ->> 'concurrencyKey' IS NULL \
+            OR (q.data ->> 'maxRunningTasksPerKey')::bigint > COALESCE(rk.count, 0)) \
+        AND (rb.tokens IS NULL OR rb.tokens >= 1) \
+        order by effective_priority(c.data, q.data) desc, c.data -> 'created' asc
+        limit $2::Int FOR UPDATE OF c SKIP LOCKED \
+    ), admitted AS ( \
+        SELECT rn, queue_capacity, key_capacity, concurrency_key, \
+            row_number() OVER (PARTITION BY queue ORDER BY priority desc, created asc) AS queue_rank, \
+            row_number() OVER (PARTITION BY queue, concurrency_key ORDER BY priority desc, created asc) AS key_rank \
+        FROM candidates \
+    ), leased AS ( \
+        UPDATE task t SET data = jsonb_set(jsonb_set(jsonb_set(jsonb_set( \
+                jsonb_set(t.data, '{status}', '\"running\"'), \
+             '{owner}', $3), '{deadline}', to_jsonb(to_char(timezone('UTC'::text, now() + COALESCE($4::bigint, \
+                (SELECT (q.data ->> 'leaseTimeoutInSecs')::bigint FROM queue q WHERE q.data ->> 'name' = t.data ->> 'queue'), $9::bigint) * interval '1 second'), \
+                'YYYY-MM-DD\"T\"HH24:MI:SS.US\"Z\"'))), '{updated}', $5), '{history, 100}', $6) \
+             || jsonb_build_object('attempts', (SELECT count(*) FROM ( \
+                    SELECT h.e ->> 'typ' AS typ, lead(h.e ->> 'typ') OVER (ORDER BY h.i) AS next_typ \
+                    FROM jsonb_array_elements(t.data -> 'history') WITH ORDINALITY h(e, i) WHERE h.e ->> 'typ' IN ('TaskAssignment', 'TaskYield')) a \
+                WHERE a.typ = 'TaskAssignment' AND a.next_typ IS DISTINCT FROM 'TaskYield') + 1, \
+                'version', COALESCE((t.data ->> 'version')::bigint, 0) + 1, \
+                'leaseEpoch', COALESCE((t.data ->> 'leaseEpoch')::bigint, 0) + 1) \
+        FROM admitted a \
+        where t.data ->> 'rn' = a.rn \
+        AND (a.queue_capacity IS NULL OR a.queue_rank <= a.queue_capacity) \
+        AND (a.key_capacity IS NULL OR a.concurrency_key IS NULL OR a.key_rank <= a.key_capacity) \
+        returning t.data \
+    ), consumed AS ( \
+        INSERT INTO queue_rate_limit(data) \
+        SELECT jsonb_build_object('name', b.queue, 'tokens', b.tokens - count(*), 'refilled', statement_timestamp()) \
+        FROM budget b JOIN leased l ON l.data ->> 'queue' = b.queue GROUP BY b.queue, b.tokens \
+        ON CONFLICT ((data ->> 'name')) DO UPDATE SET data = EXCLUDED.data \
+    ) \
+    SELECT data FROM leased";
+
+const HEARTBEAT_BATCH_TASK_SQL: &str = "
+    WITH beats AS ( \
+        SELECT b ->> 'rn' AS rn, (b ->> 'leaseEpoch')::bigint AS lease_epoch, NULLIF(b -> 'progress', 'null'::jsonb) AS progress \
+        FROM jsonb_array_elements($1::jsonb) b \
+    ), extended AS ( \
+        UPDATE task t SET data = t.data || jsonb_build_object( \
+            'deadline', to_char(timezone('UTC'::text, now() + COALESCE($3::bigint, \
+                (SELECT (q.data ->> 'leaseTimeoutInSecs')::bigint FROM queue q WHERE q.data ->> 'name' = t.data ->> 'queue'), $5::bigint) * interval '1 second'), \
+                'YYYY-MM-DD\"T\"HH24:MI:SS.US\"Z\"'), \
+            'updated', $4::jsonb, \
+            'progress', COALESCE(b.progress, t.data -> 'progress'), \
+            'version', COALESCE((t.data ->> 'version')::bigint, 0) + 1) \
+        FROM beats b \
+        where t.data ->> 'rn' = b.rn AND t.data ->> 'status' IN ('running', 'cancelling') AND t.data ->> 'owner' = $2 \
+        AND COALESCE((t.data ->> 'leaseEpoch')::bigint, 0) = b.lease_epoch \
+        returning t.data \
+    ) \
+    SELECT COALESCE(e.data, c.data) FROM task c \
+    LEFT JOIN extended e ON e.data ->> 'rn' = c.data ->> 'rn' \
+    where c.data ->> 'rn' IN (SELECT rn FROM beats)";
 
 const RESET_BATCH_TASK_SQL: &str = "
-        UPDATE task t SET data = jsonb_set(t.data, '{history, 100}', jsonb_build_object(
-	'typ', 'TaskTimeout',
-	'time', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH:MI:SS.MSZ'),
-	'worker', t.data->>'owner',
-	'progress', (t.data->>'progress')::float
-      )) || jsonb_build_object('progress', 0, 'status', 'ready', 'owner', null, 'deadline', null, 'updated', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH:MI:SS.MSZ'))  \
-             where t.data ->> 'deadline' < $1 AND t.data ->> 'status' = 'running' \
-             returning t.data";
+    WITH timed_out AS ( \
+        SELECT c.data ->> 'rn' AS rn, COALESCE(c.data -> 'history' -> -1 ->> 'typ' = 'TaskYield', false) AS yielded, \
+            CASE WHEN (c.data ->> 'maxAttempts')::int IS NOT NULL AND COALESCE((c.data ->> 'attempts')::int, 0) >= (c.data ->> 'maxAttempts')::int \
+                    AND c.data -> 'history' -> -1 ->> 'typ' IS DISTINCT FROM 'TaskYield' THEN 'aborted' \
+                WHEN (c.data ->> 'expiresAt')::timestamptz <= now() THEN 'expired' \
+                ELSE 'ready' END AS outcome \
+        FROM task c WHERE c.data ->> 'status' = 'running' \
+        AND CASE WHEN $5::text IS NULL THEN c.data ->> 'deadline' < $1 ELSE c.data ->> 'owner' = $5 END \
+    ) \
+    UPDATE task t SET data = t.data || CASE r.outcome \
+        WHEN 'aborted' THEN jsonb_build_object('status', 'aborted', 'updated', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ'), \
+            'errors', COALESCE(t.data -> 'errors', '[]'::jsonb) || jsonb_build_array(jsonb_build_object( \
+              'code', $2::text, \
+              'args', jsonb_build_object('attempts', COALESCE((t.data ->> 'attempts')::int, 0), 'maxAttempts', (t.data ->> 'maxAttempts')::int), \
+              'description', 'Task timed out on its last allowed attempt'))) \
+        WHEN 'expired' THEN jsonb_build_object('status', 'expired', 'updated', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ')) \
+        ELSE jsonb_build_object('progress', 0, 'status', 'ready', 'owner', null, 'deadline', null, 'updated', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ')) \
+        END || jsonb_build_object( \
+            'history', COALESCE(t.data -> 'history', '[]'::jsonb) \
+            || CASE WHEN r.yielded THEN '[]'::jsonb ELSE jsonb_build_array(jsonb_build_object( \
+              'typ', 'TaskTimeout', \
+              'time', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ'), \
+              'worker', t.data ->> 'owner', \
+              'progress', (t.data ->> 'progress')::float, \
+              'actor', $6::text, \
+              'reason', $7::text)) END \
+            || CASE WHEN r.outcome = 'ready' THEN '[]'::jsonb ELSE jsonb_build_array(jsonb_build_object( \
+              'typ', CASE r.outcome WHEN 'aborted' THEN 'TaskAbortion' ELSE 'TaskExpiry' END, \
+              'time', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ'), \
+              'worker', t.data ->> 'owner', \
+              'progress', (t.data ->> 'progress')::float, \
+              'actor', COALESCE($6::text, $3::text), \
+              'reason', CASE r.outcome WHEN 'aborted' THEN $2::text ELSE $4::text END)) END, \
+            'version', COALESCE((t.data ->> 'version')::bigint, 0) + 1) \
+    FROM timed_out r \
+    WHERE t.data ->> 'rn' = r.rn AND t.data ->> 'status' = 'running' \
+    AND CASE WHEN $5::text IS NULL THEN t.data ->> 'deadline' < $1 ELSE t.data ->> 'owner' = $5 END \
+    RETURNING t.data";
+
+const YIELD_WORKER_TASK_SQL: &str = "
+    UPDATE task t SET data = t.data || jsonb_build_object( \
+        'deadline', to_char(timezone('UTC'::text, now() - interval '1 second'), 'YYYY-MM-DD\"T\"HH24:MI:SS.US\"Z\"'), \
+        'updated', $2::jsonb, \
+        'history', COALESCE(t.data -> 'history', '[]'::jsonb) || jsonb_build_array($3::jsonb || jsonb_build_object('progress', t.data -> 'progress')), \
+        'version', COALESCE((t.data ->> 'version')::bigint, 0) + 1) \
+    where t.data ->> 'status' = 'running' AND t.data ->> 'owner' = $1 \
+    returning t.data";
+
+const RESOLVE_BLOCKED_BATCH_TASK_SQL: &str = "
+    WITH resolved AS ( \
+        SELECT c.data ->> 'rn' AS rn, \
+            (SELECT jsonb_build_object('rn', d.rn, 'status', p.data ->> 'status') FROM jsonb_array_elements_text(c.data -> 'dependsOn') d(rn) \
+                LEFT JOIN task p ON p.data ->> 'rn' = d.rn \
+                WHERE p.data IS NULL OR p.data ->> 'status' IN ('aborted', 'cancelled', 'expired') LIMIT 1) AS failed_parent, \
+            (SELECT count(*) FROM task p \
+                WHERE p.data ->> 'rn' IN (SELECT jsonb_array_elements_text(c.data -> 'dependsOn')) \
+                AND p.data ->> 'status' = 'completed') \
+            = (SELECT count(DISTINCT parent) FROM jsonb_array_elements_text(c.data -> 'dependsOn') parent) AS parents_completed \
+        FROM task c WHERE c.data ->> 'status' = 'blocked' \
+    ) \
+    UPDATE task t SET data = t.data || CASE WHEN r.failed_parent IS NOT NULL \
+        THEN jsonb_build_object('status', CASE WHEN t.data ->> 'onDependencyFailure' = 'cancel' THEN 'cancelled' ELSE 'aborted' END, \
+            'updated', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ'), \
+            'errors', COALESCE(t.data -> 'errors', '[]'::jsonb) || jsonb_build_array(jsonb_build_object( \
+              'code', $1::text, \
+              'args', r.failed_parent, \
+              'description', 'Task it depends on has been aborted, cancelled, expired or deleted')), \
+            'history', COALESCE(t.data -> 'history', '[]'::jsonb) || jsonb_build_array(jsonb_build_object( \
+              'typ', CASE WHEN t.data ->> 'onDependencyFailure' = 'cancel' THEN 'TaskCancellation' ELSE 'TaskAbortion' END, \
+              'time', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ'), \
+              'worker', '', \
+              'progress', (t.data ->> 'progress')::float, \
+              'actor', $2::text, \
+              'reason', $1::text))) \
+        ELSE jsonb_build_object('status', 'ready', 'updated', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ')) \
+        END || jsonb_build_object('version', COALESCE((t.data ->> 'version')::bigint, 0) + 1) \
+    FROM resolved r \
+    WHERE t.data ->> 'rn' = r.rn AND t.data ->> 'status' = 'blocked' AND (r.failed_parent IS NOT NULL OR r.parents_completed) \
+    RETURNING t.data";
+
+const CANCEL_BATCH_TASK_SQL: &str = "
+    UPDATE task t SET data = t.data || jsonb_build_object('status', 'cancelled', 'updated', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ'), \
+        'history', COALESCE(t.data -> 'history', '[]'::jsonb) || jsonb_build_array(jsonb_build_object( \
+          'typ', 'TaskCancellation', \
+          'time', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ'), \
+          'worker', COALESCE(t.data ->> 'owner', ''), \
+          'progress', (t.data ->> 'progress')::float, \
+          'actor', $2::text, \
+          'reason', $3::text)), \
+        'version', COALESCE((t.data ->> 'version')::bigint, 0) + 1) \
+    where t.data ->> 'status' = 'cancelling' \
+        AND ((t.data -> 'cancelRequested' ->> 'time')::timestamptz <= now() - $1::bigint * interval '1 second' OR (t.data ->> 'deadline')::timestamptz < now()) \
+    returning t.data";
+
+const UPSERT_QUEUE_SQL: &str = "
+    INSERT INTO queue(data) VALUES ($1) \
+    ON CONFLICT ((data->>'name')) \
+    DO UPDATE SET data = EXCLUDED.data \
+    RETURNING data::JSONB
+  ";
+const GET_QUEUE_SQL: &str = "
+        Select data::JSONB from queue \
+        where data ->> 'name' = $1 \
+      ";
+const UPSERT_QUEUE_STATE_SQL: &str = "
+    INSERT INTO queue_state(data) VALUES ($1) \
+    ON CONFLICT ((data->>'name')) \
+    DO UPDATE SET data = EXCLUDED.data \
+    RETURNING data::JSONB
+  ";
+const GET_QUEUE_STATE_SQL: &str = "
+        Select data::JSONB from queue_state \
+        where data ->> 'name' = $1 \
+      ";
+const GET_DRAINING_QUEUE_STATES_SQL: &str = "
+        Select data::JSONB from queue_state \
+        where data ->> 'name' = ANY($1) AND data ->> 'mode' = 'draining' \
+      ";
+const GET_MISSING_TASK_RNS_SQL: &str = "
+        Select to_jsonb(d.rn) from unnest($1::text[]) AS d(rn) \
+        where NOT EXISTS (Select 1 from task t where t.data ->> 'rn' = d.rn) \
+      ";
+const GET_DEPENDENCY_CYCLE_SQL: &str = "
+        WITH RECURSIVE batch AS ( \
+            Select b.data from unnest($1::jsonb[]) AS b(data) \
+            where NOT EXISTS (Select 1 from task t where t.data ->> 'rn' = b.data ->> 'rn') \
+        ), reachable(origin, rn) AS ( \
+            Select b.data ->> 'rn', d.rn from batch b, jsonb_array_elements_text(COALESCE(b.data -> 'dependsOn', '[]'::jsonb)) d(rn) \
+            UNION \
+            Select r.origin, d.rn from reachable r \
+            JOIN (Select data from batch UNION ALL Select data from task) n ON n.data ->> 'rn' = r.rn, \
+            jsonb_array_elements_text(COALESCE(n.data -> 'dependsOn', '[]'::jsonb)) d(rn) \
+        ) \
+        Select to_jsonb(origin) from reachable where origin = rn limit 1 \
+      ";
+const GET_RATE_LIMIT_BUDGET_SQL: &str = "
+        Select jsonb_build_object('name', r.name, 'remaining', floor(r.tokens)::int, 'availableAt', \
+            statement_timestamp() + GREATEST(1 - r.tokens, 0) * r.interval_in_secs / r.max_starts * interval '1 second') from ( \
+            Select q.data ->> 'name' AS name, rate_limit_tokens(q.data, b.data) AS tokens, \
+                (q.data #>> '{rateLimit,maxStarts}')::float8 AS max_starts, (q.data #>> '{rateLimit,intervalInSecs}')::float8 AS interval_in_secs \
+            from queue q LEFT JOIN queue_rate_limit b ON b.data ->> 'name' = q.data ->> 'name' \
+            where q.data ->> 'name' = $1 AND q.data ->> 'rateLimit' IS NOT NULL) r \
+      ";
+const UPSERT_SCHEDULE_SQL: &str = "
+    INSERT INTO schedule(data) VALUES ($1) \
+    ON CONFLICT ((data->>'name')) \
+    DO UPDATE SET data = EXCLUDED.data || jsonb_build_object('created', schedule.data -> 'created', 'lastFired', schedule.data -> 'lastFired') \
+    RETURNING data::JSONB
+  ";
+const GET_SCHEDULES_SQL: &str = "
+        Select data::JSONB from schedule \
+        order by data ->> 'name'
+      ";
+const GET_SCHEDULE_SQL: &str = "
+        Select data::JSONB from schedule \
+        where data ->> 'name' = $1 \
+      ";
+const DELETE_SCHEDULE_SQL: &str = "
+    DELETE from schedule where data ->> 'name' = $1
+";
+const MARK_SCHEDULE_FIRED_SQL: &str = "
+    UPDATE schedule SET data = data || jsonb_build_object('lastFired', $2::text) \
+    where data ->> 'name' = $1 AND (data ->> 'lastFired' IS NULL OR (data ->> 'lastFired')::timestamptz < $2::text::timestamptz)
+";
 
+const REGISTER_WORKER_SQL: &str = "
+    INSERT INTO worker(data) VALUES ($1::jsonb || jsonb_build_object('id', 'worker-' || nextval('worker_id_seq'))) \
+    RETURNING data::JSONB
+  ";
+const HEARTBEAT_WORKER_SQL: &str = "
+    UPDATE worker SET data = data || jsonb_build_object('heartbeat', $2::jsonb) \
+    where data ->> 'id' = $1 \
+    RETURNING data::JSONB
+  ";
+const GET_WORKER_SQL: &str = "
+        Select data::JSONB from worker \
+        where data ->> 'id' = $1 \
+      ";
+const GET_WORKERS_SQL: &str = "
+        Select data::JSONB from worker order by data ->> 'registered'
+      ";
+const SET_WORKER_DRAINING_SQL: &str = "
+    UPDATE worker SET data = data || jsonb_build_object('draining', $2::bool) \
+    where data ->> 'id' = $1 \
+    RETURNING data::JSONB
+  ";
+const DELETE_WORKER_SQL: &str = "
+    DELETE from worker where data ->> 'id' = $1
+";
 const DELETE_BATCH_TASK_SQL: &str = "
-    DELETE from task where data ->> 'status' in ('completed', 'cancelled', 'aborted') AND data ->> 'updated' < $1
+    DELETE from task t where t.data ->> 'status' in ('completed', 'cancelled', 'aborted', 'expired') \
+        AND (t.data ->> 'updated')::timestamptz < now() - COALESCE( \
+            (SELECT (q.data ->> 'retentionPeriodInSecs')::bigint FROM queue q WHERE q.data ->> 'name' = t.data ->> 'queue'), $1::bigint) * interval '1 second'
 ";
 
+const EXPIRE_BATCH_TASK_SQL: &str = "
+    UPDATE task t SET data = t.data || jsonb_build_object('status', 'expired', 'updated', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ'), \
+        'history', COALESCE(t.data -> 'history', '[]'::jsonb) || jsonb_build_array(jsonb_build_object( \
+          'typ', 'TaskExpiry', \
+          'time', to_char(timezone('UTC'::text, now()), 'YYYY-MM-DD HH24:MI:SS.MSZ'), \
+          'worker', '', \
+          'progress', (t.data ->> 'progress')::float, \
+          'actor', $1::text, \
+          'reason', $2::text)), \
+        'version', COALESCE((t.data ->> 'version')::bigint, 0) + 1) \
+    where t.data ->> 'status' IN ('ready', 'blocked') AND (t.data ->> 'expiresAt')::timestamptz <= now() \
+    returning t.data";
+
 pub struct PgAdapter {
     pub pool: Pool,
 }
 
+impl PgAdapter {
+    /// Tasks cannot depend on themselves through tasks which exist already, e.g. on a task which has been deleted since
+    /// and is added again. They would stay blocked, as monitor never resolves their dependencies.
+    /// # Errors
+    /// Returns `ScyllaOpsError` if a task depends on itself
+    async fn validate_dependencies(&self, tasks: &[Task]) -> Result<(), PgAdapterError> {
+        if tasks.iter().all(|task| task.depends_on.is_empty()) {
+            return Ok(());
+        }
+        let cycle: Vec<String> = self
+            .execute(GET_DEPENDENCY_CYCLE_SQL, &[&prepare_batch_insert_tasks(tasks)], IsolationLevel::RepeatableRead)
+            .await?;
+        match cycle.into_iter().next() {
+            Some(rn) => Err(ScyllaOperationsError::ValidationFailed(format!("Task {rn} depends on itself through the tasks it depends on")).into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Task depended on which neither exists nor is inserted along with the tasks, if any. Tasks depending on it are
+    /// not inserted, otherwise they would be aborted by monitor right away.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    async fn missing_dependency(&self, tasks: &[Task]) -> Result<Option<String>, PgAdapterError> {
+        let rns: BTreeSet<&String> = tasks.iter().map(|task| &task.rn).collect();
+        let dependencies: Vec<&String> = tasks.iter().flat_map(|task| &task.depends_on).filter(|rn| !rns.contains(rn)).collect();
+        if dependencies.is_empty() {
+            return Ok(None);
+        }
+        let missing: Vec<String> = self.execute(GET_MISSING_TASK_RNS_SQL, &[&dependencies], IsolationLevel::RepeatableRead).await?;
+        Ok(missing.into_iter().next())
+    }
+
+    /// Reason for which the task has been neither inserted nor replaced.
+    /// # Errors
+    /// Returns `QueueDraining` if the queue of the task is draining, `NoTaskFound` for a missing task it depends on,
+    /// `DuplicateTask` otherwise
+    async fn not_inserted(&self, task: Task) -> Result<Task, PgAdapterError> {
+        if self.query_queue_state(task.queue.clone()).await?.mode == QueueMode::Draining {
+            return Err(PgAdapterError::QueueDraining(task.queue));
+        }
+        if let Some(rn) = self.missing_dependency(std::slice::from_ref(&task)).await? {
+            return Err(PgAdapterError::NoTaskFound(rn));
+        }
+        Err(PgAdapterError::DuplicateTask(task.rn))
+    }
+}
+
 #[async_trait]
 pub trait DbExecute {
-    async fn execute(&self, sql: &str, params: &[&(dyn ToSql + Sync)], isolation_level: IsolationLevel) -> Result<Vec<Task>, PgAdapterError>;
+    async fn execute<T: DeserializeOwned + Send>(
+        &self,
+        sql: &str,
+        params: &[&(dyn ToSql + Sync)],
+        isolation_level: IsolationLevel,
+    ) -> Result<Vec<T>, PgAdapterError>;
     async fn execute_count(&self, sql: &str, params: &[&(dyn ToSql + Sync)], isolation_level: IsolationLevel) -> Result<u64, PgAdapterError>;
+    /// Runs `lock_sql` and then `sql` in the same read committed transaction, returning the rows of `sql`.
+    async fn execute_after_lock<T: DeserializeOwned + Send>(
+        &self,
+        lock_sql: &str,
+        lock_params: &[&(dyn ToSql + Sync)],
+        sql: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<T>, PgAdapterError>;
 }
 
 #[async_trait]
 impl DbExecute for PgAdapter {
-    async fn execute(&self, sql: &str, params: &[&(dyn ToSql + Sync)], isolation_level: IsolationLevel) -> Result<Vec<Task>, PgAdapterError> {
+    async fn execute<T: DeserializeOwned + Send>(
+        &self,
+        sql: &str,
+        params: &[&(dyn ToSql + Sync)],
+        isolation_level: IsolationLevel,
+    ) -> Result<Vec<T>, PgAdapterError> {
         let max_tries = 10;
         let mut try_count = 1;
-        let mut tasks: Option<Vec<Task>> = None;
+        let mut tasks: Option<Vec<T>> = None;
         let error: Option<PgAdapterError>;
         loop {
             let mut client: Client = self.pool.get().await?;
@@ -96,7 +562,7 @@ impl DbExecute for PgAdapter {
                     tasks = Some(
                         rows.into_iter()
                             .map(|row| {
-                                let task_value: Task = from_value(row.get(0)).unwrap();
+                                let task_value: T = from_value(row.get(0)).unwrap();
                                 task_value
                             })
                             .collect(),
@@ -160,6 +626,42 @@ impl DbExecute for PgAdapter {
             }
         }
     }
+    async fn execute_after_lock<T: DeserializeOwned + Send>(
+        &self,
+        lock_sql: &str,
+        lock_params: &[&(dyn ToSql + Sync)],
+        sql: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<T>, PgAdapterError> {
+        let mut client: Client = self.pool.get().await?;
+        let lock_stmt = client.prepare_cached(lock_sql).await?;
+        let stmt = client.prepare_cached(sql).await?;
+        let tx = client.build_transaction().isolation_level(IsolationLevel::ReadCommitted).start().await?;
+
+        let result = match tx.query(&lock_stmt, lock_params).await {
+            Ok(_) => tx.query(&stmt, params).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(rows) => {
+                debug!("row count : {} returned from query : {} for params: {:?}", rows.len(), sql, params);
+                let values = rows.into_iter().map(|row| from_value(row.get(0)).unwrap()).collect();
+                match tx.commit().await {
+                    Ok(()) => Ok(values),
+                    Err(commit_err) => {
+                        log::error!("commit for tx failed: {}", commit_err);
+                        Err(PgAdapterError::DbError(commit_err))
+                    }
+                }
+            }
+            Err(e) => {
+                if let Err(rollback_err) = tx.rollback().await {
+                    log::error!("rollback for tx failed: {}", rollback_err);
+                }
+                Err(PgAdapterError::DbError(e))
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -167,31 +669,131 @@ impl Persistence for PgAdapter {
     type PersistenceError = PgAdapterError;
 
     async fn insert(&self, task: Task) -> Result<Task, PgAdapterError> {
+        self.validate_dependencies(std::slice::from_ref(&task)).await?;
         let execute_resp = &self
             .execute(INSERT_TASK_SQL, &[&prepare_insert_task(&task)], IsolationLevel::RepeatableRead)
             .await?;
-        let t = handle_insert_return(execute_resp, &task)?;
-        Ok(t.clone())
+        match handle_insert_return(execute_resp, &task) {
+            Ok(t) => Ok(t.clone()),
+            Err(PgAdapterError::DuplicateTask(_)) => self.not_inserted(task).await,
+            Err(e) => Err(e),
+        }
     }
 
     async fn batch_insert(&self, tasks: Vec<Task>) -> Result<TaskBatch, PgAdapterError> {
+        let queues: Vec<String> = tasks.iter().map(|task| task.queue.clone()).collect();
+        let draining: Vec<QueueState> = self.execute(GET_DRAINING_QUEUE_STATES_SQL, &[&queues], IsolationLevel::RepeatableRead).await?;
+        if let Some(queue_state) = draining.into_iter().next() {
+            return Err(PgAdapterError::QueueDraining(queue_state.name));
+        }
+        self.validate_dependencies(&tasks).await?;
         let execute_resp = &self
             .execute(INSERT_BATCH_TASKS_SQL, &[&prepare_batch_insert_tasks(&tasks)], IsolationLevel::RepeatableRead)
             .await?;
+        // whole batch is rejected if any task depends on a task which does not exist
+        if execute_resp.is_empty() {
+            if let Some(rn) = self.missing_dependency(&tasks).await? {
+                return Err(PgAdapterError::NoTaskFound(rn));
+            }
+        }
         Ok(handle_batch_insert_tasks_return(execute_resp, &tasks))
     }
 
+    async fn supersede(&self, task: Task, cancel_running: bool) -> Result<Task, PgAdapterError> {
+        self.validate_dependencies(std::slice::from_ref(&task)).await?;
+        // serializable, so that concurrent submissions under the same supersession key do not both insert
+        let execute_resp = &self
+            .execute(
+                SUPERSEDE_TASK_SQL,
+                &[&prepare_insert_task(&task), &cancel_running, &SUPERSEDED_ERROR_CODE, &SUPERSESSION_ACTOR],
+                IsolationLevel::Serializable,
+            )
+            .await?;
+        match handle_insert_return(execute_resp, &task) {
+            Ok(t) => Ok(t.clone()),
+            Err(PgAdapterError::DuplicateTask(_)) => self.not_inserted(task).await,
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn resubmit(&self, task: Task, previous_version: i64) -> Result<Task, PgAdapterError> {
+        // serializable, so that the task is not resubmitted twice concurrently
+        let execute_resp = &self
+            .execute(
+                RESUBMIT_TASK_SQL,
+                &[&prepare_insert_task(&task), &previous_version],
+                IsolationLevel::Serializable,
+            )
+            .await?;
+        match handle_insert_return(execute_resp, &task) {
+            Ok(t) => Ok(t.clone()),
+            Err(PgAdapterError::DuplicateTask(rn)) => match self.query_by_rn(rn.clone()).await {
+                Ok(_) => Err(PgAdapterError::DuplicateTask(rn)),
+                Err(PgAdapterError::NoTaskFound(_)) => match self.query_queue_state(task.queue.clone()).await?.mode {
+                    QueueMode::Draining => Err(PgAdapterError::QueueDraining(task.queue)),
+                    // task to resubmit has been updated by someone else since it was read
+                    _ => Err(PgAdapterError::ConcurrentModification(task.previous_rn.unwrap_or_default())),
+                },
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
     async fn update(&self, task: Task) -> Result<Task, PgAdapterError> {
         let up = prepare_update_task(&task);
-        let execute_resp = &self.execute(UPDATE_TASK_SQL, &[&up.json_task, &up.rn], IsolationLevel::RepeatableRead).await?;
-        let t = handle_update_return(execute_resp, &task)?;
-        Ok(t.clone())
+        let execute_resp = &self
+            .execute(UPDATE_TASK_SQL, &[&up.json_task, &up.rn, &up.version], IsolationLevel::RepeatableRead)
+            .await?;
+        match handle_update_return(execute_resp, &task) {
+            Ok(t) => Ok(t.clone()),
+            Err(PgAdapterError::NoTaskFound(rn)) => {
+                // task still exists, so it has been updated by someone else since it was read
+                self.query_by_rn(rn.clone()).await?;
+                Err(PgAdapterError::ConcurrentModification(rn))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn lease(&self, task: Task) -> Result<Task, PgAdapterError> {
+        let up = prepare_update_task(&task);
+        let execute_resp = &self
+            .execute_after_lock(
+                LOCK_LIMITED_QUEUES_SQL,
+                &[&task.queue],
+                LEASE_TASK_SQL,
+                &[&up.json_task, &up.rn, &up.version, &task.queue],
+            )
+            .await?;
+        match handle_update_return(execute_resp, &task) {
+            Ok(t) => Ok(t.clone()),
+            Err(PgAdapterError::NoTaskFound(rn)) => {
+                if self.query_by_rn(rn.clone()).await?.version != task.version {
+                    return Err(PgAdapterError::ConcurrentModification(rn));
+                }
+                // task is unchanged since it was read, so it was held back by its queue being paused or by its limits
+                if self.query_queue_state(task.queue.clone()).await?.mode == QueueMode::Paused {
+                    return Err(PgAdapterError::QueuePaused(task.queue));
+                }
+                match self.query_rate_limit_budget(task.queue.clone()).await {
+                    Ok(budget) if budget.remaining < 1 => Err(PgAdapterError::RateLimited(task.queue)),
+                    Ok(_) | Err(PgAdapterError::NoRateLimitFound(_)) => Err(PgAdapterError::ConcurrencyLimitReached(task.queue)),
+                    Err(e) => Err(e),
+                }
+            }
+            Err(e) => Err(e),
+        }
     }
 
     async fn query(&self, get_task_model: &GetTaskModel) -> Result<Vec<Task>, PgAdapterError> {
         let qp = prepare_query_task(get_task_model);
-        self.execute(GET_TASKS_SQL, &[&qp.status, &qp.queue, &qp.worker, &qp.limit], IsolationLevel::RepeatableRead)
-            .await
+        self.execute(
+            GET_TASKS_SQL,
+            &[&qp.status, &qp.queue, &qp.worker, &qp.limit, &qp.labels.equals, &qp.labels.any_of],
+            IsolationLevel::RepeatableRead,
+        )
+        .await
     }
 
     async fn query_by_rn(&self, rn: String) -> Result<Task, PgAdapterError> {
@@ -200,8 +802,14 @@ impl Persistence for PgAdapter {
         Ok(t.clone())
     }
 
-    async fn lease_batch(&self, queue: String, limit: i32, worker: String, task_timeout_in_secs: i64) -> Result<Vec<Task>, Self::PersistenceError> {
-        let deadline = Json(json!(Utc::now() + Duration::seconds(task_timeout_in_secs)));
+    async fn lease_batch(
+        &self,
+        queue: String,
+        limit: i32,
+        worker: String,
+        task_timeout_in_secs: Option<i64>,
+        labels: Vec<LabelSelector>,
+    ) -> Result<Vec<Task>, Self::PersistenceError> {
         let updated = Json(json!(Utc::now()));
         let worker_json = Json(json!(worker));
         let task_history = Json(json!(TaskHistory {
@@ -209,26 +817,216 @@ impl Persistence for PgAdapter {
             time: Utc::now(),
             worker: worker.clone(),
             progress: Some(0.0),
+            actor: None,
+            reason: None,
         }));
+        let label_params = prepare_label_selectors(&labels);
 
-        self.execute(
+        self.execute_after_lock(
+            LOCK_LIMITED_QUEUES_SQL,
+            &[&queue],
             LEASE_N_TASK_SQL,
-            &[&queue, &limit, &worker_json, &deadline, &updated, &task_history],
+            &[
+                &queue,
+                &limit,
+                &worker_json,
+                &task_timeout_in_secs,
+                &updated,
+                &task_history,
+                &label_params.equals,
+                &label_params.any_of,
+                &DEFAULT_LEASE_TIMEOUT_IN_SECS,
+            ],
+        )
+        .await
+    }
+
+    async fn heartbeat_batch(
+        &self,
+        worker: String,
+        heartbeats: Vec<TaskHeartbeat>,
+        task_timeout_in_secs: Option<i64>,
+    ) -> Result<Vec<Task>, Self::PersistenceError> {
+        let updated = Json(json!(Utc::now()));
+        self.execute(
+            HEARTBEAT_BATCH_TASK_SQL,
+            &[
+                &Json(json!(heartbeats)),
+                &worker,
+                &task_timeout_in_secs,
+                &updated,
+                &DEFAULT_LEASE_TIMEOUT_IN_SECS,
+            ],
             IsolationLevel::ReadCommitted,
         )
         .await
     }
 
     async fn delete_batch(&self, retention_time_in_secs: i64) -> Result<u64, Self::PersistenceError> {
-        let deletion_time = format!("{:?}", Utc::now() - Duration::seconds(retention_time_in_secs));
-        self.execute_count(DELETE_BATCH_TASK_SQL, &[&deletion_time], IsolationLevel::RepeatableRead)
+        self.execute_count(DELETE_BATCH_TASK_SQL, &[&retention_time_in_secs], IsolationLevel::RepeatableRead)
             .await
     }
 
     async fn reset_batch(&self) -> Result<Vec<Task>, Self::PersistenceError> {
         let deadline = format!("{:?}", Utc::now());
+        let owner: Option<String> = None;
+
+        self.execute(
+            RESET_BATCH_TASK_SQL,
+            &[
+                &deadline,
+                &MAX_ATTEMPTS_EXCEEDED_ERROR_CODE,
+                &MONITOR_ACTOR,
+                &EXPIRED_REASON,
+                &owner,
+                &owner,
+                &owner,
+            ],
+            IsolationLevel::RepeatableRead,
+        )
+        .await
+    }
+
+    async fn reset_worker_batch(&self, worker: String, actor: String) -> Result<Vec<Task>, Self::PersistenceError> {
+        let deadline = format!("{:?}", Utc::now());
+
+        self.execute(
+            RESET_BATCH_TASK_SQL,
+            &[
+                &deadline,
+                &MAX_ATTEMPTS_EXCEEDED_ERROR_CODE,
+                &MONITOR_ACTOR,
+                &EXPIRED_REASON,
+                &worker,
+                &actor,
+                &WORKER_RESET_REASON,
+            ],
+            IsolationLevel::RepeatableRead,
+        )
+        .await
+    }
+
+    async fn yield_worker_batch(&self, worker: String) -> Result<Vec<Task>, Self::PersistenceError> {
+        let updated = Json(json!(Utc::now()));
+        let task_history = Json(json!(TaskHistory {
+            typ: TaskHistoryType::Yield,
+            time: Utc::now(),
+            worker: worker.clone(),
+            progress: None,
+            actor: None,
+            reason: None,
+        }));
+        self.execute(YIELD_WORKER_TASK_SQL, &[&worker, &updated, &task_history], IsolationLevel::ReadCommitted)
+            .await
+    }
+
+    async fn expire_batch(&self) -> Result<Vec<Task>, Self::PersistenceError> {
+        self.execute(EXPIRE_BATCH_TASK_SQL, &[&MONITOR_ACTOR, &EXPIRED_REASON], IsolationLevel::RepeatableRead)
+            .await
+    }
+
+    async fn cancel_batch(&self, grace_period_in_secs: i64) -> Result<Vec<Task>, Self::PersistenceError> {
+        self.execute(
+            CANCEL_BATCH_TASK_SQL,
+            &[&grace_period_in_secs, &MONITOR_ACTOR, &CANCELLATION_NOT_ACKNOWLEDGED_REASON],
+            IsolationLevel::RepeatableRead,
+        )
+        .await
+    }
+
+    async fn resolve_blocked_batch(&self) -> Result<Vec<Task>, Self::PersistenceError> {
+        self.execute(
+            RESOLVE_BLOCKED_BATCH_TASK_SQL,
+            &[&DEPENDENCY_FAILED_ERROR_CODE, &MONITOR_ACTOR],
+            IsolationLevel::RepeatableRead,
+        )
+        .await
+    }
+
+    async fn upsert_queue(&self, queue: Queue) -> Result<Queue, PgAdapterError> {
+        let execute_resp = self
+            .execute(UPSERT_QUEUE_SQL, &[&to_value(&queue).unwrap()], IsolationLevel::RepeatableRead)
+            .await?;
+        execute_resp.into_iter().next().ok_or(PgAdapterError::NoQueueFound(queue.name))
+    }
+
+    async fn query_queue_by_name(&self, name: String) -> Result<Queue, PgAdapterError> {
+        let execute_resp = self.execute(GET_QUEUE_SQL, &[&name], IsolationLevel::RepeatableRead).await?;
+        execute_resp.into_iter().next().ok_or(PgAdapterError::NoQueueFound(name))
+    }
+
+    async fn upsert_queue_state(&self, queue_state: QueueState) -> Result<QueueState, PgAdapterError> {
+        let execute_resp = self
+            .execute(UPSERT_QUEUE_STATE_SQL, &[&to_value(&queue_state).unwrap()], IsolationLevel::RepeatableRead)
+            .await?;
+        execute_resp.into_iter().next().ok_or(PgAdapterError::NoQueueFound(queue_state.name))
+    }
+
+    async fn query_queue_state(&self, name: String) -> Result<QueueState, PgAdapterError> {
+        let execute_resp = self.execute(GET_QUEUE_STATE_SQL, &[&name], IsolationLevel::RepeatableRead).await?;
+        Ok(execute_resp.into_iter().next().unwrap_or(QueueState { name, mode: QueueMode::Active }))
+    }
+
+    async fn query_rate_limit_budget(&self, name: String) -> Result<RateLimitBudget, PgAdapterError> {
+        let execute_resp = self.execute(GET_RATE_LIMIT_BUDGET_SQL, &[&name], IsolationLevel::RepeatableRead).await?;
+        execute_resp.into_iter().next().ok_or(PgAdapterError::NoRateLimitFound(name))
+    }
+
+    async fn upsert_schedule(&self, schedule: Schedule) -> Result<Schedule, PgAdapterError> {
+        let execute_resp = self
+            .execute(UPSERT_SCHEDULE_SQL, &[&to_value(&schedule).unwrap()], IsolationLevel::RepeatableRead)
+            .await?;
+        execute_resp.into_iter().next().ok_or(PgAdapterError::NoScheduleFound(schedule.name))
+    }
+
+    async fn query_schedules(&self) -> Result<Vec<Schedule>, PgAdapterError> {
+        self.execute(GET_SCHEDULES_SQL, &[], IsolationLevel::RepeatableRead).await
+    }
+
+    async fn query_schedule_by_name(&self, name: String) -> Result<Schedule, PgAdapterError> {
+        let execute_resp = self.execute(GET_SCHEDULE_SQL, &[&name], IsolationLevel::RepeatableRead).await?;
+        execute_resp.into_iter().next().ok_or(PgAdapterError::NoScheduleFound(name))
+    }
+
+    async fn delete_schedule(&self, name: String) -> Result<u64, PgAdapterError> {
+        self.execute_count(DELETE_SCHEDULE_SQL, &[&name], IsolationLevel::RepeatableRead).await
+    }
+
+    async fn mark_schedule_fired(&self, name: String, fire_time: DateTime<Utc>) -> Result<u64, PgAdapterError> {
+        let fire_time = format!("{fire_time:?}");
+        self.execute_count(MARK_SCHEDULE_FIRED_SQL, &[&name, &fire_time], IsolationLevel::RepeatableRead)
+            .await
+    }
+
+    async fn register_worker(&self, worker: Worker) -> Result<Worker, PgAdapterError> {
+        let execute_resp = self
+            .execute(REGISTER_WORKER_SQL, &[&to_value(&worker).unwrap()], IsolationLevel::ReadCommitted)
+            .await?;
+        execute_resp.into_iter().next().ok_or(PgAdapterError::NoWorkerFound(worker.id))
+    }
+
+    async fn heartbeat_worker(&self, id: String) -> Result<Worker, PgAdapterError> {
+        let heartbeat = Json(json!(Utc::now()));
+        let execute_resp = self.execute(HEARTBEAT_WORKER_SQL, &[&id, &heartbeat], IsolationLevel::ReadCommitted).await?;
+        execute_resp.into_iter().next().ok_or(PgAdapterError::NoWorkerFound(id))
+    }
+
+    async fn query_worker_by_id(&self, id: String) -> Result<Worker, PgAdapterError> {
+        let execute_resp = self.execute(GET_WORKER_SQL, &[&id], IsolationLevel::RepeatableRead).await?;
+        execute_resp.into_iter().next().ok_or(PgAdapterError::NoWorkerFound(id))
+    }
+
+    async fn query_workers(&self) -> Result<Vec<Worker>, PgAdapterError> {
+        self.execute(GET_WORKERS_SQL, &[], IsolationLevel::RepeatableRead).await
+    }
+
+    async fn set_worker_draining(&self, id: String, draining: bool) -> Result<Worker, PgAdapterError> {
+        let execute_resp = self.execute(SET_WORKER_DRAINING_SQL, &[&id, &draining], IsolationLevel::ReadCommitted).await?;
+        execute_resp.into_iter().next().ok_or(PgAdapterError::NoWorkerFound(id))
+    }
 
-        self.execute(RESET_BATCH_TASK_SQL, &[&deadline], IsolationLevel::RepeatableRead).await
+    async fn delete_worker(&self, id: String) -> Result<u64, PgAdapterError> {
+        self.execute_count(DELETE_WORKER_SQL, &[&id], IsolationLevel::RepeatableRead).await
     }
 }
 
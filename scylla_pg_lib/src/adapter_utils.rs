@@ -1,8 +1,8 @@
 use std::collections::HashSet;
 
 use crate::error::PgAdapterError;
-use scylla_models::{GetTaskModel, Task, TaskBatch};
-use serde_json::to_value;
+use scylla_models::{GetTaskModel, LabelSelector, Task, TaskBatch};
+use serde_json::{json, to_value, Map};
 
 /// # Panics
 /// In case task cannot be converted to `serde_json::Value`
@@ -45,6 +45,7 @@ pub fn handle_batch_insert_tasks_return(tasks: &[Task], original_tasks: &[Task])
 pub struct UpdateParams {
     pub json_task: serde_json::Value,
     pub rn: String,
+    pub version: i64,
 }
 /// # Panics
 /// In case task cannot be converted to `serde_json::Value`
@@ -52,6 +53,7 @@ pub fn prepare_update_task(task: &Task) -> UpdateParams {
     UpdateParams {
         rn: task.rn.clone(),
         json_task: to_value(task).unwrap(),
+        version: task.version,
     }
 }
 /// # Errors
@@ -71,13 +73,44 @@ pub struct QueryParams {
     pub queue: String,
     pub worker: String,
     pub limit: i32,
+    pub labels: LabelParams,
+}
+/// Equality selectors are matched by containment, which is served by the labels index.
+/// Set membership selectors are matched one by one.
+#[derive(PartialEq, Eq, Debug)]
+pub struct LabelParams {
+    pub equals: serde_json::Value,
+    pub any_of: serde_json::Value,
+}
+pub fn prepare_label_selectors(label_selectors: &[LabelSelector]) -> LabelParams {
+    let mut equals = Map::new();
+    let mut any_of = Vec::new();
+    for label_selector in label_selectors {
+        match label_selector {
+            LabelSelector::Equals(key, value) => {
+                equals.insert(key.clone(), json!(value));
+            }
+            LabelSelector::In(key, values) => any_of.push(json!({ "key": key, "values": values })),
+        }
+    }
+    LabelParams {
+        equals: serde_json::Value::Object(equals),
+        any_of: serde_json::Value::Array(any_of),
+    }
 }
 pub fn prepare_query_task(get_task_model: &GetTaskModel) -> QueryParams {
     let status = get_task_model.status.clone().map_or_else(|| '%'.to_string(), |s| s.to_string().to_lowercase());
     let queue = get_task_model.queue.clone().map_or_else(|| '%'.to_string(), |q| q);
     let worker = get_task_model.worker.clone().map_or_else(|| '%'.to_string(), |w| w);
     let limit = get_task_model.limit.map_or_else(|| 100, |l| l);
-    QueryParams { status, queue, worker, limit }
+    let labels = prepare_label_selectors(&get_task_model.labels);
+    QueryParams {
+        status,
+        queue,
+        worker,
+        limit,
+        labels,
+    }
 }
 /// # Errors
 /// Returns `PgAdapterError::NoTaskFound` Error
@@ -211,12 +244,14 @@ mod tests {
     fn prepare_update_task_cases() {
         let t = Task {
             rn: "123".to_string(),
+            version: 4,
             ..Task::default()
         };
         assert_eq!(
             prepare_update_task(&t),
             UpdateParams {
                 rn: t.rn.clone(),
+                version: 4,
                 json_task: to_value(t).unwrap()
             }
         )
@@ -268,6 +303,7 @@ mod tests {
             queue: None,
             worker: None,
             limit: None,
+            labels: Vec::new(),
         };
         assert_eq!(
             prepare_query_task(&gtm),
@@ -275,7 +311,11 @@ mod tests {
                 status: "%".to_string(),
                 queue: "%".to_string(),
                 worker: "%".to_string(),
-                limit: 100
+                limit: 100,
+                labels: LabelParams {
+                    equals: serde_json::json!({}),
+                    any_of: serde_json::json!([]),
+                }
             }
         );
         // Everything passed
@@ -284,6 +324,7 @@ mod tests {
             queue: Some("abc".to_string()),
             worker: Some("s".to_string()),
             limit: Some(20),
+            labels: vec![LabelSelector::Equals("region".to_string(), "eu".to_string())],
         };
         assert_eq!(
             prepare_query_task(&gtm),
@@ -291,7 +332,33 @@ mod tests {
                 status: "cancelled".to_string(),
                 queue: "abc".to_string(),
                 worker: "s".to_string(),
-                limit: 20
+                limit: 20,
+                labels: LabelParams {
+                    equals: serde_json::json!({"region": "eu"}),
+                    any_of: serde_json::json!([]),
+                }
+            }
+        );
+    }
+    #[test]
+    fn prepare_label_selectors_cases() {
+        assert_eq!(
+            prepare_label_selectors(&[]),
+            LabelParams {
+                equals: serde_json::json!({}),
+                any_of: serde_json::json!([]),
+            }
+        );
+        let label_selectors = vec![
+            LabelSelector::Equals("region".to_string(), "eu".to_string()),
+            LabelSelector::In("market".to_string(), vec!["uk".to_string(), "se".to_string()]),
+            LabelSelector::Equals("tier".to_string(), "gold".to_string()),
+        ];
+        assert_eq!(
+            prepare_label_selectors(&label_selectors),
+            LabelParams {
+                equals: serde_json::json!({"region": "eu", "tier": "gold"}),
+                any_of: serde_json::json!([{"key": "market", "values": ["uk", "se"]}]),
             }
         );
     }
@@ -0,0 +1,30 @@
+//! Drains a worker through the database, e.g. before an upgrade of its node: `drain_worker <worker_id> [--resume]`.
+//! The worker is leased no more tasks and its heartbeats request it to yield the tasks it holds, until it is resumed.
+use scylla_pg_core::config::PGConfig;
+use scylla_pg_lib::manager::PgManager;
+
+#[tokio::main]
+pub async fn main() {
+    dotenv::dotenv().ok();
+    env_logger::builder().format_timestamp_millis().init();
+    let args: Vec<String> = std::env::args().collect();
+    let Some(worker_id) = args.get(1).cloned() else {
+        eprintln!("Usage: drain_worker <worker_id> [--resume]");
+        std::process::exit(2);
+    };
+    let resume = args.get(2).is_some_and(|arg| arg == "--resume");
+    let pgm = PgManager::from_config(&PGConfig::from_env().unwrap()).expect("Error creating PgManager Instance");
+
+    let worker_result = if resume {
+        pgm.resume_worker(worker_id).await
+    } else {
+        pgm.drain_worker(worker_id).await
+    };
+    match worker_result {
+        Ok(worker) => println!("{}", serde_json::to_string(&worker).unwrap()),
+        Err(e) => {
+            log::error!("error occurred while draining worker {e}");
+            std::process::exit(1);
+        }
+    }
+}
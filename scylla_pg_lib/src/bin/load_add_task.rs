@@ -21,6 +21,7 @@ pub async fn main() {
                 spec: json!("{}"),
                 priority: 0,
                 queue: "load_test".to_string(),
+                ..AddTaskModel::default()
             };
             if let Err(e) = pgm_clone.read().await.insert_task(atm).await {
                 log::error!("error occurred while adding tasks {e}")
@@ -28,6 +28,7 @@ pub async fn start_worker(worker_id: String) {
                 queue: Some("load_test".to_string()),
                 status: Some(TaskStatus::Ready),
                 worker: None,
+                labels: Vec::new(),
             })
             .await
         {
@@ -39,11 +40,11 @@ pub async fn start_worker(worker_id: String) {
                             log::error!("error occurred while leasing task {e}");
                         }
                         Ok(t) => {
-                            // if let Err(e) = pgm.heartbeat_task(t.rn.clone(), t.owner.unwrap(), None, None).await {
+                            // if let Err(e) = pgm.heartbeat_task(t.rn.clone(), t.owner.unwrap(), t.lease_epoch, None, None).await {
                             //     log::error!("error occurred while heartbeat tasks {e}");
                             // }
                             // tokio::time::sleep(Duration::from_millis(1000)).await;
-                            if let Err(e) = pgm.complete_task(t.rn.clone(), None).await {
+                            if let Err(e) = pgm.complete_task(t.rn.clone(), t.owner.clone().unwrap(), t.lease_epoch, None, None).await {
                                 log::error!("error occurred while complete tasks {e}");
                             }
                         }
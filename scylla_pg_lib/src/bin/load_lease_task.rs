@@ -30,14 +30,14 @@ pub async fn start_worker(worker_id: String, tx: tokio::sync::mpsc::Sender<u64>)
     loop {
         let worker_clone = worker_id.clone();
         let instant = tokio::time::Instant::now();
-        match pgm.lease_n_tasks("load_test".to_string(), 1, worker_clone, Some(5)).await {
+        match pgm.lease_n_tasks("load_test".to_string(), 1, worker_clone, Some(5), Vec::new()).await {
             Err(e) => {
                 log::error!("error occurred while leasing tasks {e}");
             }
             Ok(tasks) => {
                 for _ in tasks {
                     let _ = tx.send(instant.elapsed().as_millis().try_into().unwrap()).await;
-                    // if let Err(e) = pgm.heartbeat_task(t.rn.clone(), t.owner.unwrap(), None, None).await {
+                    // if let Err(e) = pgm.heartbeat_task(t.rn.clone(), t.owner.unwrap(), t.lease_epoch, None, None).await {
                     //     log::error!("error occurred while heartbeat tasks {e}");
                     // }
                     // if let Err(e) = pgm.complete_task(t.rn.clone()).await {
@@ -12,6 +12,16 @@ pub enum PgAdapterError {
     DbError(tokio_postgres::Error),
     DuplicateTask(String),
     NoTaskFound(String),
+    NoQueueFound(String),
+    NoScheduleFound(String),
+    ConcurrentModification(String),
+    QueuePaused(String),
+    QueueDraining(String),
+    ConcurrencyLimitReached(String),
+    RateLimited(String),
+    NoRateLimitFound(String),
+    NoWorkerFound(String),
+    WorkerDraining(String),
 }
 
 impl From<ScyllaOperationsError> for PgAdapterError {
@@ -41,6 +51,16 @@ impl Display for PgAdapterError {
             PgAdapterError::DbError(pg) => write!(f, "{pg}"),
             PgAdapterError::DuplicateTask(rn) => write!(f, "Task already exist for {rn}"),
             PgAdapterError::NoTaskFound(rn) => write!(f, "No task found for {rn}"),
+            PgAdapterError::NoQueueFound(name) => write!(f, "No queue found for {name}"),
+            PgAdapterError::NoScheduleFound(name) => write!(f, "No schedule found for {name}"),
+            PgAdapterError::ConcurrentModification(rn) => write!(f, "Task {rn} was modified concurrently"),
+            PgAdapterError::QueuePaused(name) => write!(f, "Queue {name} is paused, its tasks cannot be leased"),
+            PgAdapterError::QueueDraining(name) => write!(f, "Queue {name} is draining, it does not accept new tasks"),
+            PgAdapterError::ConcurrencyLimitReached(name) => write!(f, "Queue {name} has reached its concurrency limit"),
+            PgAdapterError::RateLimited(name) => write!(f, "Queue {name} has reached its rate limit"),
+            PgAdapterError::NoRateLimitFound(name) => write!(f, "No rate limit found for {name}"),
+            PgAdapterError::NoWorkerFound(id) => write!(f, "No worker found for {id}"),
+            PgAdapterError::WorkerDraining(id) => write!(f, "Worker {id} is draining, it is not leased new tasks"),
             PgAdapterError::PoolCreationError(build_error) => write!(f, "{build_error}"),
             PgAdapterError::PoolError(pool_error) => write!(f, "{pool_error}"),
             PgAdapterError::ScyllaOpsError(sc_ops_error) => write!(f, "{sc_ops_error}"),
@@ -68,6 +88,46 @@ mod tests {
             PgAdapterError::NoTaskFound("sample".to_string()).to_string(),
             "No task found for sample".to_string()
         );
+        assert_eq!(
+            PgAdapterError::NoQueueFound("sample".to_string()).to_string(),
+            "No queue found for sample".to_string()
+        );
+        assert_eq!(
+            PgAdapterError::NoScheduleFound("sample".to_string()).to_string(),
+            "No schedule found for sample".to_string()
+        );
+        assert_eq!(
+            PgAdapterError::ConcurrentModification("sample".to_string()).to_string(),
+            "Task sample was modified concurrently".to_string()
+        );
+        assert_eq!(
+            PgAdapterError::QueuePaused("sample".to_string()).to_string(),
+            "Queue sample is paused, its tasks cannot be leased".to_string()
+        );
+        assert_eq!(
+            PgAdapterError::QueueDraining("sample".to_string()).to_string(),
+            "Queue sample is draining, it does not accept new tasks".to_string()
+        );
+        assert_eq!(
+            PgAdapterError::ConcurrencyLimitReached("sample".to_string()).to_string(),
+            "Queue sample has reached its concurrency limit".to_string()
+        );
+        assert_eq!(
+            PgAdapterError::RateLimited("sample".to_string()).to_string(),
+            "Queue sample has reached its rate limit".to_string()
+        );
+        assert_eq!(
+            PgAdapterError::NoRateLimitFound("sample".to_string()).to_string(),
+            "No rate limit found for sample".to_string()
+        );
+        assert_eq!(
+            PgAdapterError::NoWorkerFound("sample".to_string()).to_string(),
+            "No worker found for sample".to_string()
+        );
+        assert_eq!(
+            PgAdapterError::WorkerDraining("sample".to_string()).to_string(),
+            "Worker sample is draining, it is not leased new tasks".to_string()
+        );
         assert_eq!(
             format!("{:?}", PgAdapterError::DuplicateTask("sample".to_string())),
             "DuplicateTask(\"sample\")".to_string()
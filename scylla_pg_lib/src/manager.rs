@@ -1,14 +1,31 @@
 //! PG Manager used by external crates to deal with Database operations. `PGAdapter` is not accessible without `PGManager`
 use crate::adapter::PgAdapter;
 use crate::error::PgAdapterError;
+use chrono::Utc;
 use log::debug;
-use scylla_models::{AddTaskModel, GetTaskModel, Task, TaskBatch, TaskError, TaskStatus, UpdateOperation, UpdateTaskModel};
+use scylla_models::{
+    AddTaskModel, GetTaskModel, HeartbeatOutcome, LabelSelector, Queue, QueueMode, QueueState, RateLimitBudget, ResubmitOverrides, Schedule, Task, TaskBatch,
+    TaskError, TaskHeartbeat, TaskHeartbeatOutcome, TaskStatus, UpdateOperation, UpdateTaskModel, Worker, WorkerMetadata,
+};
+use scylla_operations::error::ScyllaOperationsError;
+use scylla_operations::queue::{lease_timeout_in_secs, validate_priority, validate_queue};
+use scylla_operations::schedule::{due_fire_time, schedule_task, validate_schedule};
 use scylla_operations::task::{Persistence, ScyllaOperations};
+use scylla_operations::worker::register_worker_operation;
 use scylla_pg_core::config::PGConfig;
 use scylla_pg_core::connection::get_pool;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Number of times an update is re-read and re-validated when the task is modified concurrently.
+const MAX_UPDATE_TRIES: u32 = 5;
 
 pub struct PgManager {
     pg_adapter: Box<dyn Persistence<PersistenceError = PgAdapterError> + Send + Sync>,
+    /// Tasks are accepted only for queues registered with `upsert_queue`
+    require_registered_queues: bool,
+    /// Tasks are leased only by workers registered with `register_worker`
+    require_registered_workers: bool,
 }
 
 impl PgManager {
@@ -20,24 +37,52 @@ impl PgManager {
         let pool = get_pool(config)?;
         Ok(Self {
             pg_adapter: Box::new(PgAdapter { pool }),
+            require_registered_queues: false,
+            require_registered_workers: false,
         })
     }
     // $coverage:ignore-end
+    /// Rejects tasks for queues which have not been registered with `upsert_queue` with `NoQueueFound`, when `required` is set.
+    #[must_use]
+    pub fn require_registered_queues(mut self, required: bool) -> Self {
+        self.require_registered_queues = required;
+        self
+    }
+    /// Rejects leases of workers which have not been registered with `register_worker` with `NoWorkerFound`, when `required` is set.
+    #[must_use]
+    pub fn require_registered_workers(mut self, required: bool) -> Self {
+        self.require_registered_workers = required;
+        self
+    }
     /// # Errors
     /// Returns `PgAdapterError`
     pub async fn fetch_task(&self, rn: String) -> Result<Task, PgAdapterError> {
         self.pg_adapter.query_by_rn(rn).await
     }
     /// # Errors
-    /// Returns `PgAdapterError`
+    /// Returns `PgAdapterError`, `QueueDraining` if the queue of the task is draining, `NoTaskFound` if a task it depends on
+    /// does not exist, `ScyllaOpsError` if it depends on itself
     pub async fn insert_task(&self, atm: AddTaskModel) -> Result<Task, PgAdapterError> {
-        let task = ScyllaOperations::add_task_operation(&atm);
+        let task = ScyllaOperations::add_task_operation(&atm)?;
+        self.validate_new_tasks(std::slice::from_ref(&task)).await?;
         self.pg_adapter.insert(task).await
     }
+    /// Replaces spec and priority of ready tasks with the same supersession key in the queue, or inserts the task if
+    /// there is none. Running tasks with the same supersession key are cancelled with a `superseded` error if `cancel_running` is set.
     /// # Errors
-    /// Returns `PgAdapterError`
+    /// Returns `PgAdapterError`, `QueueDraining` if there is no ready task to replace and the queue of the task is draining
+    pub async fn supersede_task(&self, atm: AddTaskModel, cancel_running: bool) -> Result<Task, PgAdapterError> {
+        let task = ScyllaOperations::supersede_task_operation(&atm)?;
+        self.validate_new_tasks(std::slice::from_ref(&task)).await?;
+        self.pg_adapter.supersede(task, cancel_running).await
+    }
+    /// # Errors
+    /// Returns `PgAdapterError`, `QueueDraining` if the queue of any task is draining, `NoTaskFound` if a task any of them
+    /// depends on neither exists nor is in the batch, `ScyllaOpsError` if any of them depends on itself
     pub async fn batch_insert_tasks(&self, atms: Vec<AddTaskModel>) -> Result<TaskBatch, PgAdapterError> {
-        self.pg_adapter.batch_insert(ScyllaOperations::add_task_operations(&atms)).await
+        let tasks = ScyllaOperations::add_task_operations(&atms)?;
+        self.validate_new_tasks(&tasks).await?;
+        self.pg_adapter.batch_insert(tasks).await
     }
     /// # Errors
     /// Returns `PgAdapterError`
@@ -46,8 +91,14 @@ impl PgManager {
         self.pg_adapter.query(&get_task_model).await
     }
     /// # Errors
-    /// Returns `PgAdapterError`
+    /// Returns `PgAdapterError`, `QueuePaused` if the queue of the task is paused,
+    /// `ConcurrencyLimitReached` or `RateLimited` if its queue cannot start another task,
+    /// `NoWorkerFound` if workers are required to be registered and the worker is not, `WorkerDraining` if the worker is
+    /// being drained
     pub async fn lease_task(&self, rn: String, worker: String, task_timeout_in_secs: Option<i64>) -> Result<Task, PgAdapterError> {
+        if self.validate_worker(&worker).await?.is_some_and(|w| w.draining) {
+            return Err(PgAdapterError::WorkerDraining(worker));
+        }
         let update_task_model = UpdateTaskModel {
             rn,
             worker: Some(worker),
@@ -57,27 +108,98 @@ impl PgManager {
             error: None,
             task_timeout_in_secs,
             metrics: None,
+            lease_epoch: None,
+            priority: None,
+            spec: None,
+            result: None,
+            actor: None,
+            reason: None,
         };
         self.update_task(&update_task_model).await
     }
+    /// Extends the lease of the worker, unless it has lost it or the task has been cancelled or completed meanwhile.
+    /// A worker being drained is requested to yield the task.
     /// # Errors
-    /// Returns `PgAdapterError`
-    pub async fn heartbeat_task(&self, rn: String, worker: String, progress: Option<f32>, task_timeout_in_secs: Option<i64>) -> Result<Task, PgAdapterError> {
+    /// Returns `PgAdapterError` in case the outcome of the heartbeat cannot be determined
+    pub async fn heartbeat_task(
+        &self,
+        rn: String,
+        worker: String,
+        lease_epoch: i64,
+        progress: Option<f32>,
+        task_timeout_in_secs: Option<i64>,
+    ) -> Result<HeartbeatOutcome, PgAdapterError> {
         let update_task_model = UpdateTaskModel {
-            rn,
-            worker: Some(worker),
+            rn: rn.clone(),
+            worker: Some(worker.clone()),
             status: None,
             progress,
             operation: UpdateOperation::HeartBeat,
             error: None,
             task_timeout_in_secs,
             metrics: None,
+            lease_epoch: Some(lease_epoch),
+            priority: None,
+            spec: None,
+            result: None,
+            actor: None,
+            reason: None,
         };
-        self.update_task(&update_task_model).await
+        match self.update_task(&update_task_model).await {
+            Ok(task) => {
+                let outcome = ScyllaOperations::heart_beat_outcome(task, &worker, lease_epoch);
+                Ok(self.request_yield_if_draining(&worker, vec![outcome]).await?.remove(0))
+            }
+            Err(PgAdapterError::NoTaskFound(_)) => Ok(HeartbeatOutcome::NotFound),
+            Err(PgAdapterError::ScyllaOpsError(e)) => match self.fetch_task(rn).await {
+                Ok(task) => match ScyllaOperations::heart_beat_outcome(task, &worker, lease_epoch) {
+                    // rejected for another reason than the state of the lease
+                    HeartbeatOutcome::Extended { .. } => Err(PgAdapterError::ScyllaOpsError(e)),
+                    outcome => Ok(outcome),
+                },
+                Err(PgAdapterError::NoTaskFound(_)) => Ok(HeartbeatOutcome::NotFound),
+                Err(fetch_error) => Err(fetch_error),
+            },
+            Err(e) => Err(e),
+        }
+    }
+    /// Heartbeats all the tasks held by `worker` at once, extending its leases in a single statement. Returns the outcome
+    /// of each heartbeat in the order of `heartbeats`, leases lost or cancelled meanwhile are reported rather than failing
+    /// the whole batch. A worker being drained is requested to yield its tasks.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn heartbeat_tasks(
+        &self,
+        worker: String,
+        heartbeats: Vec<TaskHeartbeat>,
+        task_timeout_in_secs: Option<i64>,
+    ) -> Result<Vec<TaskHeartbeatOutcome>, PgAdapterError> {
+        let by_rn: BTreeMap<String, Task> = self
+            .pg_adapter
+            .heartbeat_batch(worker.clone(), heartbeats.clone(), task_timeout_in_secs)
+            .await?
+            .into_iter()
+            .map(|task| (task.rn.clone(), task))
+            .collect();
+        let rns: Vec<String> = heartbeats.iter().map(|heartbeat| heartbeat.rn.clone()).collect();
+        let outcomes = heartbeats
+            .into_iter()
+            .map(|heartbeat| match by_rn.get(&heartbeat.rn).cloned() {
+                Some(task) => ScyllaOperations::heart_beat_outcome(task, &worker, heartbeat.lease_epoch),
+                None => HeartbeatOutcome::NotFound,
+            })
+            .collect();
+        Ok(rns
+            .into_iter()
+            .zip(self.request_yield_if_draining(&worker, outcomes).await?)
+            .map(|(rn, outcome)| TaskHeartbeatOutcome { rn, outcome })
+            .collect())
     }
+    /// A running task is moved to cancelling, until its worker acknowledges the cancellation with `acknowledge_cancellation`.
+    /// `actor` and optional `reason` are recorded in the history of the task.
     /// # Errors
     /// Returns `PgAdapterError`
-    pub async fn cancel_task(&self, rn: String) -> Result<Task, PgAdapterError> {
+    pub async fn cancel_task(&self, rn: String, actor: String, reason: Option<String>) -> Result<Task, PgAdapterError> {
         let update_task_model = UpdateTaskModel {
             rn,
             worker: None,
@@ -87,60 +209,121 @@ impl PgManager {
             error: None,
             task_timeout_in_secs: None,
             metrics: None,
+            lease_epoch: None,
+            priority: None,
+            spec: None,
+            result: None,
+            actor: Some(actor),
+            reason,
         };
         self.update_task(&update_task_model).await
     }
+    /// Acknowledges the requested cancellation of a cancelling task by its worker, which moves it to cancelled.
     /// # Errors
     /// Returns `PgAdapterError`
-    pub async fn complete_task(&self, rn: String, metrics: Option<String>) -> Result<Task, PgAdapterError> {
+    pub async fn acknowledge_cancellation(&self, rn: String, worker: String, lease_epoch: i64) -> Result<Task, PgAdapterError> {
         let update_task_model = UpdateTaskModel {
             rn,
-            worker: None,
+            worker: Some(worker.clone()),
+            status: Some(TaskStatus::Cancelled),
+            progress: None,
+            operation: UpdateOperation::Status,
+            error: None,
+            task_timeout_in_secs: None,
+            metrics: None,
+            lease_epoch: Some(lease_epoch),
+            priority: None,
+            spec: None,
+            result: None,
+            actor: Some(worker),
+            reason: None,
+        };
+        self.update_task(&update_task_model).await
+    }
+    /// `metrics` and `result` need to be valid JSON. `result` is limited to `MAX_RESULT_SIZE_IN_BYTES` of `scylla_operations::update_task`.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn complete_task(
+        &self,
+        rn: String,
+        worker: String,
+        lease_epoch: i64,
+        metrics: Option<String>,
+        result: Option<String>,
+    ) -> Result<Task, PgAdapterError> {
+        let update_task_model = UpdateTaskModel {
+            rn,
+            worker: Some(worker.clone()),
             status: Some(TaskStatus::Completed),
             progress: None,
             operation: UpdateOperation::Status,
             error: None,
             task_timeout_in_secs: None,
-            metrics: if let Some(metrics_str) = metrics {
-                serde_json::from_str(&metrics_str).ok() // set to None if deserialization fails
-            } else {
-                None
-            },
+            metrics: parse_json(metrics, "metrics")?,
+            lease_epoch: Some(lease_epoch),
+            priority: None,
+            spec: None,
+            result: parse_json(result, "result")?,
+            actor: Some(worker),
+            reason: None,
         };
         self.update_task(&update_task_model).await
     }
     /// # Errors
     /// Returns `PgAdapterError`
-    pub async fn abort_task(&self, rn: String, error: TaskError) -> Result<Task, PgAdapterError> {
+    pub async fn abort_task(&self, rn: String, worker: String, lease_epoch: i64, error: TaskError) -> Result<Task, PgAdapterError> {
         let update_task_model = UpdateTaskModel {
             rn,
-            worker: None,
+            worker: Some(worker.clone()),
             status: Some(TaskStatus::Aborted),
             progress: None,
             operation: UpdateOperation::Status,
             error: Some(error),
             task_timeout_in_secs: None,
             metrics: None,
+            lease_epoch: Some(lease_epoch),
+            priority: None,
+            spec: None,
+            result: None,
+            actor: Some(worker),
+            reason: None,
         };
         self.update_task(&update_task_model).await
     }
+    /// Tasks of paused queues are skipped. A worker being drained is leased no tasks.
     /// # Errors
-    /// Returns `PgAdapterError`
-    pub async fn lease_n_tasks(&self, queue: String, limit: i32, worker: String, task_timeout_in_secs: Option<i64>) -> Result<Vec<Task>, PgAdapterError> {
-        self.pg_adapter.lease_batch(queue, limit, worker, task_timeout_in_secs.unwrap_or(10)).await
+    /// Returns `PgAdapterError`, `NoWorkerFound` if workers are required to be registered and the worker is not
+    pub async fn lease_n_tasks(
+        &self,
+        queue: String,
+        limit: i32,
+        worker: String,
+        task_timeout_in_secs: Option<i64>,
+        labels: Vec<LabelSelector>,
+    ) -> Result<Vec<Task>, PgAdapterError> {
+        if self.validate_worker(&worker).await?.is_some_and(|w| w.draining) {
+            return Ok(Vec::new());
+        }
+        self.pg_adapter.lease_batch(queue, limit, worker, task_timeout_in_secs, labels).await
     }
     /// # Errors
     /// Returns `PgAdapterError`
-    pub async fn yield_task(&self, rn: String) -> Result<Task, PgAdapterError> {
+    pub async fn yield_task(&self, rn: String, worker: String, lease_epoch: i64) -> Result<Task, PgAdapterError> {
         let update_task_model = UpdateTaskModel {
             rn,
-            worker: None,
+            worker: Some(worker),
             status: None,
             progress: None,
             operation: UpdateOperation::Yield,
             error: None,
             task_timeout_in_secs: None,
             metrics: None,
+            lease_epoch: Some(lease_epoch),
+            priority: None,
+            spec: None,
+            result: None,
+            actor: None,
+            reason: None,
         };
         self.update_task(&update_task_model).await
     }
@@ -156,17 +339,208 @@ impl PgManager {
             error: None,
             task_timeout_in_secs: None,
             metrics: None,
+            lease_epoch: None,
+            priority: None,
+            spec: None,
+            result: None,
+            actor: None,
+            reason: None,
         };
         self.update_task(&update_task_model).await
     }
+    /// Yields all the running tasks owned by `worker` at once, e.g. from a shutdown hook, so that they are reset on the
+    /// next poll of the monitor rather than once their leases time out. A `TaskYield` entry is appended to their history.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn yield_worker_tasks(&self, worker: String) -> Result<Vec<Task>, PgAdapterError> {
+        self.pg_adapter.yield_worker_batch(worker).await
+    }
+    /// Resets all the running tasks owned by `worker` right away, without waiting for their deadline. Meant for operators
+    /// once the worker is known to be dead, as a worker still alive would carry on with tasks leased again meanwhile.
+    /// Tasks are reset as if they had timed out, with `actor` and the `worker_reset` reason recorded in their history.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn reset_worker_tasks(&self, worker: String, actor: String) -> Result<Vec<Task>, PgAdapterError> {
+        self.pg_adapter.reset_worker_batch(worker, actor).await
+    }
+    /// Changes priority of a ready task. `actor` is recorded in the history of the task.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn reprioritise_task(&self, rn: String, priority: i8, actor: String) -> Result<Task, PgAdapterError> {
+        let update_task_model = UpdateTaskModel {
+            rn,
+            worker: None,
+            status: None,
+            progress: None,
+            operation: UpdateOperation::Reprioritise,
+            error: None,
+            task_timeout_in_secs: None,
+            metrics: None,
+            lease_epoch: None,
+            priority: Some(priority),
+            spec: None,
+            result: None,
+            actor: Some(actor),
+            reason: None,
+        };
+        self.update_task(&update_task_model).await
+    }
+    /// Amends spec of a ready task. Fields of an object `spec` are merged into the current spec, any other value replaces it.
+    /// `actor` is recorded in the history of the task.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn amend_task_spec(&self, rn: String, spec: Value, actor: String) -> Result<Task, PgAdapterError> {
+        let update_task_model = UpdateTaskModel {
+            rn,
+            worker: None,
+            status: None,
+            progress: None,
+            operation: UpdateOperation::AmendSpec,
+            error: None,
+            task_timeout_in_secs: None,
+            metrics: None,
+            lease_epoch: None,
+            priority: None,
+            spec: Some(spec),
+            result: None,
+            actor: Some(actor),
+            reason: None,
+        };
+        self.update_task(&update_task_model).await
+    }
+    /// Adds a ready clone of an aborted, cancelled or expired task as `new_rn`. The clone refers to the task by `previous_rn`
+    /// and the task to the clone by `resubmitted_as`.
+    /// # Errors
+    /// Returns `PgAdapterError`, `QueueDraining` if the queue of the clone is draining
+    pub async fn resubmit_task(&self, rn: String, new_rn: String, overrides: ResubmitOverrides) -> Result<Task, PgAdapterError> {
+        let mut try_count = 1;
+        loop {
+            let previous = self.fetch_task(rn.clone()).await?;
+            let task = ScyllaOperations::resubmit_task_operation(&previous, new_rn.clone(), &overrides)?;
+            self.validate_new_tasks(std::slice::from_ref(&task)).await?;
+            match self.pg_adapter.resubmit(task, previous.version).await {
+                Err(PgAdapterError::ConcurrentModification(rn)) if try_count < MAX_UPDATE_TRIES => {
+                    debug!("resubmit_task: {rn} modified concurrently, retrying");
+                    try_count += 1;
+                }
+                result => return result,
+            }
+        }
+    }
     /// # Errors
     /// Returns `PgAdapterError`
     async fn update_task(&self, utm: &UpdateTaskModel) -> Result<Task, PgAdapterError> {
-        let task_to_update = self.fetch_task(utm.rn.clone()).await?;
-        let task = ScyllaOperations::update_task_operation(utm, task_to_update)?;
-        self.pg_adapter.update(task).await
+        let mut try_count = 1;
+        loop {
+            let task_to_update = self.fetch_task(utm.rn.clone()).await?;
+            let utm = &self.apply_queue_defaults(utm, &task_to_update).await?;
+            let task = ScyllaOperations::update_task_operation(utm, task_to_update)?;
+            let result = if utm.operation == UpdateOperation::Lease {
+                self.pg_adapter.lease(task).await
+            } else {
+                self.pg_adapter.update(task).await
+            };
+            match result {
+                Err(PgAdapterError::ConcurrentModification(rn)) if try_count < MAX_UPDATE_TRIES => {
+                    debug!("update_task: {rn} modified concurrently, retrying {:?}", utm.operation);
+                    try_count += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Lease timeout of the queue of the task applies to leases and heartbeats which pass none.
+    /// Priority a task is reprioritised to is limited by the max priority of its queue.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    async fn apply_queue_defaults(&self, utm: &UpdateTaskModel, task: &Task) -> Result<UpdateTaskModel, PgAdapterError> {
+        match utm.operation {
+            UpdateOperation::Lease | UpdateOperation::HeartBeat if utm.task_timeout_in_secs.is_none() => {
+                let queue = self.registered_queue(task.queue.clone()).await?;
+                Ok(UpdateTaskModel {
+                    task_timeout_in_secs: Some(lease_timeout_in_secs(None, queue.as_ref())),
+                    ..utm.clone()
+                })
+            }
+            UpdateOperation::Reprioritise => {
+                if let Some(priority) = utm.priority {
+                    validate_priority(priority, self.registered_queue(task.queue.clone()).await?.as_ref())?;
+                }
+                Ok(utm.clone())
+            }
+            _ => Ok(utm.clone()),
+        }
+    }
+
+    /// # Errors
+    /// Returns `NoQueueFound` if queues are required to be registered and the queue of any task is not,
+    /// `ScyllaOpsError` if the priority of any task exceeds the max priority of its queue
+    async fn validate_new_tasks(&self, tasks: &[Task]) -> Result<(), PgAdapterError> {
+        let mut queues = BTreeMap::new();
+        for task in tasks {
+            if !queues.contains_key(&task.queue) {
+                let queue = self.registered_queue(task.queue.clone()).await?;
+                if queue.is_none() && self.require_registered_queues {
+                    return Err(PgAdapterError::NoQueueFound(task.queue.clone()));
+                }
+                queues.insert(task.queue.clone(), queue);
+            }
+            validate_priority(task.priority, queues[&task.queue].as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Returns the worker if it is registered.
+    /// # Errors
+    /// Returns `NoWorkerFound` if workers are required to be registered and the worker is not
+    async fn validate_worker(&self, worker: &str) -> Result<Option<Worker>, PgAdapterError> {
+        match self.registered_worker(worker).await? {
+            None if self.require_registered_workers => Err(PgAdapterError::NoWorkerFound(worker.to_string())),
+            registered => Ok(registered),
+        }
+    }
+
+    /// # Errors
+    /// Returns `PgAdapterError`
+    async fn registered_worker(&self, worker: &str) -> Result<Option<Worker>, PgAdapterError> {
+        match self.pg_adapter.query_worker_by_id(worker.to_string()).await {
+            Ok(registered) => Ok(Some(registered)),
+            Err(PgAdapterError::NoWorkerFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Turns extended leases into requests to yield when the worker is being drained.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    async fn request_yield_if_draining(&self, worker: &str, outcomes: Vec<HeartbeatOutcome>) -> Result<Vec<HeartbeatOutcome>, PgAdapterError> {
+        if !outcomes.iter().any(|outcome| matches!(outcome, HeartbeatOutcome::Extended { .. })) {
+            return Ok(outcomes);
+        }
+        if !self.registered_worker(worker).await?.is_some_and(|w| w.draining) {
+            return Ok(outcomes);
+        }
+        Ok(outcomes
+            .into_iter()
+            .map(|outcome| match outcome {
+                HeartbeatOutcome::Extended { task } => HeartbeatOutcome::YieldRequested { task },
+                outcome => outcome,
+            })
+            .collect())
     }
 
+    /// # Errors
+    /// Returns `PgAdapterError`
+    async fn registered_queue(&self, name: String) -> Result<Option<Queue>, PgAdapterError> {
+        match self.pg_adapter.query_queue_by_name(name).await {
+            Ok(queue) => Ok(Some(queue)),
+            Err(PgAdapterError::NoQueueFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Terminated tasks of queues without a retention period of their own are deleted after `retention_time_in_secs`.
     /// # Errors
     /// Returns `PgAdapterError`
     pub async fn delete_terminated_tasks(&self, retention_time_in_secs: i64) -> Result<u64, PgAdapterError> {
@@ -178,6 +552,210 @@ impl PgManager {
     pub async fn reset_batch(&self) -> Result<Vec<Task>, PgAdapterError> {
         self.pg_adapter.reset_batch().await
     }
+
+    /// Moves ready and blocked tasks past their expiry to expired.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn expire_batch(&self) -> Result<Vec<Task>, PgAdapterError> {
+        self.pg_adapter.expire_batch().await
+    }
+
+    /// Moves cancelling tasks to cancelled, which have not been acknowledged by their worker within `grace_period_in_secs`.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn cancel_batch(&self, grace_period_in_secs: i64) -> Result<Vec<Task>, PgAdapterError> {
+        self.pg_adapter.cancel_batch(grace_period_in_secs).await
+    }
+
+    /// Moves blocked tasks to ready once all their dependencies are completed. Aborts or cancels them if any dependency failed.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn resolve_blocked_batch(&self) -> Result<Vec<Task>, PgAdapterError> {
+        self.pg_adapter.resolve_blocked_batch().await
+    }
+
+    /// Registers the queue along with its defaults, or replaces them if it is registered already.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn upsert_queue(&self, queue: Queue) -> Result<Queue, PgAdapterError> {
+        validate_queue(&queue)?;
+        self.pg_adapter.upsert_queue(queue).await
+    }
+
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn fetch_queue(&self, name: String) -> Result<Queue, PgAdapterError> {
+        self.pg_adapter.query_queue_by_name(name).await
+    }
+
+    /// Tasks of a paused queue are not leased until it is resumed. New tasks are still accepted.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn pause_queue(&self, name: String) -> Result<QueueState, PgAdapterError> {
+        self.set_queue_mode(name, QueueMode::Paused).await
+    }
+
+    /// A draining queue does not accept new tasks. Tasks already added are still leased.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn drain_queue(&self, name: String) -> Result<QueueState, PgAdapterError> {
+        self.set_queue_mode(name, QueueMode::Draining).await
+    }
+
+    /// Moves a paused or draining queue back to active.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn resume_queue(&self, name: String) -> Result<QueueState, PgAdapterError> {
+        self.set_queue_mode(name, QueueMode::Active).await
+    }
+
+    async fn set_queue_mode(&self, name: String, mode: QueueMode) -> Result<QueueState, PgAdapterError> {
+        self.pg_adapter.upsert_queue_state(QueueState { name, mode }).await
+    }
+
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn fetch_queue_state(&self, name: String) -> Result<QueueState, PgAdapterError> {
+        self.pg_adapter.query_queue_state(name).await
+    }
+
+    /// Remaining budget of a rate limited queue, for workers to back off until a token is available.
+    /// # Errors
+    /// Returns `PgAdapterError`, `NoRateLimitFound` if the queue is not rate limited
+    pub async fn fetch_rate_limit_budget(&self, name: String) -> Result<RateLimitBudget, PgAdapterError> {
+        self.pg_adapter.query_rate_limit_budget(name).await
+    }
+
+    /// # Errors
+    /// Returns `PgAdapterError`, `NoQueueFound` if queues are required to be registered and the queue of the schedule is not,
+    /// `ScyllaOpsError` if the schedule is invalid or its priority exceeds the max priority of its queue
+    pub async fn upsert_schedule(&self, schedule: Schedule) -> Result<Schedule, PgAdapterError> {
+        validate_schedule(&schedule)?;
+        let task = schedule_task(&schedule, Utc::now())?;
+        self.validate_new_tasks(std::slice::from_ref(&task)).await?;
+        self.pg_adapter.upsert_schedule(schedule).await
+    }
+
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn fetch_schedule(&self, name: String) -> Result<Schedule, PgAdapterError> {
+        self.pg_adapter.query_schedule_by_name(name).await
+    }
+
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn fetch_schedules(&self) -> Result<Vec<Schedule>, PgAdapterError> {
+        self.pg_adapter.query_schedules().await
+    }
+
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn delete_schedule(&self, name: String) -> Result<u64, PgAdapterError> {
+        self.pg_adapter.delete_schedule(name).await
+    }
+
+    /// Adds a task for every schedule which is due and returns the added tasks.
+    /// Task already added by another monitor for the same occurrence is skipped, so is the occurrence if the queue is draining.
+    /// Schedules whose task is not accepted by its queue, e.g. because the queue has been unregistered since, are skipped.
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn fire_due_schedules(&self) -> Result<Vec<Task>, PgAdapterError> {
+        let now = Utc::now();
+        let mut tasks = Vec::new();
+        for schedule in self.pg_adapter.query_schedules().await? {
+            let fire_time = match due_fire_time(&schedule, now) {
+                Ok(Some(fire_time)) => fire_time,
+                Ok(None) => continue,
+                Err(e) => {
+                    log::error!("skipping schedule {}: {e}", schedule.name);
+                    continue;
+                }
+            };
+            let task = match schedule_task(&schedule, fire_time) {
+                Ok(task) => task,
+                Err(e) => {
+                    log::error!("skipping schedule {}: {e}", schedule.name);
+                    continue;
+                }
+            };
+            match self.validate_new_tasks(std::slice::from_ref(&task)).await {
+                Ok(()) => {}
+                Err(e @ (PgAdapterError::NoQueueFound(_) | PgAdapterError::ScyllaOpsError(_))) => {
+                    log::error!("skipping schedule {}: {e}", schedule.name);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            }
+            match self.pg_adapter.insert(task).await {
+                Ok(task) => tasks.push(task),
+                Err(PgAdapterError::DuplicateTask(rn)) => debug!("fire_due_schedules: task {rn} already added"),
+                Err(PgAdapterError::QueueDraining(queue)) => debug!("fire_due_schedules: skipping schedule {}, queue {queue} is draining", schedule.name),
+                Err(e) => return Err(e),
+            }
+            self.pg_adapter.mark_schedule_fired(schedule.name, fire_time).await?;
+        }
+        Ok(tasks)
+    }
+
+    /// Registers a worker process and returns it along with the id issued to it, which the worker uses to lease tasks.
+    /// # Errors
+    /// Returns `PgAdapterError`, `ScyllaOpsError` if hostname or version is empty
+    pub async fn register_worker(&self, metadata: WorkerMetadata) -> Result<Worker, PgAdapterError> {
+        let worker = register_worker_operation(&metadata)?;
+        self.pg_adapter.register_worker(worker).await
+    }
+
+    /// Signals that the worker is alive.
+    /// # Errors
+    /// Returns `PgAdapterError`, `NoWorkerFound` if the worker is not registered
+    pub async fn heartbeat_worker(&self, id: String) -> Result<Worker, PgAdapterError> {
+        self.pg_adapter.heartbeat_worker(id).await
+    }
+
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn fetch_worker(&self, id: String) -> Result<Worker, PgAdapterError> {
+        self.pg_adapter.query_worker_by_id(id).await
+    }
+
+    /// # Errors
+    /// Returns `PgAdapterError`
+    pub async fn fetch_workers(&self) -> Result<Vec<Worker>, PgAdapterError> {
+        self.pg_adapter.query_workers().await
+    }
+
+    /// Drains the worker, e.g. before an upgrade of its node. It is leased no more tasks and its heartbeats request it to
+    /// yield the tasks it holds, until it is resumed.
+    /// # Errors
+    /// Returns `PgAdapterError`, `NoWorkerFound` if the worker is not registered
+    pub async fn drain_worker(&self, id: String) -> Result<Worker, PgAdapterError> {
+        self.pg_adapter.set_worker_draining(id, true).await
+    }
+
+    /// Leases tasks to a drained worker again.
+    /// # Errors
+    /// Returns `PgAdapterError`, `NoWorkerFound` if the worker is not registered
+    pub async fn resume_worker(&self, id: String) -> Result<Worker, PgAdapterError> {
+        self.pg_adapter.set_worker_draining(id, false).await
+    }
+
+    /// Removes the worker from the registry, e.g. on shutdown. Its id is not issued again.
+    /// # Errors
+    /// Returns `PgAdapterError`, `NoWorkerFound` if the worker is not registered
+    pub async fn deregister_worker(&self, id: String) -> Result<(), PgAdapterError> {
+        match self.pg_adapter.delete_worker(id.clone()).await? {
+            0 => Err(PgAdapterError::NoWorkerFound(id)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// # Errors
+/// Returns `ScyllaOperationsError::ValidationFailed` if the value is not valid JSON
+fn parse_json(value: Option<String>, field: &str) -> Result<Option<Value>, PgAdapterError> {
+    value
+        .map(|v| serde_json::from_str(&v).map_err(|e| ScyllaOperationsError::ValidationFailed(format!("Invalid JSON for {field}: {e}")).into()))
+        .transpose()
 }
 
 #[cfg(test)]
@@ -1,5 +1,6 @@
 // $coverage:ignore-start
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
+use scylla_operations::error::ScyllaOperationsError;
 use scylla_operations::task::Persistence;
 
 use crate::error::PgAdapterError;
@@ -7,16 +8,46 @@ use crate::error::PgAdapterError;
 use super::PgManager;
 use async_trait::async_trait;
 use scylla_models::*;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+type LeaseBatchFn =
+    fn(queue: String, limit: i32, worker: String, task_timeout_in_secs: Option<i64>, labels: Vec<LabelSelector>) -> Result<Vec<Task>, PgAdapterError>;
+type HeartbeatBatchFn = fn(worker: String, heartbeats: Vec<TaskHeartbeat>, task_timeout_in_secs: Option<i64>) -> Result<Vec<Task>, PgAdapterError>;
 
 struct MockPgAdapter {
     insert: fn(Task) -> Result<Task, PgAdapterError>,
     batch_insert: fn(Vec<Task>) -> Result<TaskBatch, PgAdapterError>,
+    supersede: fn(Task, bool) -> Result<Task, PgAdapterError>,
+    resubmit: fn(Task, i64) -> Result<Task, PgAdapterError>,
     update: fn(Task) -> Result<Task, PgAdapterError>,
+    lease: Option<fn(Task) -> Result<Task, PgAdapterError>>,
     query: fn(&GetTaskModel) -> Result<Vec<Task>, PgAdapterError>,
     query_by_rn: fn(String) -> Result<Task, PgAdapterError>,
     reset_batch: fn() -> Result<Vec<Task>, PgAdapterError>,
-    lease_batch: fn(queue: String, limit: i32, worker: String, task_timeout_in_secs: i64) -> Result<Vec<Task>, PgAdapterError>,
+    reset_worker_batch: fn(worker: String, actor: String) -> Result<Vec<Task>, PgAdapterError>,
+    yield_worker_batch: fn(worker: String) -> Result<Vec<Task>, PgAdapterError>,
+    resolve_blocked_batch: fn() -> Result<Vec<Task>, PgAdapterError>,
+    expire_batch: fn() -> Result<Vec<Task>, PgAdapterError>,
+    cancel_batch: fn(grace_period_in_secs: i64) -> Result<Vec<Task>, PgAdapterError>,
+    lease_batch: LeaseBatchFn,
+    heartbeat_batch: HeartbeatBatchFn,
     delete_batch: fn(retention_time_in_secs: i64) -> Result<u64, PgAdapterError>,
+    upsert_queue: fn(Queue) -> Result<Queue, PgAdapterError>,
+    query_queue_by_name: fn(String) -> Result<Queue, PgAdapterError>,
+    upsert_queue_state: fn(QueueState) -> Result<QueueState, PgAdapterError>,
+    query_queue_state: fn(String) -> Result<QueueState, PgAdapterError>,
+    query_rate_limit_budget: fn(String) -> Result<RateLimitBudget, PgAdapterError>,
+    upsert_schedule: fn(Schedule) -> Result<Schedule, PgAdapterError>,
+    query_schedules: fn() -> Result<Vec<Schedule>, PgAdapterError>,
+    query_schedule_by_name: fn(String) -> Result<Schedule, PgAdapterError>,
+    delete_schedule: fn(String) -> Result<u64, PgAdapterError>,
+    mark_schedule_fired: fn(String, DateTime<Utc>) -> Result<u64, PgAdapterError>,
+    register_worker: fn(Worker) -> Result<Worker, PgAdapterError>,
+    heartbeat_worker: fn(String) -> Result<Worker, PgAdapterError>,
+    query_worker_by_id: fn(String) -> Result<Worker, PgAdapterError>,
+    query_workers: fn() -> Result<Vec<Worker>, PgAdapterError>,
+    set_worker_draining: fn(String, bool) -> Result<Worker, PgAdapterError>,
+    delete_worker: fn(String) -> Result<u64, PgAdapterError>,
 }
 
 impl MockPgAdapter {
@@ -30,11 +61,46 @@ impl MockPgAdapter {
         self
     }
 
+    fn on_supersede(mut self, f: fn(Task, bool) -> Result<Task, PgAdapterError>) -> Self {
+        self.supersede = f;
+        self
+    }
+
+    fn on_resubmit(mut self, f: fn(Task, i64) -> Result<Task, PgAdapterError>) -> Self {
+        self.resubmit = f;
+        self
+    }
+
     fn on_update(mut self, f: fn(Task) -> Result<Task, PgAdapterError>) -> Self {
         self.update = f;
         self
     }
 
+    fn on_lease(mut self, f: fn(Task) -> Result<Task, PgAdapterError>) -> Self {
+        self.lease = Some(f);
+        self
+    }
+
+    fn on_lease_batch(mut self, f: LeaseBatchFn) -> Self {
+        self.lease_batch = f;
+        self
+    }
+
+    fn on_reset_worker_batch(mut self, f: fn(String, String) -> Result<Vec<Task>, PgAdapterError>) -> Self {
+        self.reset_worker_batch = f;
+        self
+    }
+
+    fn on_yield_worker_batch(mut self, f: fn(String) -> Result<Vec<Task>, PgAdapterError>) -> Self {
+        self.yield_worker_batch = f;
+        self
+    }
+
+    fn on_heartbeat_batch(mut self, f: HeartbeatBatchFn) -> Self {
+        self.heartbeat_batch = f;
+        self
+    }
+
     fn on_query(mut self, f: fn(&GetTaskModel) -> Result<Vec<Task>, PgAdapterError>) -> Self {
         self.query = f;
         self
@@ -49,6 +115,101 @@ impl MockPgAdapter {
         self.reset_batch = f;
         self
     }
+
+    fn on_resolve_blocked_batch(mut self, f: fn() -> Result<Vec<Task>, PgAdapterError>) -> Self {
+        self.resolve_blocked_batch = f;
+        self
+    }
+
+    fn on_expire_batch(mut self, f: fn() -> Result<Vec<Task>, PgAdapterError>) -> Self {
+        self.expire_batch = f;
+        self
+    }
+
+    fn on_cancel_batch(mut self, f: fn(i64) -> Result<Vec<Task>, PgAdapterError>) -> Self {
+        self.cancel_batch = f;
+        self
+    }
+
+    fn on_upsert_queue(mut self, f: fn(Queue) -> Result<Queue, PgAdapterError>) -> Self {
+        self.upsert_queue = f;
+        self
+    }
+
+    fn on_query_queue_by_name(mut self, f: fn(String) -> Result<Queue, PgAdapterError>) -> Self {
+        self.query_queue_by_name = f;
+        self
+    }
+
+    fn on_upsert_queue_state(mut self, f: fn(QueueState) -> Result<QueueState, PgAdapterError>) -> Self {
+        self.upsert_queue_state = f;
+        self
+    }
+
+    fn on_query_queue_state(mut self, f: fn(String) -> Result<QueueState, PgAdapterError>) -> Self {
+        self.query_queue_state = f;
+        self
+    }
+
+    fn on_query_rate_limit_budget(mut self, f: fn(String) -> Result<RateLimitBudget, PgAdapterError>) -> Self {
+        self.query_rate_limit_budget = f;
+        self
+    }
+
+    fn on_upsert_schedule(mut self, f: fn(Schedule) -> Result<Schedule, PgAdapterError>) -> Self {
+        self.upsert_schedule = f;
+        self
+    }
+
+    fn on_query_schedules(mut self, f: fn() -> Result<Vec<Schedule>, PgAdapterError>) -> Self {
+        self.query_schedules = f;
+        self
+    }
+
+    fn on_query_schedule_by_name(mut self, f: fn(String) -> Result<Schedule, PgAdapterError>) -> Self {
+        self.query_schedule_by_name = f;
+        self
+    }
+
+    fn on_delete_schedule(mut self, f: fn(String) -> Result<u64, PgAdapterError>) -> Self {
+        self.delete_schedule = f;
+        self
+    }
+
+    fn on_mark_schedule_fired(mut self, f: fn(String, DateTime<Utc>) -> Result<u64, PgAdapterError>) -> Self {
+        self.mark_schedule_fired = f;
+        self
+    }
+
+    fn on_register_worker(mut self, f: fn(Worker) -> Result<Worker, PgAdapterError>) -> Self {
+        self.register_worker = f;
+        self
+    }
+
+    fn on_heartbeat_worker(mut self, f: fn(String) -> Result<Worker, PgAdapterError>) -> Self {
+        self.heartbeat_worker = f;
+        self
+    }
+
+    fn on_query_worker_by_id(mut self, f: fn(String) -> Result<Worker, PgAdapterError>) -> Self {
+        self.query_worker_by_id = f;
+        self
+    }
+
+    fn on_query_workers(mut self, f: fn() -> Result<Vec<Worker>, PgAdapterError>) -> Self {
+        self.query_workers = f;
+        self
+    }
+
+    fn on_set_worker_draining(mut self, f: fn(String, bool) -> Result<Worker, PgAdapterError>) -> Self {
+        self.set_worker_draining = f;
+        self
+    }
+
+    fn on_delete_worker(mut self, f: fn(String) -> Result<u64, PgAdapterError>) -> Self {
+        self.delete_worker = f;
+        self
+    }
 }
 
 impl Default for MockPgAdapter {
@@ -56,12 +217,41 @@ impl Default for MockPgAdapter {
         Self {
             insert: |_| unimplemented!(),
             batch_insert: |_| unimplemented!(),
+            supersede: |_, _| unimplemented!(),
+            resubmit: |_, _| unimplemented!(),
             update: |_| unimplemented!(),
+            // leases are plain updates unless a test says otherwise
+            lease: None,
             query: |_| unimplemented!(),
             query_by_rn: |_| unimplemented!(),
-            lease_batch: |_, _, _, _| unimplemented!(),
+            lease_batch: |_, _, _, _, _| unimplemented!(),
+            heartbeat_batch: |_, _, _| unimplemented!(),
             delete_batch: |_| unimplemented!(),
             reset_batch: || unimplemented!(),
+            reset_worker_batch: |_, _| unimplemented!(),
+            yield_worker_batch: |_| unimplemented!(),
+            resolve_blocked_batch: || unimplemented!(),
+            expire_batch: || unimplemented!(),
+            cancel_batch: |_| unimplemented!(),
+            upsert_queue: |_| unimplemented!(),
+            // queues are not registered unless a test says otherwise
+            query_queue_by_name: |name| Err(PgAdapterError::NoQueueFound(name)),
+            // queues are active unless a test says otherwise
+            query_queue_state: |name| Ok(QueueState { name, mode: QueueMode::Active }),
+            upsert_queue_state: |_| unimplemented!(),
+            query_rate_limit_budget: |_| unimplemented!(),
+            upsert_schedule: |_| unimplemented!(),
+            query_schedules: || unimplemented!(),
+            query_schedule_by_name: |_| unimplemented!(),
+            delete_schedule: |_| unimplemented!(),
+            mark_schedule_fired: |_, _| unimplemented!(),
+            register_worker: |_| unimplemented!(),
+            heartbeat_worker: |_| unimplemented!(),
+            // workers are not registered unless a test says otherwise
+            query_worker_by_id: |id| Err(PgAdapterError::NoWorkerFound(id)),
+            query_workers: || unimplemented!(),
+            set_worker_draining: |_, _| unimplemented!(),
+            delete_worker: |_| unimplemented!(),
         }
     }
 }
@@ -77,10 +267,22 @@ impl Persistence for MockPgAdapter {
         (self.batch_insert)(tasks)
     }
 
+    async fn supersede(&self, task: Task, cancel_running: bool) -> Result<Task, Self::PersistenceError> {
+        (self.supersede)(task, cancel_running)
+    }
+
+    async fn resubmit(&self, task: Task, previous_version: i64) -> Result<Task, Self::PersistenceError> {
+        (self.resubmit)(task, previous_version)
+    }
+
     async fn update(&self, task: Task) -> Result<Task, Self::PersistenceError> {
         (self.update)(task)
     }
 
+    async fn lease(&self, task: Task) -> Result<Task, Self::PersistenceError> {
+        (self.lease.unwrap_or(self.update))(task)
+    }
+
     async fn query(&self, get_task_model: &GetTaskModel) -> Result<Vec<Task>, Self::PersistenceError> {
         (self.query)(get_task_model)
     }
@@ -89,8 +291,23 @@ impl Persistence for MockPgAdapter {
         (self.query_by_rn)(rn)
     }
 
-    async fn lease_batch(&self, queue: String, limit: i32, worker: String, task_timeout_in_secs: i64) -> Result<Vec<Task>, Self::PersistenceError> {
-        (self.lease_batch)(queue, limit, worker, task_timeout_in_secs)
+    async fn lease_batch(
+        &self,
+        queue: String,
+        limit: i32,
+        worker: String,
+        task_timeout_in_secs: Option<i64>,
+        labels: Vec<LabelSelector>,
+    ) -> Result<Vec<Task>, Self::PersistenceError> {
+        (self.lease_batch)(queue, limit, worker, task_timeout_in_secs, labels)
+    }
+    async fn heartbeat_batch(
+        &self,
+        worker: String,
+        heartbeats: Vec<TaskHeartbeat>,
+        task_timeout_in_secs: Option<i64>,
+    ) -> Result<Vec<Task>, Self::PersistenceError> {
+        (self.heartbeat_batch)(worker, heartbeats, task_timeout_in_secs)
     }
     async fn delete_batch(&self, retention_time_in_secs: i64) -> Result<u64, Self::PersistenceError> {
         (self.delete_batch)(retention_time_in_secs)
@@ -98,6 +315,69 @@ impl Persistence for MockPgAdapter {
     async fn reset_batch(&self) -> Result<Vec<Task>, PgAdapterError> {
         (self.reset_batch)()
     }
+    async fn reset_worker_batch(&self, worker: String, actor: String) -> Result<Vec<Task>, PgAdapterError> {
+        (self.reset_worker_batch)(worker, actor)
+    }
+    async fn yield_worker_batch(&self, worker: String) -> Result<Vec<Task>, PgAdapterError> {
+        (self.yield_worker_batch)(worker)
+    }
+    async fn resolve_blocked_batch(&self) -> Result<Vec<Task>, PgAdapterError> {
+        (self.resolve_blocked_batch)()
+    }
+    async fn expire_batch(&self) -> Result<Vec<Task>, PgAdapterError> {
+        (self.expire_batch)()
+    }
+    async fn cancel_batch(&self, grace_period_in_secs: i64) -> Result<Vec<Task>, PgAdapterError> {
+        (self.cancel_batch)(grace_period_in_secs)
+    }
+    async fn upsert_queue(&self, queue: Queue) -> Result<Queue, PgAdapterError> {
+        (self.upsert_queue)(queue)
+    }
+    async fn query_queue_by_name(&self, name: String) -> Result<Queue, PgAdapterError> {
+        (self.query_queue_by_name)(name)
+    }
+    async fn upsert_queue_state(&self, queue_state: QueueState) -> Result<QueueState, PgAdapterError> {
+        (self.upsert_queue_state)(queue_state)
+    }
+    async fn query_queue_state(&self, name: String) -> Result<QueueState, PgAdapterError> {
+        (self.query_queue_state)(name)
+    }
+    async fn query_rate_limit_budget(&self, name: String) -> Result<RateLimitBudget, PgAdapterError> {
+        (self.query_rate_limit_budget)(name)
+    }
+    async fn upsert_schedule(&self, schedule: Schedule) -> Result<Schedule, PgAdapterError> {
+        (self.upsert_schedule)(schedule)
+    }
+    async fn query_schedules(&self) -> Result<Vec<Schedule>, PgAdapterError> {
+        (self.query_schedules)()
+    }
+    async fn query_schedule_by_name(&self, name: String) -> Result<Schedule, PgAdapterError> {
+        (self.query_schedule_by_name)(name)
+    }
+    async fn delete_schedule(&self, name: String) -> Result<u64, PgAdapterError> {
+        (self.delete_schedule)(name)
+    }
+    async fn mark_schedule_fired(&self, name: String, fire_time: DateTime<Utc>) -> Result<u64, PgAdapterError> {
+        (self.mark_schedule_fired)(name, fire_time)
+    }
+    async fn register_worker(&self, worker: Worker) -> Result<Worker, PgAdapterError> {
+        (self.register_worker)(worker)
+    }
+    async fn heartbeat_worker(&self, id: String) -> Result<Worker, PgAdapterError> {
+        (self.heartbeat_worker)(id)
+    }
+    async fn query_worker_by_id(&self, id: String) -> Result<Worker, PgAdapterError> {
+        (self.query_worker_by_id)(id)
+    }
+    async fn query_workers(&self) -> Result<Vec<Worker>, PgAdapterError> {
+        (self.query_workers)()
+    }
+    async fn set_worker_draining(&self, id: String, draining: bool) -> Result<Worker, PgAdapterError> {
+        (self.set_worker_draining)(id, draining)
+    }
+    async fn delete_worker(&self, id: String) -> Result<u64, PgAdapterError> {
+        (self.delete_worker)(id)
+    }
 }
 
 #[tokio::test]
@@ -161,8 +441,32 @@ async fn pg_manager_mock_adapter() {
                 rn: "reset".to_string(),
                 ..Task::default()
             }])
+        })
+        .on_resolve_blocked_batch(|| {
+            Ok(vec![Task {
+                rn: "resolved".to_string(),
+                ..Task::default()
+            }])
+        })
+        .on_expire_batch(|| {
+            Ok(vec![Task {
+                rn: "expired".to_string(),
+                status: TaskStatus::Expired,
+                ..Task::default()
+            }])
+        })
+        .on_cancel_batch(|_| {
+            Ok(vec![Task {
+                rn: "cancelled".to_string(),
+                status: TaskStatus::Cancelled,
+                ..Task::default()
+            }])
         });
-    let pgm = PgManager { pg_adapter: Box::new(mock) };
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
     assert_eq!(pgm.fetch_task("rn".to_string()).await.unwrap().rn, "query_by_rn".to_string());
     assert_eq!(
         pgm.fetch_tasks(GetTaskModel {
@@ -170,6 +474,7 @@ async fn pg_manager_mock_adapter() {
             queue: None,
             worker: None,
             status: None,
+            labels: Vec::new(),
         })
         .await
         .unwrap()[0]
@@ -182,6 +487,7 @@ async fn pg_manager_mock_adapter() {
             spec: serde_json::Value::default(),
             priority: 1,
             queue: "s".to_string(),
+            ..AddTaskModel::default()
         })
         .await
         .unwrap()
@@ -196,18 +502,21 @@ async fn pg_manager_mock_adapter() {
                 spec: task1.spec.clone(),
                 priority: task1.priority,
                 queue: task1.queue.clone(),
+                ..AddTaskModel::default()
             },
             AddTaskModel {
                 rn: task2.rn.clone(),
                 spec: task2.spec.clone(),
                 priority: task2.priority,
                 queue: task2.queue.clone(),
+                ..AddTaskModel::default()
             },
             AddTaskModel {
                 rn: task3.rn.clone(),
                 spec: task3.spec.clone(),
                 priority: task3.priority,
                 queue: task3.queue.clone(),
+                ..AddTaskModel::default()
             },
         ])
         .await
@@ -240,9 +549,15 @@ async fn pg_manager_mock_adapter() {
     );
     // update cases
     assert_eq!(pgm.lease_task("2".to_string(), "w".to_string(), None).await.unwrap().rn, "update".to_string());
-    assert_eq!(pgm.cancel_task("2".to_string()).await.unwrap().rn, "update".to_string());
+    assert_eq!(
+        pgm.cancel_task("2".to_string(), "client".to_string(), None).await.unwrap().rn,
+        "update".to_string()
+    );
     // reset
     assert_eq!(pgm.reset_batch().await.unwrap().first().unwrap().rn, "reset".to_string());
+    assert_eq!(pgm.resolve_blocked_batch().await.unwrap().first().unwrap().rn, "resolved".to_string());
+    assert_eq!(pgm.expire_batch().await.unwrap().first().unwrap().rn, "expired".to_string());
+    assert_eq!(pgm.cancel_batch(60).await.unwrap().first().unwrap().rn, "cancelled".to_string());
 
     //heartbeat
     let mock = MockPgAdapter::default()
@@ -258,18 +573,63 @@ async fn pg_manager_mock_adapter() {
         .on_update(|_t| {
             Ok(Task {
                 rn: "update".to_string(),
+                owner: Some("worker".to_string()),
+                status: TaskStatus::Running,
                 ..Task::default()
             })
         });
-    let pgm = PgManager { pg_adapter: Box::new(mock) };
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    assert!(matches!(
+        pgm.heartbeat_task("2".to_string(), "worker".to_string(), 0, None, Some(5)).await.unwrap(),
+        HeartbeatOutcome::Extended { task } if task.rn == *"update"
+    ));
+    // rejected heartbeat is reported by the state of the lease
     assert_eq!(
-        pgm.heartbeat_task("2".to_string(), "worker".to_string(), None, Some(5)).await.unwrap().rn,
+        pgm.heartbeat_task("2".to_string(), "worker2".to_string(), 0, None, Some(5)).await.unwrap(),
+        HeartbeatOutcome::LeaseLost {
+            new_owner: Some("worker".to_string()),
+            status: TaskStatus::Running,
+        }
+    );
+    let pgm_without_task = PgManager {
+        pg_adapter: Box::new(MockPgAdapter::default().on_query_by_rn(|rn| Err(PgAdapterError::NoTaskFound(rn)))),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    assert_eq!(
+        pgm_without_task
+            .heartbeat_task("2".to_string(), "worker".to_string(), 0, None, None)
+            .await
+            .unwrap(),
+        HeartbeatOutcome::NotFound
+    );
+    assert_eq!(
+        pgm.complete_task("2".to_string(), "worker".to_string(), 0, None, None).await.unwrap().rn,
         "update".to_string()
     );
-    assert_eq!(pgm.complete_task("2".to_string(), None).await.unwrap().rn, "update".to_string());
+    assert_eq!(
+        pgm.complete_task("2".to_string(), "worker".to_string(), 0, Some("{".to_string()), None)
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Validation failed: Invalid JSON for metrics: EOF while parsing an object at line 1 column 1".to_string()
+    );
+    assert_eq!(
+        pgm.complete_task("2".to_string(), "worker".to_string(), 0, None, Some("{\"rows\": }".to_string()))
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Validation failed: Invalid JSON for result: expected value at line 1 column 10".to_string()
+    );
     assert_eq!(
         pgm.abort_task(
             "2".to_string(),
+            "worker".to_string(),
+            0,
             TaskError {
                 code: "123".to_string(),
                 args: serde_json::Value::default(),
@@ -281,6 +641,682 @@ async fn pg_manager_mock_adapter() {
         .rn,
         "update".to_string()
     );
-    assert_eq!(pgm.yield_task("2".to_string()).await.unwrap().rn, "update".to_string());
+    assert_eq!(pgm.yield_task("2".to_string(), "worker".to_string(), 0).await.unwrap().rn, "update".to_string());
     assert_eq!(pgm.reset_task("2".to_string()).await.unwrap().rn, "update".to_string());
 }
+
+#[tokio::test]
+async fn pg_manager_update_retries_on_concurrent_modification() {
+    static VERSION: AtomicI64 = AtomicI64::new(0);
+    // every read sees a newer version, update is only accepted once the task has been read at version 2
+    let mock = MockPgAdapter::default()
+        .on_query_by_rn(|rn| {
+            Ok(Task {
+                rn,
+                status: TaskStatus::Ready,
+                version: VERSION.fetch_add(1, Ordering::SeqCst),
+                ..Task::default()
+            })
+        })
+        .on_update(|t| {
+            if t.version < 2 {
+                Err(PgAdapterError::ConcurrentModification(t.rn))
+            } else {
+                Ok(Task { version: t.version + 1, ..t })
+            }
+        });
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    let task = pgm.lease_task("1".to_string(), "worker".to_string(), None).await.unwrap();
+    assert_eq!(task.status, TaskStatus::Running);
+    assert_eq!(task.version, 3);
+
+    // gives up after max tries
+    let mock = MockPgAdapter::default()
+        .on_query_by_rn(|rn| {
+            Ok(Task {
+                rn,
+                status: TaskStatus::Ready,
+                ..Task::default()
+            })
+        })
+        .on_update(|t| Err(PgAdapterError::ConcurrentModification(t.rn)));
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    assert_eq!(
+        pgm.cancel_task("1".to_string(), "client".to_string(), None).await.unwrap_err().to_string(),
+        "Task 1 was modified concurrently".to_string()
+    );
+}
+
+#[tokio::test]
+async fn pg_manager_supersede_task() {
+    let mock = MockPgAdapter::default().on_supersede(|task, cancel_running| {
+        assert!(cancel_running);
+        Ok(task)
+    });
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    let atm = AddTaskModel {
+        rn: "new".to_string(),
+        queue: "q".to_string(),
+        supersession_key: Some("key".to_string()),
+        ..AddTaskModel::default()
+    };
+    let task = pgm.supersede_task(atm, true).await.unwrap();
+    assert_eq!(task.supersession_key, Some("key".to_string()));
+    let result = pgm
+        .supersede_task(
+            AddTaskModel {
+                rn: "new".to_string(),
+                ..AddTaskModel::default()
+            },
+            true,
+        )
+        .await;
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Validation failed: supersession_key is mandatory to supersede a task".to_string()
+    );
+}
+
+#[tokio::test]
+async fn pg_manager_resubmit_task_retries_on_concurrent_modification() {
+    static PREVIOUS_VERSION: AtomicI64 = AtomicI64::new(0);
+    let mock = MockPgAdapter::default()
+        .on_query_by_rn(|rn| {
+            Ok(Task {
+                rn,
+                status: TaskStatus::Aborted,
+                priority: 3,
+                version: PREVIOUS_VERSION.fetch_add(1, Ordering::SeqCst),
+                ..Task::default()
+            })
+        })
+        .on_resubmit(|task, previous_version| {
+            if previous_version == 0 {
+                Err(PgAdapterError::ConcurrentModification(task.previous_rn.unwrap()))
+            } else {
+                Ok(task)
+            }
+        });
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    let overrides = ResubmitOverrides {
+        priority: Some(7),
+        ..ResubmitOverrides::default()
+    };
+    let task = pgm.resubmit_task("old".to_string(), "new".to_string(), overrides).await.unwrap();
+    assert_eq!(task.rn, "new".to_string());
+    assert_eq!(task.previous_rn, Some("old".to_string()));
+    assert_eq!(task.priority, 7);
+    assert_eq!(PREVIOUS_VERSION.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn pg_manager_queue_operations() {
+    let mock = MockPgAdapter::default().on_upsert_queue(Ok).on_query_queue_by_name(|name| {
+        Ok(Queue {
+            name,
+            max_attempts: Some(5),
+            ..Queue::default()
+        })
+    });
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    let queue = Queue {
+        name: "q".to_string(),
+        max_attempts: Some(3),
+        ..Queue::default()
+    };
+    assert_eq!(pgm.upsert_queue(queue.clone()).await.unwrap(), queue);
+    assert_eq!(pgm.fetch_queue("q".to_string()).await.unwrap().max_attempts, Some(5));
+}
+
+#[tokio::test]
+async fn pg_manager_queue_defaults() {
+    let mock = MockPgAdapter::default()
+        .on_query_queue_by_name(|name| {
+            if name == "q" {
+                Ok(Queue {
+                    name,
+                    lease_timeout_in_secs: Some(60),
+                    max_priority: Some(50),
+                    ..Queue::default()
+                })
+            } else {
+                Err(PgAdapterError::NoQueueFound(name))
+            }
+        })
+        .on_query_by_rn(|rn| {
+            Ok(Task {
+                rn,
+                queue: "q".to_string(),
+                ..Task::default()
+            })
+        })
+        .on_insert(Ok)
+        .on_batch_insert(|tasks| {
+            Ok(TaskBatch {
+                inserted: tasks,
+                failed_to_insert: Vec::new(),
+            })
+        })
+        .on_update(Ok);
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    let add_task_model = |queue: &str, priority: i8| AddTaskModel {
+        rn: "1".to_string(),
+        queue: queue.to_string(),
+        priority,
+        ..AddTaskModel::default()
+    };
+    // lease timeout of the queue applies unless the worker passes one
+    let leased = pgm.lease_task("1".to_string(), "worker".to_string(), None).await.unwrap();
+    assert!(leased.deadline.unwrap() > Utc::now() + Duration::seconds(55));
+    let leased = pgm.lease_task("1".to_string(), "worker".to_string(), Some(5)).await.unwrap();
+    assert!(leased.deadline.unwrap() < Utc::now() + Duration::seconds(10));
+    // max priority of the queue
+    pgm.insert_task(add_task_model("q", 50)).await.unwrap();
+    assert!(matches!(
+        pgm.insert_task(add_task_model("q", 51)).await,
+        Err(PgAdapterError::ScyllaOpsError(ScyllaOperationsError::ValidationFailed(_)))
+    ));
+    assert!(pgm
+        .batch_insert_tasks(vec![
+            add_task_model("unregistered", 100),
+            AddTaskModel {
+                rn: "2".to_string(),
+                ..add_task_model("q", 51)
+            },
+        ])
+        .await
+        .is_err());
+    assert!(pgm.reprioritise_task("1".to_string(), 51, "operator".to_string()).await.is_err());
+    assert_eq!(pgm.reprioritise_task("1".to_string(), 50, "operator".to_string()).await.unwrap().priority, 50);
+    // unregistered queues are accepted unless registration is required
+    pgm.insert_task(add_task_model("unregistered", 100)).await.unwrap();
+    let pgm = pgm.require_registered_queues(true);
+    pgm.insert_task(add_task_model("q", 1)).await.unwrap();
+    assert!(matches!(
+        pgm.insert_task(add_task_model("unregistered", 1)).await,
+        Err(PgAdapterError::NoQueueFound(queue)) if queue == "unregistered"
+    ));
+    assert!(matches!(
+        pgm.batch_insert_tasks(vec![
+            add_task_model("q", 1),
+            AddTaskModel {
+                rn: "2".to_string(),
+                ..add_task_model("unregistered", 1)
+            },
+        ]).await,
+        Err(PgAdapterError::NoQueueFound(queue)) if queue == "unregistered"
+    ));
+    assert!(pgm
+        .upsert_queue(Queue {
+            name: "q".to_string(),
+            lease_timeout_in_secs: Some(0),
+            ..Queue::default()
+        })
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn pg_manager_queue_state_operations() {
+    let mock = MockPgAdapter::default()
+        .on_upsert_queue_state(Ok)
+        .on_query_queue_state(|name| Ok(QueueState { name, mode: QueueMode::Paused }))
+        .on_query_by_rn(|rn| {
+            Ok(Task {
+                rn,
+                queue: "q".to_string(),
+                ..Task::default()
+            })
+        })
+        .on_lease(|t| Err(PgAdapterError::QueuePaused(t.queue)));
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    assert_eq!(pgm.pause_queue("q".to_string()).await.unwrap().mode, QueueMode::Paused);
+    assert_eq!(pgm.drain_queue("q".to_string()).await.unwrap().mode, QueueMode::Draining);
+    assert_eq!(pgm.resume_queue("q".to_string()).await.unwrap().mode, QueueMode::Active);
+    assert_eq!(pgm.fetch_queue_state("q".to_string()).await.unwrap().mode, QueueMode::Paused);
+    // tasks of a paused queue cannot be leased, which is not retried as a concurrent modification
+    assert!(matches!(
+        pgm.lease_task("1".to_string(), "worker".to_string(), None).await,
+        Err(PgAdapterError::QueuePaused(queue)) if queue == "q"
+    ));
+}
+
+#[tokio::test]
+async fn pg_manager_lease_task_respects_concurrency_limit() {
+    let mock = MockPgAdapter::default()
+        .on_query_by_rn(|rn| {
+            Ok(Task {
+                rn,
+                queue: "q".to_string(),
+                ..Task::default()
+            })
+        })
+        .on_update(Ok)
+        .on_lease(|t| Err(PgAdapterError::ConcurrencyLimitReached(t.queue)));
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    // limit is not retried as a concurrent modification
+    assert!(matches!(
+        pgm.lease_task("1".to_string(), "worker".to_string(), None).await,
+        Err(PgAdapterError::ConcurrencyLimitReached(queue)) if queue == "q"
+    ));
+    // other operations are plain updates
+    assert_eq!(pgm.reprioritise_task("1".to_string(), 5, "operator".to_string()).await.unwrap().priority, 5);
+}
+
+#[tokio::test]
+async fn pg_manager_fetch_rate_limit_budget() {
+    let mock = MockPgAdapter::default().on_query_rate_limit_budget(|name| match name.as_str() {
+        "limited" => Ok(RateLimitBudget {
+            name,
+            remaining: 3,
+            available_at: Utc::now(),
+        }),
+        _ => Err(PgAdapterError::NoRateLimitFound(name)),
+    });
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    assert_eq!(pgm.fetch_rate_limit_budget("limited".to_string()).await.unwrap().remaining, 3);
+    assert!(matches!(
+        pgm.fetch_rate_limit_budget("other".to_string()).await,
+        Err(PgAdapterError::NoRateLimitFound(queue)) if queue == "other"
+    ));
+}
+
+#[tokio::test]
+async fn pg_manager_schedule_operations() {
+    let mock = MockPgAdapter::default()
+        .on_upsert_schedule(Ok)
+        .on_query_schedule_by_name(|name| Ok(Schedule { name, ..Schedule::default() }))
+        .on_delete_schedule(|_| Ok(1));
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    let schedule = Schedule {
+        name: "nightly".to_string(),
+        cron: "0 0 * * *".to_string(),
+        queue: "q".to_string(),
+        ..Schedule::default()
+    };
+    assert_eq!(pgm.upsert_schedule(schedule.clone()).await.unwrap(), schedule);
+    assert!(pgm
+        .upsert_schedule(Schedule {
+            cron: "invalid".to_string(),
+            ..schedule
+        })
+        .await
+        .is_err());
+    assert_eq!(pgm.fetch_schedule("nightly".to_string()).await.unwrap().name, "nightly".to_string());
+    assert_eq!(pgm.delete_schedule("nightly".to_string()).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn pg_manager_upsert_schedule_validates_task() {
+    let mock = MockPgAdapter::default().on_upsert_schedule(Ok).on_query_queue_by_name(|name| {
+        if name == "q" {
+            Ok(Queue {
+                name,
+                max_priority: Some(50),
+                ..Queue::default()
+            })
+        } else {
+            Err(PgAdapterError::NoQueueFound(name))
+        }
+    });
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: true,
+        require_registered_workers: false,
+    };
+    let schedule = Schedule {
+        name: "nightly".to_string(),
+        cron: "0 0 * * *".to_string(),
+        queue: "q".to_string(),
+        priority: 50,
+        ..Schedule::default()
+    };
+    assert_eq!(pgm.upsert_schedule(schedule.clone()).await.unwrap(), schedule);
+    // max priority of the queue
+    assert!(matches!(
+        pgm.upsert_schedule(Schedule {
+            priority: 51,
+            ..schedule.clone()
+        })
+        .await,
+        Err(PgAdapterError::ScyllaOpsError(ScyllaOperationsError::ValidationFailed(_)))
+    ));
+    assert!(matches!(
+        pgm.upsert_schedule(Schedule {
+            queue: "unregistered".to_string(),
+            ..schedule
+        })
+        .await,
+        Err(PgAdapterError::NoQueueFound(queue)) if queue == "unregistered"
+    ));
+}
+
+#[tokio::test]
+async fn pg_manager_fire_due_schedules() {
+    let mock = MockPgAdapter::default()
+        .on_query_schedules(|| {
+            let created = Utc::now() - Duration::minutes(2);
+            Ok(vec![
+                Schedule {
+                    name: "due".to_string(),
+                    cron: "* * * * *".to_string(),
+                    created,
+                    ..Schedule::default()
+                },
+                Schedule {
+                    name: "duplicate".to_string(),
+                    cron: "* * * * *".to_string(),
+                    created,
+                    ..Schedule::default()
+                },
+                Schedule {
+                    name: "draining".to_string(),
+                    cron: "* * * * *".to_string(),
+                    queue: "drained".to_string(),
+                    created,
+                    ..Schedule::default()
+                },
+                Schedule {
+                    name: "not_due".to_string(),
+                    cron: "* * * * *".to_string(),
+                    last_fired: Some(Utc::now()),
+                    ..Schedule::default()
+                },
+                Schedule {
+                    name: "invalid".to_string(),
+                    cron: "invalid".to_string(),
+                    created,
+                    ..Schedule::default()
+                },
+                Schedule {
+                    name: "too_high".to_string(),
+                    cron: "* * * * *".to_string(),
+                    queue: "limited".to_string(),
+                    priority: 51,
+                    created,
+                    ..Schedule::default()
+                },
+            ])
+        })
+        .on_query_queue_by_name(|name| {
+            if name == "limited" {
+                Ok(Queue {
+                    name,
+                    max_priority: Some(50),
+                    ..Queue::default()
+                })
+            } else {
+                Err(PgAdapterError::NoQueueFound(name))
+            }
+        })
+        .on_insert(|task| {
+            if task.rn.starts_with("duplicate") {
+                Err(PgAdapterError::DuplicateTask(task.rn))
+            } else if task.queue == "drained" {
+                Err(PgAdapterError::QueueDraining(task.queue))
+            } else {
+                Ok(task)
+            }
+        })
+        .on_mark_schedule_fired(|name, _| {
+            if name == "not_due" || name == "invalid" || name == "too_high" {
+                unreachable!()
+            } else {
+                Ok(1)
+            }
+        });
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    let tasks = pgm.fire_due_schedules().await.unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert!(tasks[0].rn.starts_with("due:"));
+    // schedules of unregistered queues are skipped if registration is required
+    let pgm = pgm.require_registered_queues(true);
+    assert!(pgm.fire_due_schedules().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn pg_manager_worker_operations() {
+    let mock = MockPgAdapter::default()
+        .on_register_worker(|worker| {
+            Ok(Worker {
+                id: "worker-1".to_string(),
+                ..worker
+            })
+        })
+        .on_heartbeat_worker(|id| Ok(Worker { id, ..Worker::default() }))
+        .on_query_worker_by_id(|id| match id.as_str() {
+            "worker-1" => Ok(Worker { id, ..Worker::default() }),
+            _ => Err(PgAdapterError::NoWorkerFound(id)),
+        })
+        .on_query_workers(|| Ok(vec![Worker::default()]))
+        .on_delete_worker(|id| Ok(u64::from(id == "worker-1")))
+        .on_lease_batch(|_, _, _, _, _| Ok(vec![]));
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    }
+    .require_registered_workers(true);
+    let metadata = WorkerMetadata {
+        hostname: "node-1".to_string(),
+        version: "1.4.0".to_string(),
+    };
+    let worker = pgm.register_worker(metadata.clone()).await.unwrap();
+    assert_eq!(worker.id, "worker-1".to_string());
+    assert_eq!(worker.hostname, "node-1".to_string());
+    assert!(matches!(
+        pgm.register_worker(WorkerMetadata {
+            hostname: String::new(),
+            ..metadata
+        })
+        .await,
+        Err(PgAdapterError::ScyllaOpsError(ScyllaOperationsError::ValidationFailed(_)))
+    ));
+    assert_eq!(pgm.heartbeat_worker("worker-1".to_string()).await.unwrap().id, "worker-1".to_string());
+    assert_eq!(pgm.fetch_worker("worker-1".to_string()).await.unwrap().id, "worker-1".to_string());
+    assert_eq!(pgm.fetch_workers().await.unwrap().len(), 1);
+
+    // only registered workers lease tasks
+    assert!(pgm
+        .lease_n_tasks("q".to_string(), 1, "worker-1".to_string(), None, Vec::new())
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(matches!(
+        pgm.lease_n_tasks("q".to_string(), 1, "worker-2".to_string(), None, Vec::new()).await,
+        Err(PgAdapterError::NoWorkerFound(id)) if id == "worker-2"
+    ));
+    assert!(matches!(
+        pgm.lease_task("1".to_string(), "worker-2".to_string(), None).await,
+        Err(PgAdapterError::NoWorkerFound(id)) if id == "worker-2"
+    ));
+
+    pgm.deregister_worker("worker-1".to_string()).await.unwrap();
+    assert!(matches!(
+        pgm.deregister_worker("worker-2".to_string()).await,
+        Err(PgAdapterError::NoWorkerFound(id)) if id == "worker-2"
+    ));
+}
+
+#[tokio::test]
+async fn pg_manager_heartbeat_tasks() {
+    let mock = MockPgAdapter::default().on_heartbeat_batch(|_, _, _| {
+        let running = Task {
+            status: TaskStatus::Running,
+            owner: Some("worker".to_string()),
+            lease_epoch: 1,
+            ..Task::default()
+        };
+        Ok(vec![
+            Task {
+                rn: "1".to_string(),
+                ..running.clone()
+            },
+            Task {
+                rn: "2".to_string(),
+                owner: Some("worker2".to_string()),
+                lease_epoch: 2,
+                ..running.clone()
+            },
+            Task {
+                rn: "3".to_string(),
+                status: TaskStatus::Cancelling,
+                ..running
+            },
+        ])
+    });
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    let heartbeats = ["1", "2", "3", "4"]
+        .map(|rn| TaskHeartbeat {
+            rn: rn.to_string(),
+            lease_epoch: 1,
+            progress: Some(0.5),
+        })
+        .to_vec();
+    let outcomes = pgm.heartbeat_tasks("worker".to_string(), heartbeats, None).await.unwrap();
+    let rns: Vec<&str> = outcomes.iter().map(|o| o.rn.as_str()).collect();
+    assert_eq!(rns, ["1", "2", "3", "4"]);
+    assert!(matches!(&outcomes[0].outcome, HeartbeatOutcome::Extended { task } if task.rn == "1"));
+    assert_eq!(
+        outcomes[1].outcome,
+        HeartbeatOutcome::LeaseLost {
+            new_owner: Some("worker2".to_string()),
+            status: TaskStatus::Running,
+        }
+    );
+    assert!(matches!(&outcomes[2].outcome, HeartbeatOutcome::Cancelled { task } if task.rn == "3"));
+    assert_eq!(outcomes[3].outcome, HeartbeatOutcome::NotFound);
+}
+
+#[tokio::test]
+async fn pg_manager_release_worker_tasks() {
+    let mock = MockPgAdapter::default()
+        .on_yield_worker_batch(|worker| {
+            Ok(vec![Task {
+                owner: Some(worker),
+                ..Task::default()
+            }])
+        })
+        .on_reset_worker_batch(|worker, actor| match worker.as_str() {
+            "dead" => Ok(vec![Task { rn: actor, ..Task::default() }]),
+            _ => Ok(vec![]),
+        });
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    let yielded = pgm.yield_worker_tasks("worker".to_string()).await.unwrap();
+    assert_eq!(yielded[0].owner, Some("worker".to_string()));
+    let reset = pgm.reset_worker_tasks("dead".to_string(), "operator".to_string()).await.unwrap();
+    assert_eq!(reset[0].rn, "operator".to_string());
+    assert!(pgm.reset_worker_tasks("alive".to_string(), "operator".to_string()).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn pg_manager_drain_worker() {
+    let mock = MockPgAdapter::default()
+        .on_query_worker_by_id(|id| {
+            Ok(Worker {
+                draining: id == "drained",
+                id,
+                ..Worker::default()
+            })
+        })
+        .on_set_worker_draining(|id, draining| {
+            Ok(Worker {
+                id,
+                draining,
+                ..Worker::default()
+            })
+        })
+        .on_heartbeat_batch(|worker, heartbeats, _| {
+            Ok(heartbeats
+                .into_iter()
+                .map(|heartbeat| Task {
+                    rn: heartbeat.rn,
+                    status: TaskStatus::Running,
+                    owner: Some(worker.clone()),
+                    lease_epoch: heartbeat.lease_epoch,
+                    ..Task::default()
+                })
+                .collect())
+        });
+    let pgm = PgManager {
+        pg_adapter: Box::new(mock),
+        require_registered_queues: false,
+        require_registered_workers: false,
+    };
+    assert!(pgm.drain_worker("drained".to_string()).await.unwrap().draining);
+    assert!(!pgm.resume_worker("drained".to_string()).await.unwrap().draining);
+
+    // a draining worker is leased no tasks
+    assert!(pgm
+        .lease_n_tasks("q".to_string(), 1, "drained".to_string(), None, Vec::new())
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(matches!(
+        pgm.lease_task("1".to_string(), "drained".to_string(), None).await,
+        Err(PgAdapterError::WorkerDraining(id)) if id == "drained"
+    ));
+
+    // and is requested to yield the tasks it holds
+    let heartbeats = vec![TaskHeartbeat {
+        rn: "1".to_string(),
+        lease_epoch: 1,
+        progress: None,
+    }];
+    let outcomes = pgm.heartbeat_tasks("drained".to_string(), heartbeats.clone(), None).await.unwrap();
+    assert!(matches!(&outcomes[0].outcome, HeartbeatOutcome::YieldRequested { task } if task.rn == "1"));
+    let outcomes = pgm.heartbeat_tasks("worker".to_string(), heartbeats, None).await.unwrap();
+    assert!(matches!(&outcomes[0].outcome, HeartbeatOutcome::Extended { task } if task.rn == "1"));
+}
@@ -13,6 +13,7 @@ async fn insert_task() {
         queue: "add_test".to_string(),
         priority: 1,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
 
     let inserted_task = pgm.insert_task(atm).await.unwrap();
@@ -27,6 +28,7 @@ async fn insert_task() {
         queue: "add_test".to_string(),
         priority: 1,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
     let inserted_task_result = pgm.insert_task(atm_with_same_rn).await;
     assert!(inserted_task_result.is_err());
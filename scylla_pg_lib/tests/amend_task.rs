@@ -0,0 +1,48 @@
+mod common;
+
+use scylla_models::{AddTaskModel, TaskHistoryType, TaskStatus};
+
+fn add_task_model(rn: &str, priority: i8) -> AddTaskModel {
+    AddTaskModel {
+        priority,
+        spec: serde_json::json!({"account": "a", "amount": 10}),
+        ..common::add_task_model(rn, "test")
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn reprioritise_and_amend_ready_task() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.insert_task(add_task_model("low", 1)).await.unwrap();
+    pgm.insert_task(add_task_model("high", 5)).await.unwrap();
+
+    let reprioritised = pgm.reprioritise_task("low".to_string(), 9, "operator".to_string()).await.unwrap();
+    assert_eq!(reprioritised.priority, 9);
+    assert_eq!(reprioritised.history[0].typ, TaskHistoryType::Reprioritisation);
+    assert_eq!(reprioritised.history[0].actor, Some("operator".to_string()));
+
+    let amended = pgm
+        .amend_task_spec("low".to_string(), serde_json::json!({"amount": 20}), "operator".to_string())
+        .await
+        .unwrap();
+    assert_eq!(amended.spec, serde_json::json!({"account": "a", "amount": 20}));
+    assert_eq!(amended.history[1].typ, TaskHistoryType::SpecAmendment);
+
+    // reprioritised task is leased first
+    let leased_tasks = pgm.lease_n_tasks("test".to_string(), 1, "worker".to_string(), None, Vec::new()).await.unwrap();
+    assert_eq!(leased_tasks[0].rn, "low".to_string());
+    assert_eq!(leased_tasks[0].status, TaskStatus::Running);
+
+    // running task cannot be amended anymore
+    assert!(pgm.reprioritise_task("low".to_string(), 1, "operator".to_string()).await.is_err());
+    assert!(pgm
+        .amend_task_spec("low".to_string(), serde_json::json!({"amount": 30}), "operator".to_string())
+        .await
+        .is_err());
+
+    // truncate table after use
+    common::truncate_table().await;
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use scylla_models::{HeartbeatOutcome, TaskHistoryType, TaskStatus};
+use scylla_pg_lib::error::PgAdapterError;
+
+#[tokio::test]
+#[ignore]
+async fn cancellation_of_running_task_is_acknowledged_by_worker() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.insert_task(common::add_task_model("running", "test")).await.unwrap();
+    let leased = pgm.lease_task("running".to_string(), "worker".to_string(), None).await.unwrap();
+
+    let cancelling = pgm
+        .cancel_task("running".to_string(), "client".to_string(), Some("no longer needed".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(cancelling.status, TaskStatus::Cancelling);
+    let cancel_requested = cancelling.cancel_requested.unwrap();
+    assert_eq!(cancel_requested.requester, Some("client".to_string()));
+    assert_eq!(cancel_requested.reason, Some("no longer needed".to_string()));
+
+    // worker learns of the cancellation on its heartbeat and cannot complete the task anymore
+    let HeartbeatOutcome::Cancelled { task: heartbeat } = pgm
+        .heartbeat_task("running".to_string(), "worker".to_string(), leased.lease_epoch, Some(0.5), None)
+        .await
+        .unwrap()
+    else {
+        panic!("heartbeat is expected to report the requested cancellation");
+    };
+    assert_eq!(heartbeat.status, TaskStatus::Cancelling);
+    assert!(heartbeat.cancel_requested.is_some());
+    assert!(pgm
+        .complete_task("running".to_string(), "worker".to_string(), leased.lease_epoch, None, None)
+        .await
+        .is_err());
+    // only the worker acknowledges the cancellation
+    assert!(matches!(
+        pgm.cancel_task("running".to_string(), "client".to_string(), None).await,
+        Err(PgAdapterError::ScyllaOpsError(_))
+    ));
+
+    let cancelled = pgm
+        .acknowledge_cancellation("running".to_string(), "worker".to_string(), leased.lease_epoch)
+        .await
+        .unwrap();
+    assert_eq!(cancelled.status, TaskStatus::Cancelled);
+    let history = cancelled.history.last().unwrap();
+    assert_eq!(history.typ, TaskHistoryType::Cancellation);
+    assert_eq!(history.actor, Some("worker".to_string()));
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn cancel_batch_cancels_unacknowledged_tasks() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    for rn in ["unacknowledged", "slow_worker", "dead_worker"] {
+        pgm.insert_task(common::add_task_model(rn, "test")).await.unwrap();
+    }
+    pgm.lease_task("unacknowledged".to_string(), "worker".to_string(), Some(60)).await.unwrap();
+    pgm.lease_task("slow_worker".to_string(), "worker".to_string(), Some(60)).await.unwrap();
+    pgm.lease_task("dead_worker".to_string(), "worker".to_string(), Some(-1)).await.unwrap();
+    for rn in ["unacknowledged", "slow_worker", "dead_worker"] {
+        pgm.cancel_task(rn.to_string(), "client".to_string(), None).await.unwrap();
+    }
+
+    // within the grace period only the task whose deadline has passed is cancelled
+    let cancelled = pgm.cancel_batch(60).await.unwrap();
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!(cancelled[0].rn, "dead_worker".to_string());
+    assert_eq!(cancelled[0].status, TaskStatus::Cancelled);
+    let history = cancelled[0].history.last().unwrap();
+    assert_eq!(history.typ, TaskHistoryType::Cancellation);
+    assert_eq!(history.actor, Some("monitor".to_string()));
+    assert_eq!(history.reason, Some("cancellation_not_acknowledged".to_string()));
+
+    // past the grace period
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    let mut rns: Vec<String> = pgm.cancel_batch(0).await.unwrap().into_iter().map(|t| t.rn).collect();
+    rns.sort();
+    assert_eq!(rns, vec!["slow_worker".to_string(), "unacknowledged".to_string()]);
+
+    // truncate table after use
+    common::truncate_table().await;
+}
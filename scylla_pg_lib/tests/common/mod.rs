@@ -1,3 +1,4 @@
+use scylla_models::AddTaskModel;
 use scylla_pg_core::config;
 use scylla_pg_core::connection::get_client;
 use scylla_pg_lib::manager::PgManager;
@@ -5,7 +6,7 @@ use scylla_pg_lib::manager::PgManager;
 pub async fn truncate_table() {
     let conf = config::PGConfig::from_env().unwrap();
     let client = get_client(&(conf.to_pg_config())).await.unwrap();
-    let truncate_table_ddl = "TRUNCATE task".to_string();
+    let truncate_table_ddl = "TRUNCATE task, queue, queue_state, queue_rate_limit, schedule, worker".to_string();
     client.execute(&truncate_table_ddl, &[]).await.unwrap();
 }
 
@@ -13,3 +14,15 @@ pub async fn get_pg_manager() -> PgManager {
     let config = config::PGConfig::from_env().unwrap();
     PgManager::from_config(&config).expect("Error creating PgManager Instance")
 }
+
+/// Ready task for `queue`, fields a test cares about are set by struct update syntax.
+#[allow(dead_code)] // not every test adds tasks this way
+pub fn add_task_model(rn: &str, queue: &str) -> AddTaskModel {
+    AddTaskModel {
+        rn: rn.to_string(),
+        queue: queue.to_string(),
+        priority: 10,
+        spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
+    }
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use std::sync::Arc;
+
+use scylla_models::{AddTaskModel, GetTaskModel, Queue, TaskStatus};
+use scylla_pg_lib::error::PgAdapterError;
+
+fn add_task_model(rn: &str, queue: &str, concurrency_key: Option<&str>) -> AddTaskModel {
+    AddTaskModel {
+        concurrency_key: concurrency_key.map(ToString::to_string),
+        ..common::add_task_model(rn, queue)
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+#[ignore]
+async fn concurrent_leases_respect_queue_limit() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = Arc::new(common::get_pg_manager().await);
+    pgm.upsert_queue(Queue {
+        name: "limited".to_string(),
+        max_running_tasks: Some(3),
+        ..Queue::default()
+    })
+    .await
+    .unwrap();
+    for i in 0..20 {
+        pgm.insert_task(add_task_model(&i.to_string(), "limited", None)).await.unwrap();
+    }
+
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let pgm = Arc::clone(&pgm);
+            tokio::spawn(async move { pgm.lease_n_tasks("limited".to_string(), 2, format!("worker{i}"), None, Vec::new()).await })
+        })
+        .collect();
+    let mut leased = 0;
+    for handle in handles {
+        leased += handle.await.unwrap().unwrap().len();
+    }
+    assert_eq!(leased, 3);
+    assert!(matches!(
+        pgm.lease_task("19".to_string(), "worker".to_string(), None).await,
+        Err(PgAdapterError::ConcurrencyLimitReached(queue)) if queue == "limited"
+    ));
+
+    // capacity is freed once a running task terminates
+    let running = pgm.lease_n_tasks("%".to_string(), 10, "worker".to_string(), None, Vec::new()).await.unwrap();
+    assert!(running.is_empty());
+    let task = pgm
+        .fetch_tasks(GetTaskModel {
+            queue: Some("limited".to_string()),
+            status: Some(TaskStatus::Running),
+            ..GetTaskModel::default()
+        })
+        .await
+        .unwrap()
+        .remove(0);
+    pgm.complete_task(task.rn, task.owner.unwrap(), task.lease_epoch, None, None).await.unwrap();
+    let leased = pgm
+        .lease_n_tasks("limited".to_string(), 10, "worker".to_string(), None, Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(leased.len(), 1);
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn leases_respect_per_key_limit() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.upsert_queue(Queue {
+        name: "keyed".to_string(),
+        max_running_tasks_per_key: Some(1),
+        ..Queue::default()
+    })
+    .await
+    .unwrap();
+    pgm.insert_task(add_task_model("a1", "keyed", Some("a"))).await.unwrap();
+    pgm.insert_task(add_task_model("a2", "keyed", Some("a"))).await.unwrap();
+    pgm.insert_task(add_task_model("b1", "keyed", Some("b"))).await.unwrap();
+    pgm.insert_task(add_task_model("b2", "keyed", Some("b"))).await.unwrap();
+    // tasks without a key are not limited
+    pgm.insert_task(add_task_model("n1", "keyed", None)).await.unwrap();
+    pgm.insert_task(add_task_model("n2", "keyed", None)).await.unwrap();
+
+    pgm.lease_task("a1".to_string(), "worker".to_string(), None).await.unwrap();
+    assert!(matches!(
+        pgm.lease_task("a2".to_string(), "worker".to_string(), None).await,
+        Err(PgAdapterError::ConcurrencyLimitReached(queue)) if queue == "keyed"
+    ));
+    let mut leased: Vec<String> = pgm
+        .lease_n_tasks("keyed".to_string(), 10, "worker".to_string(), None, Vec::new())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|task| task.rn)
+        .collect();
+    leased.sort();
+    assert_eq!(leased.len(), 3);
+    assert!(leased[0].starts_with('b'));
+    assert_eq!(leased[1..], ["n1".to_string(), "n2".to_string()]);
+
+    // truncate table after use
+    common::truncate_table().await;
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use scylla_models::{AddTaskModel, TaskStatus};
+
+fn add_task_model(rn: &str, expires_in: Duration) -> AddTaskModel {
+    AddTaskModel {
+        expires_at: Some(Utc::now() + expires_in),
+        ..common::add_task_model(rn, "test")
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn expire_batch_tasks() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.insert_task(add_task_model("expired", Duration::seconds(-1))).await.unwrap();
+    pgm.insert_task(add_task_model("not_expired", Duration::minutes(15))).await.unwrap();
+    pgm.insert_task(add_task_model("expires_while_running", Duration::milliseconds(500)))
+        .await
+        .unwrap();
+
+    // expired tasks are not leased
+    let leased_tasks = pgm
+        .lease_n_tasks("test".to_string(), 10, "worker".to_string(), Some(-1), Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(leased_tasks.len(), 2);
+    assert_eq!(leased_tasks.iter().position(|t| t.rn == *"expired"), None);
+
+    let expired_tasks = pgm.expire_batch().await.unwrap();
+    assert_eq!(expired_tasks.len(), 1);
+    assert_eq!(expired_tasks[0].rn, "expired".to_string());
+    assert_eq!(expired_tasks[0].status, TaskStatus::Expired);
+
+    // running task which timed out after its expiry is not retried
+    tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+    let reset_tasks = pgm.reset_batch().await.unwrap();
+    assert_eq!(reset_tasks.len(), 2);
+    let expired = reset_tasks.iter().find(|t| t.rn == *"expires_while_running").unwrap();
+    assert_eq!(expired.status, TaskStatus::Expired);
+    let reset = reset_tasks.iter().find(|t| t.rn == *"not_expired").unwrap();
+    assert_eq!(reset.status, TaskStatus::Ready);
+
+    // truncate table after use
+    common::truncate_table().await;
+}
@@ -13,6 +13,7 @@ async fn get_running_tasks() {
         queue: "add_test".to_string(),
         priority: 1,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
     pgm.insert_task(atm).await.unwrap();
 
@@ -23,6 +24,7 @@ async fn get_running_tasks() {
         worker: None,
         queue: None,
         limit: None,
+        labels: Vec::new(),
     };
     let running_tasks = pgm.fetch_tasks(gtm).await.unwrap();
     assert_eq!(running_tasks.len(), 1);
@@ -44,15 +46,17 @@ async fn get_cancelled_tasks() {
         queue: "add_test".to_string(),
         priority: 1,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
     pgm.insert_task(atm).await.unwrap();
-    pgm.cancel_task("add_test_1".to_string()).await.unwrap();
+    pgm.cancel_task("add_test_1".to_string(), "client".to_string(), None).await.unwrap();
 
     let gtm = GetTaskModel {
         status: Some(TaskStatus::Cancelled),
         worker: None,
         queue: None,
         limit: None,
+        labels: Vec::new(),
     };
     let cancelled_tasks = pgm.fetch_tasks(gtm).await.unwrap();
     assert_eq!(cancelled_tasks.len(), 1);
@@ -73,16 +77,18 @@ async fn get_completed_tasks() {
         queue: "add_test".to_string(),
         priority: 1,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
     pgm.insert_task(atm).await.unwrap();
     pgm.lease_task("add_test_1".to_string(), "worker".to_string(), None).await.unwrap();
-    pgm.complete_task("add_test_1".to_string(), None).await.unwrap();
+    pgm.complete_task("add_test_1".to_string(), "worker".to_string(), 1, None, None).await.unwrap();
 
     let gtm = GetTaskModel {
         status: Some(TaskStatus::Completed),
         worker: None,
         queue: None,
         limit: None,
+        labels: Vec::new(),
     };
     let completed_tasks = pgm.fetch_tasks(gtm).await.unwrap();
     assert_eq!(completed_tasks.len(), 1);
@@ -104,6 +110,7 @@ async fn get_ready_tasks() {
         queue: "add_test".to_string(),
         priority: 1,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
     // get before insert
     let gtm_0 = GetTaskModel {
@@ -111,6 +118,7 @@ async fn get_ready_tasks() {
         worker: None,
         queue: None,
         limit: None,
+        labels: Vec::new(),
     };
     assert_eq!(pgm.fetch_tasks(gtm_0).await.unwrap().len(), 0);
     pgm.insert_task(atm).await.unwrap();
@@ -119,6 +127,7 @@ async fn get_ready_tasks() {
         worker: None,
         queue: None,
         limit: None,
+        labels: Vec::new(),
     };
     let ready_tasks = pgm.fetch_tasks(gtm).await.unwrap();
     assert_eq!(ready_tasks.len(), 1);
@@ -140,6 +149,7 @@ async fn get_aborted_tasks() {
         queue: "add_test".to_string(),
         priority: 1,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
     // get before insert
     let gtm_0 = GetTaskModel {
@@ -147,12 +157,15 @@ async fn get_aborted_tasks() {
         worker: None,
         queue: None,
         limit: None,
+        labels: Vec::new(),
     };
     assert_eq!(pgm.fetch_tasks(gtm_0).await.unwrap().len(), 0);
     pgm.insert_task(atm).await.unwrap();
     pgm.lease_task("add_test_1".to_string(), "worker".to_string(), None).await.unwrap();
     pgm.abort_task(
         "add_test_1".to_string(),
+        "worker".to_string(),
+        1,
         TaskError {
             code: "101".to_string(),
             description: "basic validation failed".to_string(),
@@ -166,6 +179,7 @@ async fn get_aborted_tasks() {
         worker: None,
         queue: None,
         limit: None,
+        labels: Vec::new(),
     };
     let aborted_tasks = pgm.fetch_tasks(gtm).await.unwrap();
     assert_eq!(aborted_tasks.len(), 1);
@@ -188,12 +202,14 @@ async fn get_worker_tasks() {
         queue: "add_test".to_string(),
         priority: 1,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
     let atm_2 = AddTaskModel {
         rn: "add_test_2".to_string(),
         queue: "add_test".to_string(),
         priority: 1,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
     pgm.insert_task(atm_1).await.unwrap();
     pgm.insert_task(atm_2).await.unwrap();
@@ -203,6 +219,7 @@ async fn get_worker_tasks() {
         worker: Some("worker".to_string()),
         queue: None,
         limit: None,
+        labels: Vec::new(),
     };
     assert_eq!(pgm.fetch_tasks(gtm_1).await.unwrap().len(), 0);
     pgm.lease_task("add_test_1".to_string(), "worker".to_string(), None).await.unwrap();
@@ -212,6 +229,7 @@ async fn get_worker_tasks() {
         worker: Some("worker".to_string()),
         queue: None,
         limit: None,
+        labels: Vec::new(),
     };
     let fetched_tasks_2 = pgm.fetch_tasks(gtm_2).await.unwrap();
     assert_eq!(fetched_tasks_2.len(), 2);
@@ -222,6 +240,7 @@ async fn get_worker_tasks() {
         worker: Some("worker".to_string()),
         queue: None,
         limit: Some(1),
+        labels: Vec::new(),
     };
     let fetched_tasks_3 = pgm.fetch_tasks(gtm_3).await.unwrap();
     assert_eq!(fetched_tasks_3.len(), 1);
@@ -242,12 +261,14 @@ async fn get_queue_tasks() {
         queue: "add_test".to_string(),
         priority: 1,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
     let atm_2 = AddTaskModel {
         rn: "add_test_2".to_string(),
         queue: "add_test".to_string(),
         priority: 1,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
 
     // get before inserting
@@ -256,6 +277,7 @@ async fn get_queue_tasks() {
         worker: None,
         queue: Some("add_test".to_string()),
         limit: None,
+        labels: Vec::new(),
     };
     assert_eq!(pgm.fetch_tasks(gtm_1).await.unwrap().len(), 0);
     pgm.insert_task(atm_1).await.unwrap();
@@ -265,6 +287,7 @@ async fn get_queue_tasks() {
         worker: None,
         queue: Some("add_test".to_string()),
         limit: None,
+        labels: Vec::new(),
     };
     let fetched_tasks_2 = pgm.fetch_tasks(gtm_2).await.unwrap();
     assert_eq!(fetched_tasks_2.len(), 2);
@@ -275,6 +298,7 @@ async fn get_queue_tasks() {
         worker: None,
         queue: Some("add_test".to_string()),
         limit: Some(1),
+        labels: Vec::new(),
     };
     let fetched_tasks_3 = pgm.fetch_tasks(gtm_3).await.unwrap();
     assert_eq!(fetched_tasks_3.len(), 1);
@@ -285,6 +309,7 @@ async fn get_queue_tasks() {
         worker: None,
         queue: Some("add_testwww".to_string()),
         limit: None,
+        labels: Vec::new(),
     };
     assert_eq!(pgm.fetch_tasks(gtm_4).await.unwrap().len(), 0);
 
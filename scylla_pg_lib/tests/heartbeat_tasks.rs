@@ -0,0 +1,87 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use scylla_models::{HeartbeatOutcome, Queue, TaskHeartbeat, TaskStatus};
+
+fn task_heartbeat(rn: &str, lease_epoch: i64) -> TaskHeartbeat {
+    TaskHeartbeat {
+        rn: rn.to_string(),
+        lease_epoch,
+        progress: Some(0.5),
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn heartbeat_all_tasks_of_worker() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.upsert_queue(Queue {
+        name: "bulk".to_string(),
+        lease_timeout_in_secs: Some(600),
+        ..Queue::default()
+    })
+    .await
+    .unwrap();
+    for rn in ["extended", "cancelled", "lost", "completed"] {
+        pgm.insert_task(common::add_task_model(rn, "bulk")).await.unwrap();
+        pgm.lease_task(rn.to_string(), "worker".to_string(), Some(5)).await.unwrap();
+    }
+    pgm.cancel_task("cancelled".to_string(), "client".to_string(), None).await.unwrap();
+    // lease is lost once the task is leased again by another worker
+    pgm.yield_task("lost".to_string(), "worker".to_string(), 1).await.unwrap();
+    pgm.reset_task("lost".to_string()).await.unwrap();
+    pgm.lease_task("lost".to_string(), "worker2".to_string(), None).await.unwrap();
+    pgm.complete_task("completed".to_string(), "worker".to_string(), 1, None, None).await.unwrap();
+
+    let heartbeats = ["extended", "cancelled", "lost", "completed", "missing"]
+        .map(|rn| task_heartbeat(rn, 1))
+        .to_vec();
+    let outcomes = pgm.heartbeat_tasks("worker".to_string(), heartbeats, None).await.unwrap();
+    let rns: Vec<&str> = outcomes.iter().map(|o| o.rn.as_str()).collect();
+    assert_eq!(rns, ["extended", "cancelled", "lost", "completed", "missing"]);
+
+    // lease timeout defaults to the one of the queue
+    let HeartbeatOutcome::Extended { task } = &outcomes[0].outcome else {
+        panic!("lease of the worker is expected to be extended");
+    };
+    assert_eq!(task.progress, 0.5);
+    assert!(task.deadline.unwrap() > Utc::now() + Duration::seconds(500));
+    assert_eq!(task.version, pgm.fetch_task("extended".to_string()).await.unwrap().version);
+
+    let HeartbeatOutcome::Cancelled { task } = &outcomes[1].outcome else {
+        panic!("heartbeat is expected to report the requested cancellation");
+    };
+    assert_eq!(task.status, TaskStatus::Cancelling);
+    assert_eq!(
+        outcomes[2].outcome,
+        HeartbeatOutcome::LeaseLost {
+            new_owner: Some("worker2".to_string()),
+            status: TaskStatus::Running,
+        }
+    );
+    assert_eq!(outcomes[3].outcome, HeartbeatOutcome::Completed);
+    assert_eq!(outcomes[4].outcome, HeartbeatOutcome::NotFound);
+
+    // lease of another worker is left alone
+    let lost = pgm.fetch_task("lost".to_string()).await.unwrap();
+    assert_eq!(lost.progress, 0.0);
+
+    // stale lease epochs are fenced off
+    let outcomes = pgm
+        .heartbeat_tasks("worker".to_string(), vec![task_heartbeat("extended", 0)], Some(5))
+        .await
+        .unwrap();
+    assert_eq!(
+        outcomes[0].outcome,
+        HeartbeatOutcome::LeaseLost {
+            new_owner: Some("worker".to_string()),
+            status: TaskStatus::Running,
+        }
+    );
+    assert!(pgm.fetch_task("extended".to_string()).await.unwrap().deadline.unwrap() > Utc::now() + Duration::seconds(500));
+
+    // truncate table after use
+    common::truncate_table().await;
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use scylla_models::{AddTaskModel, GetTaskModel, LabelSelector};
+use std::collections::BTreeMap;
+
+fn add_task_model(rn: &str, labels: &[(&str, &str)]) -> AddTaskModel {
+    AddTaskModel {
+        labels: labels
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect::<BTreeMap<String, String>>(),
+        ..common::add_task_model(rn, "test")
+    }
+}
+
+fn rns(tasks: &[scylla_models::Task]) -> Vec<String> {
+    let mut rns: Vec<String> = tasks.iter().map(|t| t.rn.clone()).collect();
+    rns.sort();
+    rns
+}
+
+#[tokio::test]
+#[ignore]
+async fn label_selectors_filter_tasks() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.insert_task(add_task_model("eu_gold", &[("region", "eu"), ("tier", "gold")])).await.unwrap();
+    pgm.insert_task(add_task_model("eu_silver", &[("region", "eu"), ("tier", "silver")]))
+        .await
+        .unwrap();
+    pgm.insert_task(add_task_model("us_gold", &[("region", "us"), ("tier", "gold")])).await.unwrap();
+    pgm.insert_task(add_task_model("unlabelled", &[])).await.unwrap();
+
+    let query = |labels: Vec<LabelSelector>| GetTaskModel {
+        queue: Some("test".to_string()),
+        labels,
+        ..GetTaskModel::default()
+    };
+    let tasks = pgm.fetch_tasks(query(Vec::new())).await.unwrap();
+    assert_eq!(tasks.len(), 4);
+    let tasks = pgm
+        .fetch_tasks(query(vec![LabelSelector::Equals("region".to_string(), "eu".to_string())]))
+        .await
+        .unwrap();
+    assert_eq!(rns(&tasks), vec!["eu_gold", "eu_silver"]);
+    let tasks = pgm
+        .fetch_tasks(query(vec![LabelSelector::In("region".to_string(), vec!["eu".to_string(), "us".to_string()])]))
+        .await
+        .unwrap();
+    assert_eq!(rns(&tasks), vec!["eu_gold", "eu_silver", "us_gold"]);
+    let tasks = pgm
+        .fetch_tasks(query(vec![
+            LabelSelector::In("region".to_string(), vec!["eu".to_string(), "us".to_string()]),
+            LabelSelector::Equals("tier".to_string(), "gold".to_string()),
+        ]))
+        .await
+        .unwrap();
+    assert_eq!(rns(&tasks), vec!["eu_gold", "us_gold"]);
+
+    // only matching tasks are leased
+    let leased_tasks = pgm
+        .lease_n_tasks(
+            "test".to_string(),
+            10,
+            "worker".to_string(),
+            None,
+            vec![LabelSelector::In("tier".to_string(), vec!["silver".to_string(), "bronze".to_string()])],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rns(&leased_tasks), vec!["eu_silver"]);
+    let leased_tasks = pgm
+        .lease_n_tasks(
+            "test".to_string(),
+            10,
+            "worker".to_string(),
+            None,
+            vec![LabelSelector::Equals("region".to_string(), "us".to_string())],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rns(&leased_tasks), vec!["us_gold"]);
+    assert_eq!(leased_tasks[0].labels.get("tier"), Some(&"gold".to_string()));
+    let leased_tasks = pgm.lease_n_tasks("test".to_string(), 10, "worker".to_string(), None, Vec::new()).await.unwrap();
+    assert_eq!(rns(&leased_tasks), vec!["eu_gold", "unlabelled"]);
+
+    // truncate table after use
+    common::truncate_table().await;
+}
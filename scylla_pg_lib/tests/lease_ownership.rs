@@ -0,0 +1,94 @@
+mod common;
+
+use scylla_models::{AddTaskModel, HeartbeatOutcome, TaskError, TaskStatus};
+use scylla_operations::error::ScyllaOperationsError;
+use scylla_pg_lib::error::PgAdapterError;
+
+#[tokio::test]
+#[ignore]
+async fn superseded_worker_cannot_finish_task() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    let atm = AddTaskModel {
+        rn: "superseded".to_string(),
+        queue: "test".to_string(),
+        priority: 1,
+        spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
+    };
+    pgm.insert_task(atm).await.unwrap();
+    pgm.lease_task("superseded".to_string(), "worker1".to_string(), Some(0)).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    // worker1 is suspected of failure and task is handed over to worker2
+    pgm.reset_batch().await.unwrap();
+    pgm.lease_task("superseded".to_string(), "worker2".to_string(), None).await.unwrap();
+
+    let not_owned = |e: PgAdapterError| matches!(e, PgAdapterError::ScyllaOpsError(ScyllaOperationsError::LeaseNotOwned(..)));
+    assert!(not_owned(
+        pgm.complete_task("superseded".to_string(), "worker1".to_string(), 1, None, None)
+            .await
+            .unwrap_err()
+    ));
+    assert!(not_owned(pgm.yield_task("superseded".to_string(), "worker1".to_string(), 1).await.unwrap_err()));
+    let task_error = TaskError {
+        code: "101".to_string(),
+        description: "failed".to_string(),
+        args: serde_json::Value::default(),
+    };
+    assert!(not_owned(
+        pgm.abort_task("superseded".to_string(), "worker1".to_string(), 1, task_error)
+            .await
+            .unwrap_err()
+    ));
+
+    let task = pgm.complete_task("superseded".to_string(), "worker2".to_string(), 2, None, None).await.unwrap();
+    assert_eq!(task.status, TaskStatus::Completed);
+    assert_eq!(task.owner, Some("worker2".to_string()));
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn stale_lease_epoch_is_rejected() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    let atm = AddTaskModel {
+        rn: "fenced".to_string(),
+        queue: "test".to_string(),
+        priority: 1,
+        spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
+    };
+    pgm.insert_task(atm).await.unwrap();
+    let first_lease = pgm
+        .lease_n_tasks("test".to_string(), 1, "worker1".to_string(), Some(0), Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(first_lease[0].lease_epoch, 1);
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    pgm.reset_batch().await.unwrap();
+    // same worker leases the task again
+    let second_lease = pgm.lease_task("fenced".to_string(), "worker1".to_string(), None).await.unwrap();
+    assert_eq!(second_lease.lease_epoch, 2);
+
+    let stale = |e: PgAdapterError| matches!(e, PgAdapterError::ScyllaOpsError(ScyllaOperationsError::StaleLease(1, 2)));
+    assert_eq!(
+        pgm.heartbeat_task("fenced".to_string(), "worker1".to_string(), 1, None, None).await.unwrap(),
+        HeartbeatOutcome::LeaseLost {
+            new_owner: Some("worker1".to_string()),
+            status: TaskStatus::Running,
+        }
+    );
+    assert!(stale(
+        pgm.complete_task("fenced".to_string(), "worker1".to_string(), 1, None, None).await.unwrap_err()
+    ));
+
+    let task = pgm.complete_task("fenced".to_string(), "worker1".to_string(), 2, None, None).await.unwrap();
+    assert_eq!(task.status, TaskStatus::Completed);
+    assert_eq!(task.lease_epoch, 2);
+    // truncate table after use
+    common::truncate_table().await;
+}
@@ -1,5 +1,6 @@
 mod common;
 
+use chrono::{Duration, Utc};
 use scylla_models::{AddTaskModel, TaskHistoryType, TaskStatus};
 
 #[tokio::test]
@@ -13,6 +14,7 @@ async fn lease_task() {
         queue: "test".to_string(),
         priority: 1,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
 
     pgm.insert_task(atm).await.unwrap();
@@ -33,31 +35,35 @@ async fn lease_n_tasks() {
         queue: "test".to_string(),
         priority: 100,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
     let atm2 = AddTaskModel {
         rn: "lease_fail2".to_string(),
         queue: "test".to_string(),
         priority: 10,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
     let atm3 = AddTaskModel {
         rn: "lease_success3".to_string(),
         queue: "test".to_string(),
         priority: 50,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
     let atm4 = AddTaskModel {
         rn: "lease_fail4".to_string(),
         queue: "testing".to_string(), // different queue
         priority: 120,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
 
     pgm.insert_task(atm1).await.unwrap();
     pgm.insert_task(atm2).await.unwrap();
     pgm.insert_task(atm3).await.unwrap();
     pgm.insert_task(atm4).await.unwrap();
-    let leased_tasks = pgm.lease_n_tasks("test".to_string(), 2, "worker".to_string(), None).await.unwrap();
+    let leased_tasks = pgm.lease_n_tasks("test".to_string(), 2, "worker".to_string(), None, Vec::new()).await.unwrap();
     assert_eq!(leased_tasks.len(), 2);
     assert_ne!(leased_tasks.iter().position(|t| t.rn == *"lease_success1"), None);
     assert_ne!(leased_tasks.iter().position(|t| t.rn == *"lease_success3"), None);
@@ -72,3 +78,37 @@ async fn lease_n_tasks() {
     // truncate table after use
     common::truncate_table().await;
 }
+
+#[tokio::test]
+#[ignore]
+async fn lease_n_tasks_skips_tasks_not_yet_due() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    let atm_due = AddTaskModel {
+        rn: "lease_due".to_string(),
+        queue: "test".to_string(),
+        priority: 10,
+        spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        not_before: Some(Utc::now() - Duration::seconds(1)),
+        ..AddTaskModel::default()
+    };
+    let atm_not_due = AddTaskModel {
+        rn: "lease_not_due".to_string(),
+        queue: "test".to_string(),
+        priority: 100,
+        spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        not_before: Some(Utc::now() + Duration::minutes(15)),
+        ..AddTaskModel::default()
+    };
+
+    pgm.insert_task(atm_due).await.unwrap();
+    pgm.insert_task(atm_not_due).await.unwrap();
+    let leased_tasks = pgm.lease_n_tasks("test".to_string(), 2, "worker".to_string(), None, Vec::new()).await.unwrap();
+    assert_eq!(leased_tasks.len(), 1);
+    assert_eq!(leased_tasks[0].rn, "lease_due".to_string());
+    // single lease is refused as well
+    assert!(pgm.lease_task("lease_not_due".to_string(), "worker".to_string(), None).await.is_err());
+    // truncate table after use
+    common::truncate_table().await;
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use scylla_models::{AddTaskModel, GetTaskModel, PriorityAging, Queue};
+use scylla_pg_core::config;
+use scylla_pg_core::connection::get_client;
+
+/// Pretends the task has been waiting for `secs` seconds.
+async fn backdate_task(rn: &str, secs: i32) {
+    let conf = config::PGConfig::from_env().unwrap();
+    let client = get_client(&(conf.to_pg_config())).await.unwrap();
+    let backdate_sql = "UPDATE task SET data = jsonb_set(data, '{created}', \
+        to_jsonb(to_char(timezone('UTC'::text, now() - $2::int * interval '1 second'), 'YYYY-MM-DD HH24:MI:SS.MSZ'))) WHERE data ->> 'rn' = $1";
+    client.execute(backdate_sql, &[&rn, &secs]).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore]
+async fn lease_n_tasks_ages_waiting_tasks() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.upsert_queue(Queue {
+        name: "test".to_string(),
+        max_attempts: None,
+        priority_aging: Some(PriorityAging {
+            seconds_per_point: 10,
+            max_boost: 50,
+        }),
+        ..Queue::default()
+    })
+    .await
+    .unwrap();
+    let atm_old = AddTaskModel {
+        rn: "aged_low".to_string(),
+        queue: "test".to_string(),
+        priority: 10,
+        spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
+    };
+    let atm_new = AddTaskModel {
+        rn: "fresh_high".to_string(),
+        queue: "test".to_string(),
+        priority: 40,
+        spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
+    };
+    pgm.insert_task(atm_old).await.unwrap();
+    pgm.insert_task(atm_new).await.unwrap();
+    // waiting for an hour is worth far more than the maximum boost
+    backdate_task("aged_low", 3600).await;
+
+    let tasks = pgm
+        .fetch_tasks(GetTaskModel {
+            queue: Some("test".to_string()),
+            ..GetTaskModel::default()
+        })
+        .await
+        .unwrap();
+    let aged = tasks.iter().find(|t| t.rn == "aged_low").unwrap();
+    assert_eq!(aged.effective_priority, Some(60));
+    assert_eq!(aged.priority, 10);
+
+    let leased_tasks = pgm.lease_n_tasks("test".to_string(), 1, "worker".to_string(), None, Vec::new()).await.unwrap();
+    assert_eq!(leased_tasks.len(), 1);
+    assert_eq!(leased_tasks[0].rn, "aged_low".to_string());
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn lease_n_tasks_without_aging_uses_priority() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    let atm_old = AddTaskModel {
+        rn: "old_low".to_string(),
+        queue: "test".to_string(),
+        priority: 10,
+        spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
+    };
+    let atm_new = AddTaskModel {
+        rn: "fresh_high".to_string(),
+        queue: "test".to_string(),
+        priority: 40,
+        spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
+    };
+    pgm.insert_task(atm_old).await.unwrap();
+    pgm.insert_task(atm_new).await.unwrap();
+    backdate_task("old_low", 3600).await;
+
+    let leased_tasks = pgm.lease_n_tasks("test".to_string(), 1, "worker".to_string(), None, Vec::new()).await.unwrap();
+    assert_eq!(leased_tasks[0].rn, "fresh_high".to_string());
+    // truncate table after use
+    common::truncate_table().await;
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use scylla_models::{AddTaskModel, Queue};
+use scylla_pg_lib::error::PgAdapterError;
+
+#[tokio::test]
+#[ignore]
+async fn lease_timeout_defaults_to_queue() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.upsert_queue(Queue {
+        name: "slow".to_string(),
+        lease_timeout_in_secs: Some(600),
+        description: Some("long running tasks".to_string()),
+        ..Queue::default()
+    })
+    .await
+    .unwrap();
+    for rn in ["1", "2", "3"] {
+        pgm.insert_task(common::add_task_model(rn, "slow")).await.unwrap();
+    }
+    pgm.insert_task(common::add_task_model("4", "fast")).await.unwrap();
+
+    let leased = pgm.lease_task("1".to_string(), "worker".to_string(), None).await.unwrap();
+    assert!(leased.deadline.unwrap() > Utc::now() + Duration::seconds(590));
+    let leased = pgm.lease_n_tasks("slow".to_string(), 1, "worker".to_string(), None, Vec::new()).await.unwrap();
+    assert!(leased[0].deadline.unwrap() > Utc::now() + Duration::seconds(590));
+    let leased = pgm
+        .lease_n_tasks("slow".to_string(), 1, "worker".to_string(), Some(5), Vec::new())
+        .await
+        .unwrap();
+    assert!(leased[0].deadline.unwrap() < Utc::now() + Duration::seconds(10));
+    // queue without a lease timeout of its own
+    let leased = pgm.lease_n_tasks("fast".to_string(), 1, "worker".to_string(), None, Vec::new()).await.unwrap();
+    assert!(leased[0].deadline.unwrap() < Utc::now() + Duration::seconds(15));
+
+    let heartbeat = pgm.heartbeat_task("4".to_string(), "worker".to_string(), 1, None, None).await.unwrap();
+    assert!(matches!(heartbeat, scylla_models::HeartbeatOutcome::Extended { .. }));
+    assert_eq!(
+        pgm.fetch_queue("slow".to_string()).await.unwrap().description,
+        Some("long running tasks".to_string())
+    );
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn retention_period_defaults_to_queue() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.upsert_queue(Queue {
+        name: "short_lived".to_string(),
+        retention_period_in_secs: Some(0),
+        ..Queue::default()
+    })
+    .await
+    .unwrap();
+    pgm.insert_task(common::add_task_model("1", "short_lived")).await.unwrap();
+    pgm.insert_task(common::add_task_model("2", "other")).await.unwrap();
+    pgm.cancel_task("1".to_string(), "client".to_string(), None).await.unwrap();
+    pgm.cancel_task("2".to_string(), "client".to_string(), None).await.unwrap();
+
+    assert_eq!(pgm.delete_terminated_tasks(3600).await.unwrap(), 1);
+    assert!(pgm.fetch_task("1".to_string()).await.is_err());
+    pgm.fetch_task("2".to_string()).await.unwrap();
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn queue_limits_are_enforced() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await.require_registered_queues(true);
+    assert!(matches!(
+        pgm.insert_task(common::add_task_model("1", "registered")).await,
+        Err(PgAdapterError::NoQueueFound(queue)) if queue == "registered"
+    ));
+    pgm.upsert_queue(Queue {
+        name: "registered".to_string(),
+        max_priority: Some(10),
+        ..Queue::default()
+    })
+    .await
+    .unwrap();
+    pgm.insert_task(common::add_task_model("1", "registered")).await.unwrap();
+    assert!(pgm
+        .insert_task(AddTaskModel {
+            priority: 11,
+            ..common::add_task_model("2", "registered")
+        })
+        .await
+        .is_err());
+    assert!(pgm.reprioritise_task("1".to_string(), 11, "operator".to_string()).await.is_err());
+
+    // truncate table after use
+    common::truncate_table().await;
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use scylla_models::{AddTaskModel, QueueMode, ResubmitOverrides};
+use scylla_pg_lib::error::PgAdapterError;
+
+#[tokio::test]
+#[ignore]
+async fn paused_queue_is_not_leased() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    assert_eq!(pgm.fetch_queue_state("paused".to_string()).await.unwrap().mode, QueueMode::Active);
+    pgm.insert_task(common::add_task_model("1", "paused")).await.unwrap();
+    pgm.insert_task(common::add_task_model("2", "paused")).await.unwrap();
+    pgm.pause_queue("paused".to_string()).await.unwrap();
+    assert_eq!(pgm.fetch_queue_state("paused".to_string()).await.unwrap().mode, QueueMode::Paused);
+
+    // backlog is kept and new tasks are still accepted
+    pgm.insert_task(common::add_task_model("3", "paused")).await.unwrap();
+    let leased = pgm
+        .lease_n_tasks("paused".to_string(), 10, "worker".to_string(), None, Vec::new())
+        .await
+        .unwrap();
+    assert!(leased.is_empty());
+    let leased = pgm.lease_n_tasks("%".to_string(), 10, "worker".to_string(), None, Vec::new()).await.unwrap();
+    assert!(leased.is_empty());
+    assert!(matches!(
+        pgm.lease_task("1".to_string(), "worker".to_string(), None).await,
+        Err(PgAdapterError::QueuePaused(queue)) if queue == "paused"
+    ));
+
+    pgm.resume_queue("paused".to_string()).await.unwrap();
+    let leased = pgm
+        .lease_n_tasks("paused".to_string(), 10, "worker".to_string(), None, Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(leased.len(), 3);
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn draining_queue_does_not_accept_tasks() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.insert_task(common::add_task_model("1", "draining")).await.unwrap();
+    pgm.drain_queue("draining".to_string()).await.unwrap();
+
+    assert!(matches!(
+        pgm.insert_task(common::add_task_model("2", "draining")).await,
+        Err(PgAdapterError::QueueDraining(queue)) if queue == "draining"
+    ));
+    // task which exists already is still reported as duplicate
+    assert!(matches!(
+        pgm.insert_task(common::add_task_model("1", "other")).await,
+        Err(PgAdapterError::DuplicateTask(rn)) if rn == "1"
+    ));
+    assert!(matches!(
+        pgm.batch_insert_tasks(vec![common::add_task_model("3", "other"), common::add_task_model("4", "draining")]).await,
+        Err(PgAdapterError::QueueDraining(queue)) if queue == "draining"
+    ));
+    assert!(pgm.fetch_task("3".to_string()).await.is_err());
+
+    // backlog is still leased
+    let leased = pgm.lease_task("1".to_string(), "worker".to_string(), None).await.unwrap();
+    assert_eq!(leased.owner, Some("worker".to_string()));
+
+    pgm.resume_queue("draining".to_string()).await.unwrap();
+    pgm.insert_task(common::add_task_model("2", "draining")).await.unwrap();
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn draining_queue_does_not_accept_superseding_tasks() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    let superseding = |rn: &str, key: &str| AddTaskModel {
+        supersession_key: Some(key.to_string()),
+        ..common::add_task_model(rn, "draining")
+    };
+    pgm.supersede_task(superseding("1", "account:42"), false).await.unwrap();
+    pgm.drain_queue("draining".to_string()).await.unwrap();
+
+    // ready task of the backlog is still replaced
+    let replaced = pgm.supersede_task(superseding("2", "account:42"), false).await.unwrap();
+    assert_eq!(replaced.rn, "1".to_string());
+    assert!(matches!(
+        pgm.supersede_task(superseding("3", "account:43"), false).await,
+        Err(PgAdapterError::QueueDraining(queue)) if queue == "draining"
+    ));
+    assert!(pgm.fetch_task("3".to_string()).await.is_err());
+
+    pgm.resume_queue("draining".to_string()).await.unwrap();
+    pgm.supersede_task(superseding("3", "account:43"), false).await.unwrap();
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn draining_queue_does_not_accept_resubmitted_tasks() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.insert_task(common::add_task_model("1", "draining")).await.unwrap();
+    pgm.cancel_task("1".to_string(), "client".to_string(), None).await.unwrap();
+    pgm.drain_queue("draining".to_string()).await.unwrap();
+
+    assert!(matches!(
+        pgm.resubmit_task("1".to_string(), "2".to_string(), ResubmitOverrides::default()).await,
+        Err(PgAdapterError::QueueDraining(queue)) if queue == "draining"
+    ));
+    assert!(pgm.fetch_task("2".to_string()).await.is_err());
+    assert_eq!(pgm.fetch_task("1".to_string()).await.unwrap().resubmitted_as, None);
+    // clone may still be resubmitted into a queue which is not draining
+    let overrides = ResubmitOverrides {
+        queue: Some("other".to_string()),
+        ..ResubmitOverrides::default()
+    };
+    let resubmitted = pgm.resubmit_task("1".to_string(), "2".to_string(), overrides).await.unwrap();
+    assert_eq!(resubmitted.queue, "other".to_string());
+
+    // truncate table after use
+    common::truncate_table().await;
+}
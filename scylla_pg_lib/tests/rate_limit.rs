@@ -0,0 +1,94 @@
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use scylla_models::{Queue, RateLimit};
+use scylla_pg_lib::error::PgAdapterError;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+#[ignore]
+async fn concurrent_leases_share_rate_limit() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = Arc::new(common::get_pg_manager().await);
+    pgm.upsert_queue(Queue {
+        name: "partner".to_string(),
+        rate_limit: Some(RateLimit {
+            max_starts: 3,
+            interval_in_secs: 3600,
+        }),
+        ..Queue::default()
+    })
+    .await
+    .unwrap();
+    for i in 0..10 {
+        pgm.insert_task(common::add_task_model(&i.to_string(), "partner")).await.unwrap();
+    }
+    assert_eq!(pgm.fetch_rate_limit_budget("partner".to_string()).await.unwrap().remaining, 3);
+
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let pgm = Arc::clone(&pgm);
+            tokio::spawn(async move { pgm.lease_n_tasks("partner".to_string(), 2, format!("worker{i}"), None, Vec::new()).await })
+        })
+        .collect();
+    let mut leased = 0;
+    for handle in handles {
+        leased += handle.await.unwrap().unwrap().len();
+    }
+    assert_eq!(leased, 3);
+    assert!(matches!(
+        pgm.lease_task("9".to_string(), "worker".to_string(), None).await,
+        Err(PgAdapterError::RateLimited(queue)) if queue == "partner"
+    ));
+
+    let budget = pgm.fetch_rate_limit_budget("partner".to_string()).await.unwrap();
+    assert_eq!(budget.remaining, 0);
+    // a token is refilled every 1200 seconds
+    assert!(budget.available_at > Utc::now() + Duration::seconds(1100));
+    assert!(budget.available_at < Utc::now() + Duration::seconds(1300));
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn rate_limit_is_refilled() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.upsert_queue(Queue {
+        name: "partner".to_string(),
+        rate_limit: Some(RateLimit {
+            max_starts: 4,
+            interval_in_secs: 1,
+        }),
+        ..Queue::default()
+    })
+    .await
+    .unwrap();
+    for i in 0..10 {
+        pgm.insert_task(common::add_task_model(&i.to_string(), "partner")).await.unwrap();
+    }
+    let leased = pgm
+        .lease_n_tasks("partner".to_string(), 10, "worker".to_string(), None, Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(leased.len(), 4);
+
+    tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+    assert!(pgm.fetch_rate_limit_budget("partner".to_string()).await.unwrap().remaining >= 2);
+    let leased = pgm.lease_task("9".to_string(), "worker".to_string(), None).await.unwrap();
+    assert_eq!(leased.rn, "9".to_string());
+
+    // queues without a rate limit have no budget
+    assert!(matches!(
+        pgm.fetch_rate_limit_budget("other".to_string()).await,
+        Err(PgAdapterError::NoRateLimitFound(queue)) if queue == "other"
+    ));
+
+    // truncate table after use
+    common::truncate_table().await;
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use scylla_models::{AddTaskModel, TaskHistoryType, TaskStatus};
+
+#[tokio::test]
+#[ignore]
+async fn yield_all_tasks_of_worker() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    for rn in ["1", "2", "other"] {
+        pgm.insert_task(common::add_task_model(rn, "release")).await.unwrap();
+    }
+    pgm.lease_task("1".to_string(), "worker".to_string(), Some(600)).await.unwrap();
+    pgm.lease_task("2".to_string(), "worker".to_string(), Some(600)).await.unwrap();
+    pgm.lease_task("other".to_string(), "worker2".to_string(), Some(600)).await.unwrap();
+    pgm.heartbeat_task("1".to_string(), "worker".to_string(), 1, Some(0.5), Some(600))
+        .await
+        .unwrap();
+
+    let mut yielded = pgm.yield_worker_tasks("worker".to_string()).await.unwrap();
+    yielded.sort_by(|a, b| a.rn.cmp(&b.rn));
+    assert_eq!(yielded.len(), 2);
+    let history = yielded[0].history.last().unwrap();
+    assert_eq!(history.typ, TaskHistoryType::Yield);
+    assert_eq!(history.worker, "worker".to_string());
+    assert_eq!(history.progress, Some(0.5));
+
+    // yielded tasks are reset on the next poll of the monitor
+    let mut reset: Vec<String> = pgm.reset_batch().await.unwrap().into_iter().map(|t| t.rn).collect();
+    reset.sort();
+    assert_eq!(reset, ["1".to_string(), "2".to_string()]);
+    let task = pgm.fetch_task("1".to_string()).await.unwrap();
+    assert_eq!(task.status, TaskStatus::Ready);
+    assert_eq!(pgm.fetch_task("other".to_string()).await.unwrap().status, TaskStatus::Running);
+    assert!(pgm.yield_worker_tasks("worker".to_string()).await.unwrap().is_empty());
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn reset_all_tasks_of_dead_worker() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    for rn in ["1", "other"] {
+        pgm.insert_task(common::add_task_model(rn, "release")).await.unwrap();
+    }
+    pgm.insert_task(AddTaskModel {
+        max_attempts: Some(1),
+        ..common::add_task_model("last_attempt", "release")
+    })
+    .await
+    .unwrap();
+    pgm.lease_task("1".to_string(), "dead".to_string(), Some(600)).await.unwrap();
+    pgm.lease_task("last_attempt".to_string(), "dead".to_string(), Some(600)).await.unwrap();
+    pgm.lease_task("other".to_string(), "alive".to_string(), Some(600)).await.unwrap();
+
+    let mut reset = pgm.reset_worker_tasks("dead".to_string(), "operator".to_string()).await.unwrap();
+    reset.sort_by(|a, b| a.rn.cmp(&b.rn));
+    assert_eq!(reset.len(), 2);
+    assert_eq!(reset[0].status, TaskStatus::Ready);
+    assert_eq!(reset[0].owner, None);
+    let history = reset[0].history.last().unwrap();
+    assert_eq!(history.typ, TaskHistoryType::Timeout);
+    assert_eq!(history.worker, "dead".to_string());
+    assert_eq!(history.actor, Some("operator".to_string()));
+    assert_eq!(history.reason, Some("worker_reset".to_string()));
+    // attempts are used up as on a timeout
+    assert_eq!(reset[1].status, TaskStatus::Aborted);
+    assert_eq!(reset[1].history.last().unwrap().actor, Some("operator".to_string()));
+
+    assert_eq!(pgm.fetch_task("other".to_string()).await.unwrap().status, TaskStatus::Running);
+    let leased = pgm.lease_task("1".to_string(), "alive".to_string(), None).await.unwrap();
+    assert_eq!(leased.lease_epoch, 2);
+
+    // truncate table after use
+    common::truncate_table().await;
+}
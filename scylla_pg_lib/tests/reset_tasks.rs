@@ -1,6 +1,6 @@
 mod common;
 
-use scylla_models::{AddTaskModel, TaskHistoryType, TaskStatus};
+use scylla_models::{AddTaskModel, Queue, TaskHistoryType, TaskStatus};
 
 #[tokio::test]
 #[ignore]
@@ -13,31 +13,38 @@ async fn reset_batch_tasks() {
         queue: "test".to_string(),
         priority: 100,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
     let atm2 = AddTaskModel {
         rn: "lease_fail2".to_string(),
         queue: "test".to_string(),
         priority: 10,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
     let atm3 = AddTaskModel {
         rn: "lease_success3".to_string(),
         queue: "test".to_string(),
         priority: 50,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
     let atm4 = AddTaskModel {
         rn: "lease_fail4".to_string(),
         queue: "testing".to_string(), // different queue
         priority: 120,
         spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
     };
 
     pgm.insert_task(atm1).await.unwrap();
     pgm.insert_task(atm2).await.unwrap();
     pgm.insert_task(atm3).await.unwrap();
     pgm.insert_task(atm4).await.unwrap();
-    let _ = pgm.lease_n_tasks("test".to_string(), 2, "worker".to_string(), Some(-1)).await.unwrap();
+    let _ = pgm
+        .lease_n_tasks("test".to_string(), 2, "worker".to_string(), Some(-1), Vec::new())
+        .await
+        .unwrap();
     let reset_tasks = pgm.reset_batch().await.unwrap();
     assert_eq!(reset_tasks.len(), 2);
     assert_ne!(reset_tasks.iter().position(|t| t.rn == *"lease_success1"), None);
@@ -60,3 +67,120 @@ async fn reset_batch_tasks() {
     // truncate table after use
     common::truncate_table().await;
 }
+
+#[tokio::test]
+#[ignore]
+async fn reset_batch_aborts_tasks_with_exhausted_attempts() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.upsert_queue(Queue {
+        name: "test".to_string(),
+        max_attempts: Some(1),
+        ..Queue::default()
+    })
+    .await
+    .unwrap();
+    let atm1 = AddTaskModel {
+        rn: "queue_default".to_string(),
+        queue: "test".to_string(),
+        priority: 100,
+        spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
+    };
+    let atm2 = AddTaskModel {
+        rn: "task_override".to_string(),
+        queue: "test".to_string(),
+        priority: 10,
+        spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        max_attempts: Some(2),
+        ..AddTaskModel::default()
+    };
+
+    assert_eq!(pgm.insert_task(atm1).await.unwrap().max_attempts, Some(1));
+    assert_eq!(pgm.insert_task(atm2).await.unwrap().max_attempts, Some(2));
+    let leased_tasks = pgm
+        .lease_n_tasks("test".to_string(), 2, "worker".to_string(), Some(-1), Vec::new())
+        .await
+        .unwrap();
+    assert!(leased_tasks.iter().all(|t| t.attempts == 1));
+    let reset_tasks = pgm.reset_batch().await.unwrap();
+    assert_eq!(reset_tasks.len(), 2);
+    let aborted = reset_tasks.iter().find(|t| t.rn == *"queue_default").unwrap();
+    assert_eq!(aborted.status, TaskStatus::Aborted);
+    assert_eq!(aborted.errors[0].code, "max_attempts_exceeded".to_string());
+    assert_eq!(aborted.errors[0].args, serde_json::json!({ "attempts": 1, "maxAttempts": 1 }));
+    let history_types: Vec<TaskHistoryType> = aborted.history.iter().map(|h| h.typ.clone()).collect();
+    assert_eq!(history_types[history_types.len() - 2..], [TaskHistoryType::Timeout, TaskHistoryType::Abortion]);
+    let retried = reset_tasks.iter().find(|t| t.rn == *"task_override").unwrap();
+    assert_eq!(retried.status, TaskStatus::Ready);
+    assert!(retried.errors.is_empty());
+
+    // second attempt is the last one
+    let leased_tasks = pgm
+        .lease_n_tasks("test".to_string(), 2, "worker".to_string(), Some(-1), Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(leased_tasks.len(), 1);
+    assert_eq!(leased_tasks[0].attempts, 2);
+    let reset_tasks = pgm.reset_batch().await.unwrap();
+    assert_eq!(reset_tasks[0].status, TaskStatus::Aborted);
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn yield_on_last_attempt_is_not_counted() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    for rn in ["batch", "single"] {
+        pgm.insert_task(AddTaskModel {
+            rn: rn.to_string(),
+            queue: "yield".to_string(),
+            priority: 10,
+            spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+            max_attempts: Some(1),
+            ..AddTaskModel::default()
+        })
+        .await
+        .unwrap();
+    }
+    let leased = pgm.lease_n_tasks("yield".to_string(), 2, "worker".to_string(), None, Vec::new()).await.unwrap();
+    assert!(leased.iter().all(|t| t.attempts == 1));
+    pgm.yield_task("batch".to_string(), "worker".to_string(), 1).await.unwrap();
+    pgm.yield_task("single".to_string(), "worker".to_string(), 1).await.unwrap();
+
+    // reset by the monitor
+    let reset_tasks = pgm.reset_batch().await.unwrap();
+    assert_eq!(reset_tasks.len(), 2);
+    for task in reset_tasks {
+        assert_eq!(task.status, TaskStatus::Ready);
+        assert!(task.errors.is_empty());
+        assert_eq!(task.history.last().unwrap().typ, TaskHistoryType::Yield);
+    }
+    // the yielded attempt is not counted on the next lease
+    let leased = pgm.lease_n_tasks("yield".to_string(), 1, "worker".to_string(), None, Vec::new()).await.unwrap();
+    assert_eq!(leased[0].attempts, 1);
+    let single = pgm.lease_task("single".to_string(), "worker".to_string(), None).await.unwrap();
+    assert_eq!(single.attempts, 1);
+
+    // reset of a single task
+    pgm.yield_task("single".to_string(), "worker".to_string(), 2).await.unwrap();
+    let task = pgm.reset_task("single".to_string()).await.unwrap();
+    assert_eq!(task.status, TaskStatus::Ready);
+    assert!(task.errors.is_empty());
+    assert_eq!(task.history.last().unwrap().typ, TaskHistoryType::Yield);
+
+    // timing out on the last attempt still aborts the task
+    let single = pgm.lease_task("single".to_string(), "worker".to_string(), Some(-1)).await.unwrap();
+    assert_eq!(single.attempts, 1);
+    let reset_tasks = pgm.reset_batch().await.unwrap();
+    let aborted = reset_tasks.iter().find(|t| t.rn == "single").unwrap();
+    assert_eq!(aborted.status, TaskStatus::Aborted);
+
+    // truncate table after use
+    common::truncate_table().await;
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use scylla_models::{AddTaskModel, DependencyFailurePolicy, TaskStatus};
+use scylla_pg_lib::error::PgAdapterError;
+
+fn add_task_model(rn: &str, depends_on: Vec<String>) -> AddTaskModel {
+    AddTaskModel {
+        depends_on,
+        ..common::add_task_model(rn, "test")
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn resolve_blocked_batch_tasks() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.insert_task(add_task_model("parent1", vec![])).await.unwrap();
+    pgm.insert_task(add_task_model("parent2", vec![])).await.unwrap();
+    pgm.insert_task(add_task_model("parent3", vec![])).await.unwrap();
+    let child = pgm
+        .insert_task(add_task_model("child", vec!["parent1".to_string(), "parent2".to_string()]))
+        .await
+        .unwrap();
+    assert_eq!(child.status, TaskStatus::Blocked);
+    pgm.insert_task(AddTaskModel {
+        on_dependency_failure: DependencyFailurePolicy::Cancel,
+        ..add_task_model("cancelled_child", vec!["parent3".to_string()])
+    })
+    .await
+    .unwrap();
+    pgm.insert_task(add_task_model("aborted_child", vec!["parent3".to_string()])).await.unwrap();
+
+    // blocked tasks are not leased
+    let leased_tasks = pgm.lease_n_tasks("test".to_string(), 10, "worker".to_string(), None, Vec::new()).await.unwrap();
+    assert_eq!(leased_tasks.len(), 3);
+    assert!(leased_tasks.iter().all(|t| t.rn.starts_with("parent")));
+
+    pgm.complete_task("parent1".to_string(), "worker".to_string(), 1, None, None).await.unwrap();
+    pgm.cancel_task("parent3".to_string(), "client".to_string(), None).await.unwrap();
+    pgm.acknowledge_cancellation("parent3".to_string(), "worker".to_string(), 1).await.unwrap();
+    let resolved_tasks = pgm.resolve_blocked_batch().await.unwrap();
+    assert_eq!(resolved_tasks.len(), 2);
+    let cancelled_child = resolved_tasks.iter().find(|t| t.rn == *"cancelled_child").unwrap();
+    assert_eq!(cancelled_child.status, TaskStatus::Cancelled);
+    assert_eq!(cancelled_child.errors[0].code, "dependency_failed".to_string());
+    assert_eq!(cancelled_child.errors[0].args, serde_json::json!({ "rn": "parent3", "status": "cancelled" }));
+    let aborted_child = resolved_tasks.iter().find(|t| t.rn == *"aborted_child").unwrap();
+    assert_eq!(aborted_child.status, TaskStatus::Aborted);
+
+    // child waits for all of its parents
+    assert_eq!(pgm.fetch_task("child".to_string()).await.unwrap().status, TaskStatus::Blocked);
+    pgm.complete_task("parent2".to_string(), "worker".to_string(), 1, None, None).await.unwrap();
+    let resolved_tasks = pgm.resolve_blocked_batch().await.unwrap();
+    assert_eq!(resolved_tasks.len(), 1);
+    assert_eq!(resolved_tasks[0].rn, "child".to_string());
+    assert_eq!(resolved_tasks[0].status, TaskStatus::Ready);
+    assert!(resolved_tasks[0].errors.is_empty());
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn insert_with_missing_dependency_fails() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.insert_task(add_task_model("parent", vec![])).await.unwrap();
+
+    // task depending on itself or on a task which does not exist is not inserted
+    let result = pgm.insert_task(add_task_model("self", vec!["self".to_string()])).await;
+    assert!(matches!(result, Err(PgAdapterError::ScyllaOpsError(_))));
+    let result = pgm
+        .insert_task(add_task_model("orphan", vec!["parent".to_string(), "missing".to_string()]))
+        .await;
+    assert!(matches!(result, Err(PgAdapterError::NoTaskFound(rn)) if rn == "missing"));
+    let result = pgm
+        .batch_insert_tasks(vec![add_task_model("orphan", vec!["missing".to_string()]), add_task_model("other", vec![])])
+        .await;
+    assert!(matches!(result, Err(PgAdapterError::NoTaskFound(rn)) if rn == "missing"));
+    assert!(matches!(pgm.fetch_task("orphan".to_string()).await, Err(PgAdapterError::NoTaskFound(_))));
+    assert!(matches!(pgm.fetch_task("other".to_string()).await, Err(PgAdapterError::NoTaskFound(_))));
+
+    // task depended on may be inserted in the same batch
+    let batch = pgm
+        .batch_insert_tasks(vec![
+            add_task_model("child", vec!["new_parent".to_string()]),
+            add_task_model("new_parent", vec![]),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(batch.inserted.len(), 2);
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn dependency_cycles_are_rejected() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+
+    // tasks of the batch depending on each other would stay blocked
+    let result = pgm
+        .batch_insert_tasks(vec![add_task_model("a", vec!["b".to_string()]), add_task_model("b", vec!["a".to_string()])])
+        .await;
+    assert!(matches!(result, Err(PgAdapterError::ScyllaOpsError(_))));
+    assert!(matches!(pgm.fetch_task("a".to_string()).await, Err(PgAdapterError::NoTaskFound(_))));
+
+    // so would a deleted task added again depending on a task which still depends on it
+    pgm.insert_task(add_task_model("parent", vec![])).await.unwrap();
+    pgm.insert_task(add_task_model("child", vec!["parent".to_string()])).await.unwrap();
+    pgm.cancel_task("parent".to_string(), "client".to_string(), None).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    assert_eq!(pgm.delete_terminated_tasks(0).await.unwrap(), 1);
+    let result = pgm.insert_task(add_task_model("parent", vec!["child".to_string()])).await;
+    assert!(matches!(result, Err(PgAdapterError::ScyllaOpsError(_))));
+    let result = pgm
+        .batch_insert_tasks(vec![
+            add_task_model("parent", vec!["grandchild".to_string()]),
+            add_task_model("grandchild", vec!["child".to_string()]),
+        ])
+        .await;
+    assert!(matches!(result, Err(PgAdapterError::ScyllaOpsError(_))));
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn deleted_parent_fails_blocked_task() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.insert_task(add_task_model("parent", vec![])).await.unwrap();
+    pgm.insert_task(add_task_model("child", vec!["parent".to_string()])).await.unwrap();
+    let leased = pgm.lease_task("parent".to_string(), "worker".to_string(), None).await.unwrap();
+    pgm.complete_task("parent".to_string(), "worker".to_string(), leased.lease_epoch, None, None)
+        .await
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    // parent is deleted before monitor resolves the child
+    assert_eq!(pgm.delete_terminated_tasks(0).await.unwrap(), 1);
+
+    let resolved_tasks = pgm.resolve_blocked_batch().await.unwrap();
+    assert_eq!(resolved_tasks.len(), 1);
+    assert_eq!(resolved_tasks[0].status, TaskStatus::Aborted);
+    assert_eq!(resolved_tasks[0].errors[0].args, serde_json::json!({ "rn": "parent", "status": null }));
+
+    // truncate table after use
+    common::truncate_table().await;
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use scylla_models::{AddTaskModel, ResubmitOverrides, TaskError, TaskStatus};
+use scylla_pg_lib::error::PgAdapterError;
+
+#[tokio::test]
+#[ignore]
+async fn resubmit_links_tasks() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.insert_task(AddTaskModel {
+        rn: "first".to_string(),
+        queue: "test".to_string(),
+        priority: 3,
+        spec: serde_json::json!({"a": 1}),
+        max_attempts: Some(5),
+        ..AddTaskModel::default()
+    })
+    .await
+    .unwrap();
+
+    // task which is not terminated cannot be resubmitted
+    assert!(pgm
+        .resubmit_task("first".to_string(), "second".to_string(), ResubmitOverrides::default())
+        .await
+        .is_err());
+
+    let leased = pgm.lease_task("first".to_string(), "worker".to_string(), None).await.unwrap();
+    let error = TaskError {
+        code: "failed".to_string(),
+        args: serde_json::json!({}),
+        description: "failed".to_string(),
+    };
+    pgm.abort_task("first".to_string(), "worker".to_string(), leased.lease_epoch, error)
+        .await
+        .unwrap();
+
+    let overrides = ResubmitOverrides {
+        priority: Some(7),
+        ..ResubmitOverrides::default()
+    };
+    let second = pgm.resubmit_task("first".to_string(), "second".to_string(), overrides).await.unwrap();
+    assert_eq!(second.status, TaskStatus::Ready);
+    assert_eq!(second.queue, "test".to_string());
+    assert_eq!(second.spec, serde_json::json!({"a": 1}));
+    assert_eq!(second.priority, 7);
+    assert_eq!(second.max_attempts, Some(5));
+    assert_eq!(second.previous_rn, Some("first".to_string()));
+    let first = pgm.fetch_task("first".to_string()).await.unwrap();
+    assert_eq!(first.status, TaskStatus::Aborted);
+    assert_eq!(first.resubmitted_as, Some("second".to_string()));
+
+    // task is resubmitted only once
+    assert!(pgm
+        .resubmit_task("first".to_string(), "third".to_string(), ResubmitOverrides::default())
+        .await
+        .is_err());
+
+    // existing rn is not overwritten and task to resubmit is left unchanged
+    pgm.cancel_task("second".to_string(), "client".to_string(), None).await.unwrap();
+    let result = pgm.resubmit_task("second".to_string(), "first".to_string(), ResubmitOverrides::default()).await;
+    assert!(matches!(result, Err(PgAdapterError::DuplicateTask(_))));
+    assert_eq!(pgm.fetch_task("second".to_string()).await.unwrap().resubmitted_as, None);
+
+    // truncate table after use
+    common::truncate_table().await;
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use scylla_models::{Schedule, TaskStatus};
+
+#[tokio::test]
+#[ignore]
+async fn fire_due_schedules() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    let schedule = pgm
+        .upsert_schedule(Schedule {
+            name: "every_minute".to_string(),
+            cron: "* * * * *".to_string(),
+            queue: "test".to_string(),
+            priority: 10,
+            spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+            created: Utc::now() - Duration::minutes(5),
+            ..Schedule::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(pgm.fetch_schedules().await.unwrap(), vec![schedule.clone()]);
+
+    // only latest occurrence is fired
+    let tasks = pgm.fire_due_schedules().await.unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert!(tasks[0].rn.starts_with("every_minute:"));
+    assert_eq!(tasks[0].queue, "test".to_string());
+    assert_eq!(tasks[0].status, TaskStatus::Ready);
+    let fired_schedule = pgm.fetch_schedule("every_minute".to_string()).await.unwrap();
+    assert!(fired_schedule.last_fired.is_some());
+
+    // already fired
+    assert!(pgm.fire_due_schedules().await.unwrap().is_empty());
+
+    // update keeps created and last fired time
+    let updated_schedule = pgm.upsert_schedule(Schedule { priority: 20, ..schedule }).await.unwrap();
+    assert_eq!(updated_schedule.priority, 20);
+    assert_eq!(updated_schedule.last_fired, fired_schedule.last_fired);
+    assert_eq!(updated_schedule.created, fired_schedule.created);
+
+    assert_eq!(pgm.delete_schedule("every_minute".to_string()).await.unwrap(), 1);
+    assert!(pgm.fetch_schedule("every_minute".to_string()).await.is_err());
+
+    // truncate table after use
+    common::truncate_table().await;
+}
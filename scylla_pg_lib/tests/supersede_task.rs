@@ -0,0 +1,90 @@
+mod common;
+
+use scylla_models::{AddTaskModel, TaskHistoryType, TaskStatus};
+use scylla_pg_lib::error::PgAdapterError;
+
+fn add_task_model(rn: &str, priority: i8, spec: &str) -> AddTaskModel {
+    AddTaskModel {
+        priority,
+        spec: serde_json::from_str(spec).unwrap(),
+        supersession_key: Some("account:42".to_string()),
+        ..common::add_task_model(rn, "test")
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn supersede_replaces_ready_task() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    let first = pgm.supersede_task(add_task_model("first", 1, "{\"a\":1}"), false).await.unwrap();
+    assert_eq!(first.rn, "first".to_string());
+    assert_eq!(first.status, TaskStatus::Ready);
+
+    // ready task is replaced instead of inserting a new one
+    let replaced = pgm.supersede_task(add_task_model("second", 5, "{\"a\":2}"), false).await.unwrap();
+    assert_eq!(replaced.rn, "first".to_string());
+    assert_eq!(replaced.priority, 5);
+    assert_eq!(replaced.spec, serde_json::json!({"a": 2}));
+    assert_eq!(replaced.version, first.version + 1);
+    let history: Vec<_> = replaced.history.iter().map(|h| (h.typ.clone(), h.actor.clone(), h.reason.clone())).collect();
+    let entry = |typ| (typ, Some("supersession".to_string()), Some("superseded".to_string()));
+    assert_eq!(history, vec![entry(TaskHistoryType::SpecAmendment), entry(TaskHistoryType::Reprioritisation)]);
+    assert!(matches!(pgm.fetch_task("second".to_string()).await, Err(PgAdapterError::NoTaskFound(_))));
+
+    // running task is left alone unless asked to request its cancellation
+    pgm.lease_task("first".to_string(), "worker".to_string(), None).await.unwrap();
+    let second = pgm.supersede_task(add_task_model("second", 1, "{\"a\":3}"), false).await.unwrap();
+    assert_eq!(second.rn, "second".to_string());
+    assert_eq!(pgm.fetch_task("first".to_string()).await.unwrap().status, TaskStatus::Running);
+
+    pgm.lease_task("second".to_string(), "worker".to_string(), None).await.unwrap();
+    let third = pgm.supersede_task(add_task_model("third", 1, "{\"a\":4}"), true).await.unwrap();
+    assert_eq!(third.rn, "third".to_string());
+    for rn in ["first", "second"] {
+        let cancelling = pgm.fetch_task(rn.to_string()).await.unwrap();
+        assert_eq!(cancelling.status, TaskStatus::Cancelling);
+        assert_eq!(cancelling.cancel_requested.unwrap().reason, Some("superseded".to_string()));
+        assert_eq!(cancelling.errors[0].code, "superseded".to_string());
+        assert_eq!(cancelling.errors[0].args, serde_json::json!({"rn": "third"}));
+        let history = cancelling.history.last().unwrap();
+        assert_eq!(history.typ, TaskHistoryType::CancellationRequest);
+        assert_eq!(history.worker, "worker".to_string());
+        assert_eq!(history.actor, Some("supersession".to_string()));
+        assert_eq!(history.reason, Some("superseded".to_string()));
+    }
+
+    // supersession is scoped to the queue
+    let other_queue = pgm
+        .supersede_task(
+            AddTaskModel {
+                queue: "other".to_string(),
+                ..add_task_model("other", 1, "{\"a\":5}")
+            },
+            true,
+        )
+        .await
+        .unwrap();
+    assert_eq!(other_queue.rn, "other".to_string());
+    assert_eq!(pgm.fetch_task("third".to_string()).await.unwrap().status, TaskStatus::Ready);
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn supersede_does_not_cancel_when_task_is_duplicate() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.supersede_task(add_task_model("first", 1, "{\"a\":1}"), false).await.unwrap();
+    pgm.lease_task("first".to_string(), "worker".to_string(), None).await.unwrap();
+    let result = pgm.supersede_task(add_task_model("first", 1, "{\"a\":2}"), true).await;
+    assert!(matches!(result, Err(PgAdapterError::DuplicateTask(_))));
+    assert_eq!(pgm.fetch_task("first".to_string()).await.unwrap().status, TaskStatus::Running);
+
+    // truncate table after use
+    common::truncate_table().await;
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use scylla_models::{AddTaskModel, TaskError, TaskHistoryType, TaskStatus};
+
+#[tokio::test]
+#[ignore]
+async fn status_updates_are_recorded_in_history() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    for rn in ["completed", "aborted", "cancelled"] {
+        pgm.insert_task(common::add_task_model(rn, "test")).await.unwrap();
+    }
+    let completed = pgm.lease_task("completed".to_string(), "worker".to_string(), None).await.unwrap();
+    let completed = pgm
+        .complete_task("completed".to_string(), "worker".to_string(), completed.lease_epoch, None, None)
+        .await
+        .unwrap();
+    let last = completed.history.last().unwrap();
+    assert_eq!(last.typ, TaskHistoryType::Completion);
+    assert_eq!(last.worker, "worker".to_string());
+    assert_eq!(last.actor, Some("worker".to_string()));
+    assert_eq!(last.time, completed.updated);
+
+    let aborted = pgm.lease_task("aborted".to_string(), "worker".to_string(), None).await.unwrap();
+    let error = TaskError {
+        code: "invalid_account".to_string(),
+        args: serde_json::json!({}),
+        description: "account does not exist".to_string(),
+    };
+    let aborted = pgm
+        .abort_task("aborted".to_string(), "worker".to_string(), aborted.lease_epoch, error)
+        .await
+        .unwrap();
+    assert_eq!(aborted.history.last().unwrap().typ, TaskHistoryType::Abortion);
+
+    let cancelled = pgm
+        .cancel_task("cancelled".to_string(), "client".to_string(), Some("no longer needed".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(cancelled.history.len(), 1);
+    assert_eq!(cancelled.history[0].typ, TaskHistoryType::Cancellation);
+    assert_eq!(cancelled.history[0].worker, String::new());
+    assert_eq!(cancelled.history[0].actor, Some("client".to_string()));
+    assert_eq!(cancelled.history[0].reason, Some("no longer needed".to_string()));
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn monitor_transitions_are_recorded_in_history() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.insert_task(AddTaskModel {
+        max_attempts: Some(1),
+        ..common::add_task_model("exhausted", "test")
+    })
+    .await
+    .unwrap();
+    pgm.insert_task(AddTaskModel {
+        expires_at: Some(Utc::now() + Duration::seconds(1)),
+        ..common::add_task_model("expiring", "test")
+    })
+    .await
+    .unwrap();
+    pgm.insert_task(AddTaskModel {
+        expires_at: Some(Utc::now() - Duration::seconds(1)),
+        ..common::add_task_model("expired", "test")
+    })
+    .await
+    .unwrap();
+    pgm.insert_task(common::add_task_model("parent", "test")).await.unwrap();
+    pgm.insert_task(AddTaskModel {
+        depends_on: vec!["parent".to_string()],
+        ..common::add_task_model("child", "test")
+    })
+    .await
+    .unwrap();
+
+    // timed out on its last attempt or past its expiry
+    pgm.lease_task("exhausted".to_string(), "worker".to_string(), Some(-1)).await.unwrap();
+    pgm.lease_task("expiring".to_string(), "worker".to_string(), Some(-1)).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    pgm.reset_batch().await.unwrap();
+    let exhausted = pgm.fetch_task("exhausted".to_string()).await.unwrap();
+    assert_eq!(exhausted.status, TaskStatus::Aborted);
+    let types: Vec<TaskHistoryType> = exhausted.history.iter().map(|h| h.typ.clone()).collect();
+    assert_eq!(types, vec![TaskHistoryType::Assignment, TaskHistoryType::Timeout, TaskHistoryType::Abortion]);
+    assert_eq!(exhausted.history[2].worker, "worker".to_string());
+    assert_eq!(exhausted.history[2].actor, Some("monitor".to_string()));
+    assert_eq!(exhausted.history[2].reason, Some("max_attempts_exceeded".to_string()));
+    let expiring = pgm.fetch_task("expiring".to_string()).await.unwrap();
+    assert_eq!(expiring.status, TaskStatus::Expired);
+    assert_eq!(expiring.history.last().unwrap().typ, TaskHistoryType::Expiry);
+
+    // expired before it was leased
+    pgm.expire_batch().await.unwrap();
+    let expired = pgm.fetch_task("expired".to_string()).await.unwrap();
+    assert_eq!(expired.history.len(), 1);
+    assert_eq!(expired.history[0].typ, TaskHistoryType::Expiry);
+    assert_eq!(expired.history[0].actor, Some("monitor".to_string()));
+    assert_eq!(expired.history[0].reason, Some("expired".to_string()));
+
+    // aborted as its dependency failed
+    pgm.cancel_task("parent".to_string(), "client".to_string(), None).await.unwrap();
+    pgm.resolve_blocked_batch().await.unwrap();
+    let child = pgm.fetch_task("child".to_string()).await.unwrap();
+    assert_eq!(child.status, TaskStatus::Aborted);
+    assert_eq!(child.history.len(), 1);
+    assert_eq!(child.history[0].typ, TaskHistoryType::Abortion);
+    assert_eq!(child.history[0].reason, Some("dependency_failed".to_string()));
+
+    // truncate table after use
+    common::truncate_table().await;
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use scylla_models::{AddTaskModel, TaskStatus};
+
+#[tokio::test]
+#[ignore]
+async fn complete_task_with_result() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    pgm.insert_task(AddTaskModel {
+        rn: "with_result".to_string(),
+        queue: "test".to_string(),
+        spec: serde_json::json!({"a": 1}),
+        ..AddTaskModel::default()
+    })
+    .await
+    .unwrap();
+    let leased = pgm.lease_task("with_result".to_string(), "worker".to_string(), None).await.unwrap();
+
+    // invalid result is rejected instead of being dropped
+    assert!(pgm
+        .complete_task("with_result".to_string(), "worker".to_string(), leased.lease_epoch, None, Some("{".to_string()))
+        .await
+        .is_err());
+    assert_eq!(pgm.fetch_task("with_result".to_string()).await.unwrap().status, TaskStatus::Running);
+
+    let completed = pgm
+        .complete_task(
+            "with_result".to_string(),
+            "worker".to_string(),
+            leased.lease_epoch,
+            Some("{\"duration\": 3}".to_string()),
+            Some("{\"rows\": 3}".to_string()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(completed.status, TaskStatus::Completed);
+    assert_eq!(completed.result, Some(serde_json::json!({"rows": 3})));
+    assert_eq!(completed.metrics, Some(serde_json::json!({"duration": 3})));
+    let fetched = pgm.fetch_task("with_result".to_string()).await.unwrap();
+    assert_eq!(fetched.result, Some(serde_json::json!({"rows": 3})));
+
+    // truncate table after use
+    common::truncate_table().await;
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use scylla_models::{AddTaskModel, HeartbeatOutcome, TaskStatus};
+
+#[tokio::test]
+#[ignore]
+async fn every_update_increments_version() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    let atm = AddTaskModel {
+        rn: "versioned".to_string(),
+        queue: "test".to_string(),
+        priority: 1,
+        spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
+    };
+    assert_eq!(pgm.insert_task(atm).await.unwrap().version, 0);
+    let leased_task = pgm.lease_task("versioned".to_string(), "worker".to_string(), None).await.unwrap();
+    assert_eq!(leased_task.version, 1);
+    let HeartbeatOutcome::Extended { task } = pgm
+        .heartbeat_task("versioned".to_string(), "worker".to_string(), 1, Some(50.0), None)
+        .await
+        .unwrap()
+    else {
+        panic!("heartbeat is expected to extend the lease");
+    };
+    assert_eq!(task.version, 2);
+    let task = pgm.complete_task("versioned".to_string(), "worker".to_string(), 1, None, None).await.unwrap();
+    assert_eq!(task.version, 3);
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn concurrent_leases_of_same_task_lease_it_once() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    let atm = AddTaskModel {
+        rn: "contended".to_string(),
+        queue: "test".to_string(),
+        priority: 1,
+        spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+        ..AddTaskModel::default()
+    };
+    pgm.insert_task(atm).await.unwrap();
+    let (lease1, lease2) = tokio::join!(
+        pgm.lease_task("contended".to_string(), "worker1".to_string(), None),
+        pgm.lease_task("contended".to_string(), "worker2".to_string(), None)
+    );
+    assert!(lease1.is_ok() != lease2.is_ok());
+    let task = pgm.fetch_task("contended".to_string()).await.unwrap();
+    assert_eq!(task.status, TaskStatus::Running);
+    assert_eq!(task.history.len(), 1);
+    assert_eq!(task.version, 1);
+    // truncate table after use
+    common::truncate_table().await;
+}
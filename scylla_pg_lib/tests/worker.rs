@@ -0,0 +1,148 @@
+mod common;
+
+use scylla_models::{AddTaskModel, HeartbeatOutcome, TaskHeartbeat, TaskStatus, WorkerMetadata};
+use scylla_pg_lib::error::PgAdapterError;
+
+fn metadata() -> WorkerMetadata {
+    WorkerMetadata {
+        hostname: "node-1".to_string(),
+        version: "1.4.0".to_string(),
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn worker_lifecycle() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    let first = pgm.register_worker(metadata()).await.unwrap();
+    let second = pgm.register_worker(metadata()).await.unwrap();
+    assert!(first.id.starts_with("worker-"));
+    assert_ne!(first.id, second.id);
+    assert_eq!(first.hostname, "node-1".to_string());
+    assert_eq!(first.version, "1.4.0".to_string());
+
+    let heartbeat = pgm.heartbeat_worker(first.id.clone()).await.unwrap();
+    assert!(heartbeat.heartbeat > first.heartbeat);
+    assert_eq!(heartbeat.registered, first.registered);
+    assert_eq!(pgm.fetch_worker(first.id.clone()).await.unwrap(), heartbeat);
+    assert_eq!(pgm.fetch_workers().await.unwrap().len(), 2);
+
+    pgm.deregister_worker(first.id.clone()).await.unwrap();
+    assert!(matches!(
+        pgm.heartbeat_worker(first.id.clone()).await,
+        Err(PgAdapterError::NoWorkerFound(id)) if id == first.id
+    ));
+    assert!(matches!(
+        pgm.deregister_worker(first.id.clone()).await,
+        Err(PgAdapterError::NoWorkerFound(id)) if id == first.id
+    ));
+    // ids are not issued again
+    assert_ne!(pgm.register_worker(metadata()).await.unwrap().id, first.id);
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn only_registered_workers_lease_tasks() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await.require_registered_workers(true);
+    for rn in ["1", "2"] {
+        pgm.insert_task(AddTaskModel {
+            rn: rn.to_string(),
+            queue: "registered".to_string(),
+            priority: 10,
+            spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+            ..AddTaskModel::default()
+        })
+        .await
+        .unwrap();
+    }
+    assert!(matches!(
+        pgm.lease_task("1".to_string(), "unknown".to_string(), None).await,
+        Err(PgAdapterError::NoWorkerFound(id)) if id == "unknown"
+    ));
+    assert!(matches!(
+        pgm.lease_n_tasks("registered".to_string(), 1, "unknown".to_string(), None, Vec::new()).await,
+        Err(PgAdapterError::NoWorkerFound(id)) if id == "unknown"
+    ));
+
+    let worker = pgm.register_worker(metadata()).await.unwrap();
+    let leased = pgm.lease_task("1".to_string(), worker.id.clone(), None).await.unwrap();
+    assert_eq!(leased.owner, Some(worker.id.clone()));
+    let leased = pgm
+        .lease_n_tasks("registered".to_string(), 1, worker.id.clone(), None, Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(leased[0].owner, Some(worker.id));
+
+    // truncate table after use
+    common::truncate_table().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn drained_worker_yields_its_tasks() {
+    // truncate table before use
+    common::truncate_table().await;
+    let pgm = common::get_pg_manager().await;
+    for rn in ["1", "2"] {
+        pgm.insert_task(AddTaskModel {
+            rn: rn.to_string(),
+            queue: "drained".to_string(),
+            priority: 10,
+            spec: serde_json::from_str("{\"a\":\"b\"}").unwrap(),
+            ..AddTaskModel::default()
+        })
+        .await
+        .unwrap();
+    }
+    let worker = pgm.register_worker(metadata()).await.unwrap();
+    assert!(!worker.draining);
+    let leased = pgm.lease_task("1".to_string(), worker.id.clone(), Some(600)).await.unwrap();
+
+    assert!(pgm.drain_worker(worker.id.clone()).await.unwrap().draining);
+    assert!(pgm.fetch_worker(worker.id.clone()).await.unwrap().draining);
+    assert!(pgm
+        .lease_n_tasks("drained".to_string(), 10, worker.id.clone(), None, Vec::new())
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(matches!(
+        pgm.lease_task("2".to_string(), worker.id.clone(), None).await,
+        Err(PgAdapterError::WorkerDraining(id)) if id == worker.id
+    ));
+
+    // heartbeats request the worker to yield, which it does through the usual yield
+    let HeartbeatOutcome::YieldRequested { task } = pgm
+        .heartbeat_task("1".to_string(), worker.id.clone(), leased.lease_epoch, None, None)
+        .await
+        .unwrap()
+    else {
+        panic!("heartbeat is expected to request the drained worker to yield");
+    };
+    assert_eq!(task.status, TaskStatus::Running);
+    let heartbeats = vec![TaskHeartbeat {
+        rn: "1".to_string(),
+        lease_epoch: leased.lease_epoch,
+        progress: None,
+    }];
+    let outcomes = pgm.heartbeat_tasks(worker.id.clone(), heartbeats, None).await.unwrap();
+    assert!(matches!(outcomes[0].outcome, HeartbeatOutcome::YieldRequested { .. }));
+    assert_eq!(pgm.yield_worker_tasks(worker.id.clone()).await.unwrap().len(), 1);
+
+    pgm.resume_worker(worker.id.clone()).await.unwrap();
+    let leased = pgm.lease_n_tasks("drained".to_string(), 10, worker.id.clone(), None, Vec::new()).await.unwrap();
+    assert_eq!(leased[0].rn, "2".to_string());
+    assert!(matches!(
+        pgm.drain_worker("unknown".to_string()).await,
+        Err(PgAdapterError::NoWorkerFound(id)) if id == "unknown"
+    ));
+
+    // truncate table after use
+    common::truncate_table().await;
+}
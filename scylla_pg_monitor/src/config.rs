@@ -1,9 +1,11 @@
-use super::env_var;
+use super::{env_var, env_var_with_defaults};
 
 #[derive(Debug, Clone, Default)]
 pub struct PGMonitorConfig {
     pub poll_interval: u64,
     pub task_retention_time: i64,
+    /// Time a worker has to acknowledge a requested cancellation, before the task is cancelled by the monitor.
+    pub cancellation_grace_period: i64,
 }
 
 impl PGMonitorConfig {
@@ -15,6 +17,7 @@ impl PGMonitorConfig {
             task_retention_time: env_var!("MONITOR_TASK_RETENTION_PERIOD_IN_SECS")
                 .parse()
                 .expect("i64 expected for MONITOR_TASK_RETENTION_PERIOD_IN_SECS"),
+            cancellation_grace_period: env_var_with_defaults!("MONITOR_CANCELLATION_GRACE_PERIOD_IN_SECS", i64, 60),
         }
     }
 }
@@ -49,6 +52,10 @@ mod tests {
         let config = PGMonitorConfig::from_env();
         assert_eq!(config.poll_interval, 10);
         assert_eq!(config.task_retention_time, 8600);
+        assert_eq!(config.cancellation_grace_period, 60);
+        set_env_var("MONITOR_CANCELLATION_GRACE_PERIOD_IN_SECS", "300");
+        assert_eq!(PGMonitorConfig::from_env().cancellation_grace_period, 300);
+        unset_env_var("MONITOR_CANCELLATION_GRACE_PERIOD_IN_SECS");
         get_monitor_env_variables().iter().for_each(|(k, _)| {
             unset_env_var(k);
         });
@@ -4,6 +4,7 @@ mod config;
 pub mod env;
 
 use crate::config::PGMonitorConfig;
+use scylla_models::TaskStatus;
 use scylla_pg_core::config::PGConfig;
 use scylla_pg_lib::manager::PgManager;
 use std::time::Duration;
@@ -16,6 +17,10 @@ pub async fn monitor_tasks() {
     loop {
         tokio::time::sleep(Duration::from_secs(pg_monitor_config.poll_interval)).await;
         reset_tasks(&pgm).await;
+        expire_tasks(&pgm).await;
+        cancel_tasks(&pgm, pg_monitor_config.cancellation_grace_period).await;
+        resolve_blocked_tasks(&pgm).await;
+        fire_due_schedules(&pgm).await;
         match pgm.delete_terminated_tasks(pg_monitor_config.task_retention_time).await {
             Ok(count) => log::info!("tasks deleted: {count}"),
             Err(e) => log::error!("error occurred while deleting terminated tasks {e}"),
@@ -27,9 +32,57 @@ async fn reset_tasks(pgm: &PgManager) {
     match pgm.reset_batch().await {
         Ok(tasks) => {
             for task in tasks.iter() {
-                log::debug!("task with {} has been reset to ready state ", task.rn);
+                match task.status {
+                    TaskStatus::Aborted => log::warn!("task with {} has been aborted after {} attempts ", task.rn, task.attempts),
+                    TaskStatus::Expired => log::debug!("task with {} has expired ", task.rn),
+                    _ => log::debug!("task with {} has been reset to ready state ", task.rn),
+                }
             }
         }
         Err(e) => log::error!("error while resetting batch, {e:?}"),
     }
 }
+
+async fn expire_tasks(pgm: &PgManager) {
+    match pgm.expire_batch().await {
+        Ok(tasks) => {
+            for task in tasks.iter() {
+                log::debug!("task with {} has expired ", task.rn);
+            }
+        }
+        Err(e) => log::error!("error while expiring batch, {e:?}"),
+    }
+}
+
+async fn cancel_tasks(pgm: &PgManager, grace_period_in_secs: i64) {
+    match pgm.cancel_batch(grace_period_in_secs).await {
+        Ok(tasks) => {
+            for task in tasks.iter() {
+                log::warn!("task with {} has been cancelled without acknowledgement of its worker ", task.rn);
+            }
+        }
+        Err(e) => log::error!("error while cancelling batch, {e:?}"),
+    }
+}
+
+async fn resolve_blocked_tasks(pgm: &PgManager) {
+    match pgm.resolve_blocked_batch().await {
+        Ok(tasks) => {
+            for task in tasks.iter() {
+                log::debug!("blocked task with {} has been moved to {} state ", task.rn, task.status);
+            }
+        }
+        Err(e) => log::error!("error while resolving blocked batch, {e:?}"),
+    }
+}
+
+async fn fire_due_schedules(pgm: &PgManager) {
+    match pgm.fire_due_schedules().await {
+        Ok(tasks) => {
+            for task in tasks.iter() {
+                log::debug!("task with {} has been added for schedule ", task.rn);
+            }
+        }
+        Err(e) => log::error!("error while firing due schedules, {e:?}"),
+    }
+}